@@ -0,0 +1,75 @@
+use std::fs;
+
+use clap::Args;
+use formatter::{format, FormatOptions};
+
+use super::render;
+
+#[derive(Args)]
+pub struct FmtArgs {
+	/// Paths to .typm files
+	#[arg(required = true)]
+	pub files: Vec<String>,
+
+	/// Only report files which would be reformatted, without writing them
+	#[arg(long)]
+	pub check: bool,
+
+	/// Spaces per indentation level
+	#[arg(long, default_value_t = 4)]
+	pub indent_width: usize,
+}
+
+pub fn run(args: FmtArgs) -> Result<(), String> {
+	let options = FormatOptions {
+		indent_width: args.indent_width,
+	};
+
+	let mut errors = Vec::new();
+	let mut unformatted = Vec::new();
+
+	for path in args.files.iter() {
+		let source = match fs::read_to_string(path) {
+			Ok(source) => source,
+			Err(error) => {
+				errors.push(format!("error: could not read {}: {}\n", path, error));
+				continue;
+			}
+		};
+
+		let formatted = match format(&source, &options) {
+			Ok(formatted) => formatted,
+			Err(diagnostics) => {
+				errors.push(render(&diagnostics, &source, path));
+				continue;
+			}
+		};
+
+		if formatted == source {
+			continue;
+		};
+
+		if args.check {
+			unformatted.push(path.clone());
+		} else if let Err(error) = fs::write(path, formatted) {
+			errors.push(format!("error: could not write {}: {}\n", path, error));
+		};
+	}
+
+	for path in unformatted.iter() {
+		println!("Would reformat {}", path);
+	}
+
+	if !unformatted.is_empty() {
+		errors.push(format!(
+			"error: {} file(s) would be reformatted\n",
+			unformatted.len()
+		));
+	};
+
+	if errors.is_empty() {
+		Ok(())
+	} else {
+		Err(errors.join("\n"))
+	}
+}
@@ -0,0 +1,96 @@
+use std::{fs, path::Path};
+
+use clap::Args;
+use codegen::{generators, get_generator, lock::Lock};
+
+use super::load_schema;
+
+#[derive(Args)]
+pub struct GenerateArgs {
+	/// Path to the .typm file
+	pub file: String,
+
+	/// Output target (typescript, ...)
+	#[arg(short, long)]
+	pub target: String,
+
+	/// Where to write generated code, printed to stdout if omitted
+	#[arg(short, long)]
+	pub output: Option<String>,
+
+	/// Lock file with assigned numbers (proto target), `<file>.lock` by default
+	#[arg(long)]
+	pub lock: Option<String>,
+
+	/// Namespace of generated code (csharp target)
+	#[arg(long)]
+	pub namespace: Option<String>,
+}
+
+pub fn run(args: GenerateArgs) -> Result<(), String> {
+	let generator = match get_generator(&args.target) {
+		Some(generator) => generator,
+		None => {
+			let names: Vec<&str> = generators().iter().map(|generator| generator.name()).collect();
+
+			return Err(format!(
+				"error: unknown target `{}`, expected one of: {}\n",
+				args.target,
+				names.join(", ")
+			));
+		}
+	};
+
+	let generator = match &args.namespace {
+		Some(namespace) => match generator.with_namespace(namespace) {
+			Some(generator) => generator,
+			None => {
+				return Err(format!(
+					"error: `{}` target doesn't support namespaces\n",
+					args.target
+				))
+			}
+		},
+		None => generator,
+	};
+
+	let schema = load_schema(&args.file, generator.as_ref())?;
+
+	let code = if generator.uses_lock() {
+		let path = args.lock.unwrap_or_else(|| format!("{}.lock", args.file));
+		let mut lock = read_lock(&path)?;
+		let previous = lock.clone();
+
+		let code = generator.generate_locked(&schema, &mut lock);
+
+		if lock != previous {
+			fs::write(&path, lock.to_json())
+				.map_err(|error| format!("error: could not write {}: {}\n", path, error))?;
+		};
+
+		code
+	} else {
+		generator.generate(&schema)
+	};
+
+	match args.output {
+		Some(output) => fs::write(&output, code)
+			.map_err(|error| format!("error: could not write {}: {}\n", output, error)),
+		None => {
+			print!("{}", code);
+			Ok(())
+		}
+	}
+}
+
+// Missing lock file is the same as an empty one
+fn read_lock(path: &str) -> Result<Lock, String> {
+	if !Path::new(path).exists() {
+		return Ok(Lock::default());
+	};
+
+	let source = fs::read_to_string(path)
+		.map_err(|error| format!("error: could not read {}: {}\n", path, error))?;
+
+	Lock::parse(&source).map_err(|error| format!("error: invalid lock file {}: {}\n", path, error))
+}
@@ -0,0 +1,41 @@
+use std::fs;
+
+use codegen::Generator;
+use parser::errors::Diagnostic;
+use semantic::{analyze, ir::Schema, validate};
+
+pub mod fmt;
+pub mod generate;
+pub mod rename;
+
+//
+// Reads, analyzes and validates .typm file for the given target
+//
+// Warnings are printed right away, errors are rendered into the
+// returned message.
+pub fn load_schema(path: &str, generator: &dyn Generator) -> Result<Schema, String> {
+	let source = match fs::read_to_string(path) {
+		Ok(source) => source,
+		Err(error) => return Err(format!("error: could not read {}: {}\n", path, error)),
+	};
+
+	let schema = analyze(&source).map_err(|diagnostics| render(&diagnostics, &source, path))?;
+	let mut diagnostics = validate(&schema, &[generator.reserved_words()]);
+	diagnostics.extend(generator.check(&schema));
+
+	if diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+		return Err(render(&diagnostics, &source, path));
+	};
+
+	eprint!("{}", render(&diagnostics, &source, path));
+
+	Ok(schema)
+}
+
+pub fn render(diagnostics: &[Diagnostic], source: &str, path: &str) -> String {
+	diagnostics
+		.iter()
+		.map(|diagnostic| diagnostic.render(source, path))
+		.collect::<Vec<String>>()
+		.join("\n")
+}
@@ -0,0 +1,54 @@
+use std::fs;
+
+use clap::Args;
+use semantic::workspace::{apply_edits, Workspace};
+
+#[derive(Args)]
+pub struct RenameArgs {
+	/// Qualified name of a declaration, variable or enum variant (`User.Status`)
+	pub from: String,
+
+	/// New name, without the path (`AccountStatus`)
+	pub to: String,
+
+	/// Paths to .typm files
+	#[arg(required = true)]
+	pub files: Vec<String>,
+}
+
+pub fn run(args: RenameArgs) -> Result<(), String> {
+	let mut workspace = Workspace::default();
+
+	for path in args.files.iter() {
+		let source = fs::read_to_string(path)
+			.map_err(|error| format!("error: could not read {}: {}\n", path, error))?;
+
+		workspace.insert(path.clone(), source);
+	}
+
+	let edits = workspace
+		.rename(&args.from, &args.to)
+		.map_err(|message| format!("error: {}\n", message))?;
+
+	// Every file is checked before anything is written
+	let mut occurrences = 0;
+
+	for (path, file_edits) in edits.iter() {
+		let source = &workspace.get(path).unwrap().source;
+
+		fs::write(path, apply_edits(source, file_edits))
+			.map_err(|error| format!("error: could not write {}: {}\n", path, error))?;
+
+		occurrences += file_edits.len();
+	}
+
+	println!(
+		"Renamed `{}` to `{}`: {} occurrence(s) in {} file(s)",
+		args.from,
+		args.to,
+		occurrences,
+		edits.len()
+	);
+
+	Ok(())
+}
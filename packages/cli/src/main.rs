@@ -1,22 +1,40 @@
-use lexer::get_tokens;
-use parser::get_ast_tree;
+use std::process::ExitCode;
 
-fn main() {
-	let source = "
-    interface Test {
-		optional const_variable: String { \"Multi-line string!\" };
-		enum TestEnum {
-			Test: Test2;
-			TestMulti: \"Multi-line enum!!!\";
-		};
-	};
+use clap::{Parser, Subcommand};
+
+mod commands;
+
+#[derive(Parser)]
+#[command(name = "typm", about = "Cross-language types generator", version)]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+}
 
-	interface Test_3 {};
-    ";
-	let tokens = get_tokens(source);
-	let tree = get_ast_tree(tokens);
+#[derive(Subcommand)]
+enum Command {
+	/// Generates code for the given target from a .typm file
+	Generate(commands::generate::GenerateArgs),
+	/// Formats .typm files in place
+	Fmt(commands::fmt::FmtArgs),
+	/// Renames a declaration, variable or enum variant and updates every reference to it
+	Rename(commands::rename::RenameArgs),
+}
+
+fn main() -> ExitCode {
+	let cli = Cli::parse();
+
+	let result = match cli.command {
+		Command::Generate(args) => commands::generate::run(args),
+		Command::Fmt(args) => commands::fmt::run(args),
+		Command::Rename(args) => commands::rename::run(args),
+	};
 
-	for node in tree.nodes.iter() {
-		println!("Tree node: {:#?}", node);
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(error) => {
+			eprint!("{}", error);
+			ExitCode::FAILURE
+		}
 	}
 }
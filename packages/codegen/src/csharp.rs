@@ -0,0 +1,604 @@
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Declaration, DeclarationKind, Field, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	helpers::{check_flat_names, flat_name, to_pascal_case},
+	quote,
+	writer::Writer,
+	Generator,
+};
+
+//
+// C# generator
+//
+// Interfaces become records with `init`-only properties, nested interfaces
+// become nested records. Every generated name is PascalCase, like the rest
+// of .NET (`interface r#enum` becomes `record Enum`), which also keeps it
+// from being a keyword. Enums are serialized by name with
+// `JsonStringEnumMemberName` (System.Text.Json 9). C# has no type aliases,
+// so aliases are replaced with their types.
+//
+// Enums and unions are placed at the namespace level with flattened names
+// (`UserStatus`), because a nested type can't share it's name with a
+// property, and `required status: User.Status` is a common pattern.
+// Unions become interfaces implemented by their members, deserializing
+// them needs a custom converter.
+pub struct CSharpGenerator {
+	pub namespace: String,
+}
+
+const DEFAULT_NAMESPACE: &str = "Schema";
+
+const KEYWORDS: &[&str] = &[
+	"abstract",
+	"as",
+	"base",
+	"bool",
+	"break",
+	"byte",
+	"case",
+	"catch",
+	"char",
+	"checked",
+	"class",
+	"const",
+	"continue",
+	"decimal",
+	"default",
+	"delegate",
+	"do",
+	"double",
+	"else",
+	"enum",
+	"event",
+	"explicit",
+	"extern",
+	"false",
+	"finally",
+	"fixed",
+	"float",
+	"for",
+	"foreach",
+	"goto",
+	"if",
+	"implicit",
+	"in",
+	"int",
+	"interface",
+	"internal",
+	"is",
+	"lock",
+	"long",
+	"namespace",
+	"new",
+	"null",
+	"object",
+	"operator",
+	"out",
+	"override",
+	"params",
+	"private",
+	"protected",
+	"public",
+	"readonly",
+	"record",
+	"ref",
+	"return",
+	"sbyte",
+	"sealed",
+	"short",
+	"sizeof",
+	"stackalloc",
+	"static",
+	"string",
+	"struct",
+	"switch",
+	"this",
+	"throw",
+	"true",
+	"try",
+	"typeof",
+	"uint",
+	"ulong",
+	"unchecked",
+	"unsafe",
+	"ushort",
+	"using",
+	"virtual",
+	"void",
+	"volatile",
+	"while",
+];
+
+impl Default for CSharpGenerator {
+	fn default() -> Self {
+		Self {
+			namespace: DEFAULT_NAMESPACE.to_string(),
+		}
+	}
+}
+
+impl Generator for CSharpGenerator {
+	fn name(&self) -> &'static str {
+		"csharp"
+	}
+
+	fn extension(&self) -> &'static str {
+		"cs"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		// Generated names are PascalCase (see `record_name`), so they can't be keywords
+		ReservedWords {
+			target: "C#",
+			declarations: &[],
+			members: &[],
+		}
+	}
+
+	fn with_namespace(&self, namespace: &str) -> Option<Box<dyn Generator>> {
+		Option::Some(Box::new(CSharpGenerator {
+			namespace: namespace.to_string(),
+		}))
+	}
+
+	// Types can't share a name within the namespace or a record, and members
+	// can't be named after their enclosing type or another member
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		let written = schema
+			.declarations()
+			.iter()
+			.filter(|declaration| !matches!(declaration.kind, DeclarationKind::Alias(_)));
+		let mut diagnostics = check_flat_names(written, "C#", type_reference);
+
+		for declaration in schema.declarations() {
+			let interface = match &declaration.kind {
+				DeclarationKind::Interface(interface) => interface,
+				_ => continue,
+			};
+
+			let nested: Vec<&Declaration> = schema
+				.children(declaration)
+				.filter(|child| matches!(child.kind, DeclarationKind::Interface(_)))
+				.collect();
+
+			for field in interface.own_fields() {
+				let name = property_name(&field.name);
+
+				if name == record_name(declaration) {
+					diagnostics.push(
+						Diagnostic::error(format!(
+							"variable `{}` becomes `{}` in C#, which is the name of it's interface",
+							field.name, name
+						))
+						.with_label(field.span.clone(), "rename this variable"),
+					);
+				} else if let Some(child) = nested.iter().find(|child| record_name(child) == name) {
+					diagnostics.push(
+						Diagnostic::error(format!(
+							"variable `{}` and nested interface `{}` are both named `{}` in C#",
+							field.name,
+							child.qualified_name(),
+							name
+						))
+						.with_label(field.span.clone(), "rename this variable"),
+					);
+				};
+			}
+		}
+
+		diagnostics
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("    ");
+
+		writer.line("#nullable enable");
+		writer.blank_line();
+		writer.line("using System.Collections.Generic;");
+		writer.line("using System.Text.Json.Serialization;");
+		writer.blank_line();
+		writer.line(format!("namespace {};", self.namespace));
+
+		for declaration in schema.declarations() {
+			let is_written = match declaration.kind {
+				// Nested records are written by their parent
+				DeclarationKind::Interface(_) => declaration.parent.is_none(),
+				DeclarationKind::Alias(_) => false,
+				_ => true,
+			};
+
+			if is_written {
+				writer.blank_line();
+				write_declaration(&mut writer, schema, declaration);
+			};
+		}
+
+		writer.finish()
+	}
+}
+
+fn write_declaration(writer: &mut Writer, schema: &Schema, declaration: &Declaration) {
+	write_docs(writer, &declaration.docs);
+
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			let mut supertypes = Vec::new();
+			if let Some(parent) = interface.extends {
+				supertypes.push(type_reference(schema.get(parent)));
+			};
+
+			// Unions this interface is a member of
+			for union in schema.declarations() {
+				if let DeclarationKind::Union(members) = &union.kind {
+					if members.members.contains(&declaration.id) {
+						supertypes.push(type_reference(union));
+					};
+				};
+			}
+
+			let mut header = format!("public record {}", record_name(declaration));
+			if !supertypes.is_empty() {
+				header.push_str(&format!(" : {}", supertypes.join(", ")));
+			};
+
+			let nested: Vec<&Declaration> = schema
+				.children(declaration)
+				.filter(|child| matches!(child.kind, DeclarationKind::Interface(_)))
+				.collect();
+
+			if interface.own_fields().count() == 0 && nested.is_empty() {
+				writer.line(format!("{};", header));
+				return;
+			};
+
+			writer.line(header);
+			writer.line("{");
+			writer.indent();
+
+			let mut is_first = true;
+			for field in interface.own_fields() {
+				if !is_first {
+					writer.blank_line();
+				};
+				is_first = false;
+
+				write_property(writer, schema, field);
+			}
+
+			for child in nested.into_iter() {
+				if !is_first {
+					writer.blank_line();
+				};
+				is_first = false;
+
+				write_declaration(writer, schema, child);
+			}
+
+			writer.dedent();
+			writer.line("}");
+		}
+		DeclarationKind::Enum(enumerate) => {
+			let name = flat_name(declaration);
+
+			writer.line(format!(
+				"[JsonConverter(typeof(JsonStringEnumConverter<{}>))]",
+				name
+			));
+			writer.line(format!("public enum {}", name));
+			writer.line("{");
+			writer.indent();
+
+			for variant in enumerate.variants.iter() {
+				write_docs(writer, &variant.docs);
+
+				let value = variant.value.as_ref().unwrap_or(&variant.name);
+				let member = to_pascal_case(&variant.name);
+
+				if &member != value {
+					writer.line(format!("[JsonStringEnumMemberName({})]", quote(value)));
+				};
+				writer.line(format!("{},", member));
+			}
+
+			writer.dedent();
+			writer.line("}");
+		}
+		DeclarationKind::Union(_) => {
+			writer.line(format!("public interface {}", flat_name(declaration)));
+			writer.line("{");
+			writer.line("}");
+		}
+		DeclarationKind::Alias(_) => {}
+	};
+}
+
+// Example:
+// [JsonPropertyName("user_name")]
+// public required string UserName { get; init; }
+fn write_property(writer: &mut Writer, schema: &Schema, field: &Field) {
+	write_docs(writer, &field.docs);
+
+	writer.line(format!("[JsonPropertyName({})]", quote(&field.name)));
+
+	let field_type = type_name(schema, &field.field_type);
+	let name = property_name(&field.name);
+
+	if field.is_required {
+		writer.line(format!(
+			"public required {} {} {{ get; init; }}",
+			field_type, name
+		));
+	} else {
+		writer.line("[JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]");
+		writer.line(format!("public {}? {} {{ get; init; }}", field_type, name));
+	};
+}
+
+pub fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match schema.resolve_type(field_type) {
+		Type::String(_) => "string".to_string(),
+		Type::Boolean => "bool".to_string(),
+		Type::Integer => "long".to_string(),
+		Type::List(item_type) => format!("List<{}>", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => format!(
+			"Dictionary<{}, {}>",
+			type_name(schema, key_type),
+			type_name(schema, value_type)
+		),
+		Type::Reference(id) => type_reference(schema.get(*id)),
+	}
+}
+
+// Records keep their nesting, everything else is flattened
+fn type_reference(declaration: &Declaration) -> String {
+	match declaration.kind {
+		DeclarationKind::Interface(_) => declaration
+			.path
+			.iter()
+			.map(|segment| to_pascal_case(segment))
+			.collect::<Vec<String>>()
+			.join("."),
+		_ => flat_name(declaration),
+	}
+}
+
+fn record_name(declaration: &Declaration) -> String {
+	to_pascal_case(&declaration.name)
+}
+
+fn property_name(name: &str) -> String {
+	let name = to_pascal_case(name);
+
+	if KEYWORDS.contains(&name.as_str()) {
+		format!("@{}", name)
+	} else {
+		name
+	}
+}
+
+fn write_docs(writer: &mut Writer, docs: &Option<String>) {
+	if let Some(docs) = docs {
+		let escaped = docs
+			.replace('&', "&amp;")
+			.replace('<', "&lt;")
+			.replace('>', "&gt;");
+
+		writer.line("/// <summary>");
+		writer.docs(&Option::Some(escaped), "/// ");
+		writer.line("/// </summary>");
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_records_and_enums() {
+		let schema = analyze(
+			"
+			/// Registered user
+			interface User {
+				required user_name: String;
+				optional tags: List<String>;
+				required status: User.Status;
+				optional home: User.Address;
+
+				enum Status {
+					Registered: registered;
+				};
+
+				interface Address {
+					required city: String;
+				};
+			};
+
+			interface Admin extends User {
+				optional level: Int;
+			};
+
+			union Account = Admin | User;
+			",
+		)
+		.unwrap();
+
+		let generator = CSharpGenerator {
+			namespace: "Company.Models".to_string(),
+		};
+
+		assert_eq!(
+			generator.generate(&schema),
+			r#"#nullable enable
+
+using System.Collections.Generic;
+using System.Text.Json.Serialization;
+
+namespace Company.Models;
+
+/// <summary>
+/// Registered user
+/// </summary>
+public record User : Account
+{
+    [JsonPropertyName("user_name")]
+    public required string UserName { get; init; }
+
+    [JsonPropertyName("tags")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public List<string>? Tags { get; init; }
+
+    [JsonPropertyName("status")]
+    public required UserStatus Status { get; init; }
+
+    [JsonPropertyName("home")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public User.Address? Home { get; init; }
+
+    public record Address
+    {
+        [JsonPropertyName("city")]
+        public required string City { get; init; }
+    }
+}
+
+[JsonConverter(typeof(JsonStringEnumConverter<UserStatus>))]
+public enum UserStatus
+{
+    [JsonStringEnumMemberName("registered")]
+    Registered,
+}
+
+public record Admin : User, Account
+{
+    [JsonPropertyName("level")]
+    [JsonIgnore(Condition = JsonIgnoreCondition.WhenWritingNull)]
+    public long? Level { get; init; }
+}
+
+public interface Account
+{
+}
+"#
+		);
+	}
+
+	#[test]
+	fn pascal_cases_record_names() {
+		let schema = analyze(
+			"
+			interface r#enum {
+				required id: String;
+
+				interface r#class {};
+			};
+
+			interface Other {
+				required value: r#enum.class;
+			};
+			",
+		)
+		.unwrap();
+
+		let output = CSharpGenerator::default().generate(&schema);
+
+		assert!(output.contains("public record Enum\n{\n"));
+		assert!(output.contains("    public record Class;\n"));
+		assert!(output.contains("    public required Enum.Class Value { get; init; }\n"));
+	}
+
+	#[test]
+	fn reports_name_collisions() {
+		let schema = analyze(
+			"
+			interface User {
+				required status: User.Status;
+
+				enum Status {
+					A: a;
+				};
+			};
+
+			interface UserStatus {
+				required user_status: String;
+			};
+			",
+		)
+		.unwrap();
+
+		let messages: Vec<String> = CSharpGenerator::default()
+			.check(&schema)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec![
+				"`User.Status` and `UserStatus` are both named `UserStatus` in C#",
+				"variable `user_status` becomes `UserStatus` in C#, which is the name of it's interface",
+			]
+		);
+	}
+
+	#[test]
+	fn pascal_cases_keyword_members() {
+		let schema = analyze(
+			"
+			interface Item {
+				required class: String;
+				optional params: Item.Kind;
+
+				enum Kind {
+					default: default;
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		let output = CSharpGenerator::default().generate(&schema);
+
+		assert!(output.contains("    public required string Class { get; init; }\n"));
+		assert!(output.contains("    public ItemKind? Params { get; init; }\n"));
+		assert!(output.contains("    Default,\n"));
+	}
+
+	#[test]
+	fn resolves_aliases_to_unions_and_int_keyed_maps() {
+		let schema = analyze(
+			"
+			interface User {
+				required status: User.Status;
+				required scores: Map<Int, Int>;
+				optional default: Contact;
+
+				enum Status {
+					Active: active;
+				};
+			};
+
+			union Account = User;
+
+			alias Contact = Account;
+			",
+		)
+		.unwrap();
+
+		let output = CSharpGenerator::default().generate(&schema);
+
+		assert!(output.contains("public record User : Account\n"));
+		assert!(output.contains("    public required UserStatus Status { get; init; }\n"));
+		assert!(
+			output.contains("    public required Dictionary<long, long> Scores { get; init; }\n")
+		);
+		// C# has no type aliases, so the union is used directly
+		assert!(output.contains("    public Account? Default { get; init; }\n"));
+		assert!(output.contains("public enum UserStatus\n"));
+	}
+}
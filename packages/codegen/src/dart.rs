@@ -0,0 +1,529 @@
+use std::collections::HashSet;
+
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Constraint, Declaration, DeclarationId, DeclarationKind, Interface, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	helpers::{check_flat_names, flat_name, to_camel_case},
+	validators::{describe, is_validated, nested_validator, validated_interfaces, NestedValidator},
+	writer::Writer,
+	Generator,
+};
+
+//
+// Dart generator
+//
+// Interfaces become immutable classes with a const constructor,
+// enums become enhanced enums holding their string value, aliases
+// become typedefs and unions become sealed classes implemented by
+// their members. Nested
+// declarations are flattened the same way as in Rust (`UserStatus`),
+// variable names are converted to lowerCamelCase.
+//
+// Interfaces with constrained variables get `List<String> validate()` method.
+pub struct DartGenerator;
+
+const RESERVED_WORDS: &[&str] = &[
+	"assert", "break", "case", "catch", "class", "const", "continue", "default", "do", "else",
+	"enum", "extends", "false", "final", "finally", "for", "if", "in", "is", "new", "null",
+	"rethrow", "return", "super", "switch", "this", "throw", "true", "try", "var", "void", "while",
+	"with",
+];
+
+// Reserved words and core types, which would be shadowed by a class
+const RESERVED_TYPES: &[&str] = &[
+	"assert", "break", "case", "catch", "class", "const", "continue", "default", "do", "else",
+	"enum", "extends", "false", "final", "finally", "for", "if", "in", "is", "new", "null",
+	"rethrow", "return", "super", "switch", "this", "throw", "true", "try", "var", "void", "while",
+	"with", "bool", "double", "dynamic", "Function", "int", "List", "Map", "num", "Object",
+	"RegExp", "Set", "String",
+];
+
+impl Generator for DartGenerator {
+	fn name(&self) -> &'static str {
+		"dart"
+	}
+
+	fn extension(&self) -> &'static str {
+		"dart"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		ReservedWords {
+			target: "Dart",
+			declarations: RESERVED_TYPES,
+			// Variables and variants get an underscore (`class_`)
+			members: &[],
+		}
+	}
+
+	// Flattened names have to stay unique
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		check_flat_names(schema.declarations(), "Dart", flat_name)
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("  ");
+		let validated = validated_interfaces(schema);
+
+		for (index, declaration) in schema.declarations().iter().enumerate() {
+			if index > 0 {
+				writer.blank_line();
+			};
+
+			writer.docs(&declaration.docs, "/// ");
+
+			match &declaration.kind {
+				DeclarationKind::Interface(interface) => {
+					write_class(&mut writer, schema, &validated, declaration, interface);
+				}
+				DeclarationKind::Enum(enumerate) => {
+					let name = flat_name(declaration);
+
+					writer.line(format!("enum {} {{", name));
+					writer.indent();
+
+					for (index, variant) in enumerate.variants.iter().enumerate() {
+						writer.docs(&variant.docs, "/// ");
+
+						let value = variant.value.as_ref().unwrap_or(&variant.name);
+						let separator = if index + 1 == enumerate.variants.len() {
+							";"
+						} else {
+							","
+						};
+
+						writer.line(format!(
+							"{}({}){}",
+							member_name(&variant.name),
+							quote(value),
+							separator
+						));
+					}
+
+					writer.blank_line();
+					writer.line(format!("const {}(this.value);", name));
+					writer.blank_line();
+					writer.line("final String value;");
+					writer.dedent();
+					writer.line("}");
+				}
+				DeclarationKind::Alias(alias) => writer.line(format!(
+					"typedef {} = {};",
+					flat_name(declaration),
+					type_name(schema, &alias.target)
+				)),
+				// Members implement it
+				DeclarationKind::Union(_) => {
+					writer.line(format!("sealed class {} {{}}", flat_name(declaration)))
+				}
+			};
+		}
+
+		writer.finish()
+	}
+}
+
+fn write_class(
+	writer: &mut Writer,
+	schema: &Schema,
+	validated: &HashSet<DeclarationId>,
+	declaration: &Declaration,
+	interface: &Interface,
+) {
+	let name = flat_name(declaration);
+
+	let mut header = format!("class {}", name);
+	if let Some(parent) = interface.extends {
+		header.push_str(&format!(" extends {}", flat_name(schema.get(parent))));
+	};
+
+	// Unions this interface is a member of
+	let unions: Vec<String> = schema
+		.declarations()
+		.iter()
+		.filter(|union| match &union.kind {
+			DeclarationKind::Union(union) => union.members.contains(&declaration.id),
+			_ => false,
+		})
+		.map(flat_name)
+		.collect();
+	if !unions.is_empty() {
+		header.push_str(&format!(" implements {}", unions.join(", ")));
+	};
+
+	writer.line(format!("{} {{", header));
+	writer.indent();
+
+	for field in interface.own_fields() {
+		writer.docs(&field.docs, "/// ");
+
+		let optional = if field.is_required { "" } else { "?" };
+		writer.line(format!(
+			"final {}{} {};",
+			type_name(schema, &field.field_type),
+			optional,
+			member_name(&field.name)
+		));
+	}
+
+	if interface.own_fields().count() > 0 {
+		writer.blank_line();
+	};
+
+	// Constructor with named parameters, inherited ones are passed to super
+	if interface.fields.is_empty() {
+		writer.line(format!("const {}();", name));
+	} else {
+		writer.line(format!("const {}({{", name));
+		writer.indent();
+
+		for field in interface.fields.iter() {
+			let receiver = if field.inherited_from.is_some() {
+				"super"
+			} else {
+				"this"
+			};
+			let required = if field.is_required { "required " } else { "" };
+
+			writer.line(format!(
+				"{}{}.{},",
+				required,
+				receiver,
+				member_name(&field.name)
+			));
+		}
+
+		writer.dedent();
+		writer.line("});");
+	};
+
+	// Inherited variables are validated by parent's validate()
+	if interface
+		.own_fields()
+		.any(|field| is_validated(schema, field, validated))
+	{
+		let overrides = interface
+			.extends
+			.is_some_and(|parent| validated.contains(&parent));
+
+		writer.blank_line();
+		write_validator(writer, schema, validated, interface, overrides);
+	};
+
+	writer.dedent();
+	writer.line("}");
+}
+
+// lowerCamelCase name, reserved words can't be escaped in Dart,
+// so they get an underscore
+fn member_name(name: &str) -> String {
+	let name = to_camel_case(name);
+
+	if RESERVED_WORDS.contains(&name.as_str()) {
+		format!("{}_", name)
+	} else {
+		name
+	}
+}
+
+pub fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match field_type {
+		Type::String(_) => "String".to_string(),
+		Type::Boolean => "bool".to_string(),
+		Type::Integer => "int".to_string(),
+		Type::List(item_type) => format!("List<{}>", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => format!(
+			"Map<{}, {}>",
+			type_name(schema, key_type),
+			type_name(schema, value_type)
+		),
+		Type::Reference(id) => flat_name(schema.get(*id)),
+	}
+}
+
+//
+// Validator
+//
+// Example:
+// List<String> validate() {
+//   final errors = <String>[];
+//   if (userName.length < 3) {
+//     errors.add('userName: must be at least 3 characters long');
+//   }
+//   return errors;
+// }
+fn write_validator(
+	writer: &mut Writer,
+	schema: &Schema,
+	validated: &HashSet<DeclarationId>,
+	interface: &Interface,
+	overrides: bool,
+) {
+	if overrides {
+		writer.line("@override");
+	};
+	writer.line("List<String> validate() {");
+	writer.indent();
+
+	if overrides {
+		writer.line("final errors = super.validate();");
+	} else {
+		writer.line("final errors = <String>[];");
+	};
+
+	for field in interface.own_fields() {
+		let nested = nested_validator(schema, &field.field_type, validated);
+
+		if field.constraints.is_empty() && nested.is_none() {
+			continue;
+		};
+
+		let name = member_name(&field.name);
+
+		// Optional values are copied into local variables for type promotion
+		writer.blank_line();
+		let value = if field.is_required {
+			name.clone()
+		} else {
+			let local = match name.as_str() {
+				"errors" | "error" | "index" => format!("{}Value", name),
+				_ => name.clone(),
+			};
+
+			writer.line(format!("final {} = this.{};", local, name));
+			writer.line(format!("if ({} != null) {{", local));
+			writer.indent();
+			local
+		};
+
+		for constraint in field.constraints.iter() {
+			writer.line(format!("if ({}) {{", failed_check(&value, constraint)));
+			writer.indent();
+			writer.line(format!(
+				"errors.add({});",
+				quote(&format!("{}: {}", field.name, describe(constraint)))
+			));
+			writer.dedent();
+			writer.line("}");
+		}
+
+		match nested {
+			Some(NestedValidator::Value(_)) => writer.line(format!(
+				"errors.addAll({}.validate().map((error) => {} + error));",
+				value,
+				quote(&format!("{}.", field.name))
+			)),
+			Some(NestedValidator::Items(_)) => {
+				writer.line(format!(
+					"for (var index = 0; index < {}.length; index++) {{",
+					value
+				));
+				writer.indent();
+				writer.line(format!(
+					"errors.addAll({}[index].validate().map((error) => {} + index.toString() + '].' + error));",
+					value,
+					quote(&format!("{}[", field.name))
+				));
+				writer.dedent();
+				writer.line("}");
+			}
+			None => {}
+		};
+
+		if !field.is_required {
+			writer.dedent();
+			writer.line("}");
+		};
+	}
+
+	writer.blank_line();
+	writer.line("return errors;");
+	writer.dedent();
+	writer.line("}");
+}
+
+// Condition which is true when the constraint is violated. String length
+// is counted in code points (`runes`), not UTF-16 units
+fn failed_check(value: &str, constraint: &Constraint) -> String {
+	match constraint {
+		Constraint::MinLength(length) => format!("{}.runes.length < {}", value, length),
+		Constraint::MaxLength(length) => format!("{}.runes.length > {}", value, length),
+		Constraint::MinItems(count) => format!("{}.length < {}", value, count),
+		Constraint::MaxItems(count) => format!("{}.length > {}", value, count),
+		Constraint::Pattern(pattern) => format!("!RegExp({}).hasMatch({})", quote(pattern), value),
+		Constraint::Format(format) => {
+			format!("!RegExp({}).hasMatch({})", quote(format.pattern()), value)
+		}
+		Constraint::Minimum(minimum) => format!("{} < {}", value, minimum),
+		Constraint::Maximum(maximum) => format!("{} > {}", value, maximum),
+	}
+}
+
+// Single-quoted Dart string, `$` has to be escaped as well
+pub fn quote(value: &str) -> String {
+	let mut result = String::from("'");
+
+	for char in value.chars() {
+		match char {
+			'\'' => result.push_str("\\'"),
+			'\\' => result.push_str("\\\\"),
+			'$' => result.push_str("\\$"),
+			'\n' => result.push_str("\\n"),
+			'\r' => result.push_str("\\r"),
+			'\t' => result.push_str("\\t"),
+			char => result.push(char),
+		};
+	}
+
+	result.push('\'');
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_classes_enums_and_validators() {
+		let schema = analyze(
+			"
+			interface User {
+				#min_length = 3
+				required user_name: String;
+				required status: User.Status;
+
+				enum Status {
+					REGISTERED: registered;
+					BANNED: banned;
+				};
+			};
+
+			interface Admin extends User {
+				#min = 1
+				optional level: Int;
+			};
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			DartGenerator.generate(&schema),
+			r#"class User {
+  final String userName;
+  final UserStatus status;
+
+  const User({
+    required this.userName,
+    required this.status,
+  });
+
+  List<String> validate() {
+    final errors = <String>[];
+
+    if (userName.runes.length < 3) {
+      errors.add('user_name: must be at least 3 characters long');
+    }
+
+    return errors;
+  }
+}
+
+enum UserStatus {
+  registered('registered'),
+  banned('banned');
+
+  const UserStatus(this.value);
+
+  final String value;
+}
+
+class Admin extends User {
+  final int? level;
+
+  const Admin({
+    required super.userName,
+    required super.status,
+    this.level,
+  });
+
+  @override
+  List<String> validate() {
+    final errors = super.validate();
+
+    final level = this.level;
+    if (level != null) {
+      if (level < 1) {
+        errors.add('level: must be greater than or equal to 1');
+      }
+    }
+
+    return errors;
+  }
+}
+"#
+		);
+	}
+
+	#[test]
+	fn reports_flattened_name_collisions() {
+		let schema = analyze(
+			"
+			interface User {
+				enum Status {
+					A: a;
+				};
+			};
+
+			enum UserStatus {
+				B: b;
+			};
+			",
+		)
+		.unwrap();
+
+		let messages: Vec<String> = DartGenerator
+			.check(&schema)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec!["`User.Status` and `UserStatus` are both named `UserStatus` in Dart"]
+		);
+	}
+
+	#[test]
+	fn flattens_nested_names_and_aliases_to_unions() {
+		let schema = analyze(
+			"
+			interface User {
+				required status: User.Status;
+				required scores: Map<Int, Int>;
+				optional default: Contact;
+
+				enum Status {
+					Active: active;
+				};
+			};
+
+			union Account = User;
+
+			alias Contact = Account;
+			",
+		)
+		.unwrap();
+
+		let output = DartGenerator.generate(&schema);
+
+		assert!(output.contains("class User implements Account {\n  final UserStatus status;\n  final Map<int, int> scores;\n  final Contact? default_;\n"));
+		assert!(output.contains("enum UserStatus {\n"));
+		assert!(output.contains("typedef Contact = Account;\n"));
+	}
+}
@@ -0,0 +1,493 @@
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Declaration, DeclarationId, DeclarationKind, Field, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	helpers::{check_flat_names, split_words},
+	quote,
+	writer::Writer,
+	Generator,
+};
+
+//
+// Go generator
+//
+// Interfaces become structs with `json` tags, parents are embedded, so that
+// `encoding/json` flattens their fields. Optional variables become pointers
+// (slices and maps are nil-able already) and are tagged `omitempty`.
+// Enums become typed string constants with `Valid()` method, aliases become
+// alias declarations and unions become interfaces with a marker method
+// implemented by their members (decoding them needs a custom unmarshaler).
+//
+// Go has no nested types, so declarations are flattened like in Rust
+// (`UserStatus`), names follow Go initialisms (`user_id` becomes `UserID`).
+//
+// Output is already gofmt-formatted: tabs for indentation, columns of
+// consecutive fields and constants aligned with spaces.
+pub struct GoGenerator;
+
+const PACKAGE: &str = "schema";
+
+// Words written in upper case as a part of identifiers
+const INITIALISMS: &[&str] = &[
+	"api", "html", "http", "https", "id", "ip", "json", "sql", "uri", "url", "uuid", "xml",
+];
+
+impl Generator for GoGenerator {
+	fn name(&self) -> &'static str {
+		"go"
+	}
+
+	fn extension(&self) -> &'static str {
+		"go"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		// Every generated identifier is exported, so it can't be a keyword
+		ReservedWords {
+			target: "Go",
+			declarations: &[],
+			members: &[],
+		}
+	}
+
+	// Flattened names have to stay unique
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		check_flat_names(schema.declarations(), "Go", go_type_name)
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("\t");
+
+		writer.line(format!("package {}", PACKAGE));
+
+		for declaration in schema.declarations() {
+			writer.blank_line();
+			writer.docs(&declaration.docs, "// ");
+
+			let name = go_type_name(declaration);
+
+			match &declaration.kind {
+				DeclarationKind::Interface(interface) => {
+					let fields: Vec<&Field> = interface.own_fields().collect();
+
+					if fields.is_empty() && interface.extends.is_none() {
+						writer.line(format!("type {} struct{{}}", name));
+					} else {
+						writer.line(format!("type {} struct {{", name));
+						writer.indent();
+
+						if let Some(parent) = interface.extends {
+							writer.line(go_type_name(schema.get(parent)));
+						};
+
+						let mut rows = Vec::new();
+						for field in fields {
+							if field.docs.is_some() {
+								write_aligned(&mut writer, &std::mem::take(&mut rows));
+								writer.docs(&field.docs, "// ");
+							};
+
+							rows.push(field_row(schema, declaration.id, field));
+						}
+						write_aligned(&mut writer, &rows);
+
+						writer.dedent();
+						writer.line("}");
+					};
+
+					// Marker methods of unions this interface is a member of
+					for union in schema.declarations() {
+						if let DeclarationKind::Union(members) = &union.kind {
+							if members.members.contains(&declaration.id) {
+								writer.blank_line();
+								writer.line(format!(
+									"func ({}) {}() {{}}",
+									name,
+									marker_method(union)
+								));
+							};
+						};
+					}
+				}
+				DeclarationKind::Enum(enumerate) => {
+					writer.line(format!("type {} string", name));
+					writer.blank_line();
+					writer.line("const (");
+					writer.indent();
+
+					let mut rows = Vec::new();
+					for variant in enumerate.variants.iter() {
+						if variant.docs.is_some() {
+							write_aligned(&mut writer, &std::mem::take(&mut rows));
+							writer.docs(&variant.docs, "// ");
+						};
+
+						let value = variant.value.as_ref().unwrap_or(&variant.name);
+						rows.push(vec![
+							constant_name(declaration, &variant.name),
+							name.clone(),
+							format!("= {}", quote(value)),
+						]);
+					}
+					write_aligned(&mut writer, &rows);
+
+					writer.dedent();
+					writer.line(")");
+
+					let constants: Vec<String> = enumerate
+						.variants
+						.iter()
+						.map(|variant| constant_name(declaration, &variant.name))
+						.collect();
+
+					writer.blank_line();
+					writer.line(format!(
+						"// Valid reports whether value is one of {} constants",
+						name
+					));
+					writer.line(format!("func (value {}) Valid() bool {{", name));
+					writer.indent();
+					writer.line("switch value {");
+					writer.line(format!("case {}:", constants.join(", ")));
+					writer.indent();
+					writer.line("return true");
+					writer.dedent();
+					writer.line("}");
+					writer.line("return false");
+					writer.dedent();
+					writer.line("}");
+				}
+				DeclarationKind::Alias(alias) => writer.line(format!(
+					"type {} = {}",
+					name,
+					type_name(schema, &alias.target)
+				)),
+				DeclarationKind::Union(_) => {
+					writer.line(format!("type {} interface {{", name));
+					writer.indent();
+					writer.line(format!("{}()", marker_method(declaration)));
+					writer.dedent();
+					writer.line("}");
+				}
+			};
+		}
+
+		writer.finish()
+	}
+}
+
+// Example: ["UserName", "*string", "`json:\"user_name,omitempty\"`"]
+fn field_row(schema: &Schema, parent: DeclarationId, field: &Field) -> Vec<String> {
+	let mut field_type = type_name(schema, &field.field_type);
+
+	let is_nullable = matches!(
+		schema.resolve_type(&field.field_type),
+		Type::List(_) | Type::Map(_, _)
+	) || is_union(schema, &field.field_type);
+
+	// Structs can't contain themselves without indirection
+	let is_recursive = contains(schema, &field.field_type, parent, &mut Vec::new());
+
+	let tag = if field.is_required {
+		if is_recursive && !is_nullable {
+			field_type = format!("*{}", field_type);
+		};
+
+		format!("`json:{}`", quote(&field.name))
+	} else {
+		if !is_nullable {
+			field_type = format!("*{}", field_type);
+		};
+
+		format!("`json:{}`", quote(&format!("{},omitempty", field.name)))
+	};
+
+	vec![go_name(&field.name), field_type, tag]
+}
+
+pub fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match field_type {
+		Type::String(_) => "string".to_string(),
+		Type::Boolean => "bool".to_string(),
+		Type::Integer => "int64".to_string(),
+		Type::List(item_type) => format!("[]{}", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => format!(
+			"map[{}]{}",
+			type_name(schema, key_type),
+			type_name(schema, value_type)
+		),
+		Type::Reference(id) => go_type_name(schema.get(*id)),
+	}
+}
+
+fn is_union(schema: &Schema, field_type: &Type) -> bool {
+	match schema.resolve_type(field_type) {
+		Type::Reference(id) => matches!(schema.get(*id).kind, DeclarationKind::Union(_)),
+		_ => false,
+	}
+}
+
+// Whether a value of this type holds target directly, through required
+// struct fields. Pointers, slices, maps and interfaces are indirections
+fn contains(
+	schema: &Schema,
+	field_type: &Type,
+	target: DeclarationId,
+	visited: &mut Vec<DeclarationId>,
+) -> bool {
+	let id = match schema.resolve_type(field_type) {
+		Type::Reference(id) => *id,
+		_ => return false,
+	};
+
+	if id == target {
+		return true;
+	};
+
+	if visited.contains(&id) {
+		return false;
+	};
+	visited.push(id);
+
+	match &schema.get(id).kind {
+		DeclarationKind::Interface(interface) => interface
+			.fields
+			.iter()
+			.filter(|field| field.is_required && !is_union(schema, &field.field_type))
+			.any(|field| contains(schema, &field.field_type, target, visited)),
+		_ => false,
+	}
+}
+
+// Writes rows of cells the way gofmt does: every column but the last one
+// is padded to the widest cell plus one space
+fn write_aligned(writer: &mut Writer, rows: &[Vec<String>]) {
+	let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+	let mut widths = vec![0; columns];
+
+	for row in rows.iter() {
+		for (index, cell) in row.iter().enumerate().take(row.len() - 1) {
+			widths[index] = widths[index].max(cell.chars().count());
+		}
+	}
+
+	for row in rows.iter() {
+		let mut line = String::new();
+
+		for (index, cell) in row.iter().enumerate() {
+			line.push_str(cell);
+
+			if index + 1 < row.len() {
+				let padding = widths[index] - cell.chars().count() + 1;
+				line.push_str(&" ".repeat(padding));
+			};
+		}
+
+		writer.line(line);
+	}
+}
+
+// `user_id` becomes `UserID`
+fn go_name(name: &str) -> String {
+	split_words(name)
+		.iter()
+		.map(|word| {
+			if INITIALISMS.contains(&word.as_str()) {
+				word.to_uppercase()
+			} else {
+				let mut chars = word.chars();
+				match chars.next() {
+					Some(first) => first.to_uppercase().chain(chars).collect(),
+					None => String::new(),
+				}
+			}
+		})
+		.collect()
+}
+
+fn go_type_name(declaration: &Declaration) -> String {
+	declaration
+		.path
+		.iter()
+		.map(|segment| go_name(segment))
+		.collect()
+}
+
+// Constants are prefixed with their type: `UserStatusRegistered`
+fn constant_name(declaration: &Declaration, variant: &str) -> String {
+	format!("{}{}", go_type_name(declaration), go_name(variant))
+}
+
+fn marker_method(union: &Declaration) -> String {
+	format!("is{}", go_type_name(union))
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_gofmt_compatible_code() {
+		let schema = analyze(
+			"
+			/// Registered user
+			interface User {
+				required user_id: String;
+				optional username: String;
+				optional tags: List<String>;
+				required status: User.Status;
+
+				/// Referrer of this user
+				optional referrer: User;
+
+				enum Status {
+					Registered: registered;
+					BANNED: banned;
+				};
+			};
+
+			interface Admin extends User {
+				required level: Int;
+			};
+
+			union Account = Admin | User;
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			GoGenerator.generate(&schema),
+			r#"package schema
+
+// Registered user
+type User struct {
+	UserID   string     `json:"user_id"`
+	Username *string    `json:"username,omitempty"`
+	Tags     []string   `json:"tags,omitempty"`
+	Status   UserStatus `json:"status"`
+	// Referrer of this user
+	Referrer *User `json:"referrer,omitempty"`
+}
+
+func (User) isAccount() {}
+
+type UserStatus string
+
+const (
+	UserStatusRegistered UserStatus = "registered"
+	UserStatusBanned     UserStatus = "banned"
+)
+
+// Valid reports whether value is one of UserStatus constants
+func (value UserStatus) Valid() bool {
+	switch value {
+	case UserStatusRegistered, UserStatusBanned:
+		return true
+	}
+	return false
+}
+
+type Admin struct {
+	User
+	Level int64 `json:"level"`
+}
+
+func (Admin) isAccount() {}
+
+type Account interface {
+	isAccount()
+}
+"#
+		);
+	}
+
+	#[test]
+	fn reports_flattened_name_collisions() {
+		let schema = analyze(
+			"
+			interface User {
+				required user_id: String;
+
+				enum Status {
+					A: a;
+				};
+			};
+
+			enum UserStatus {
+				B: b;
+			};
+
+			alias UserID = String;
+			",
+		)
+		.unwrap();
+
+		let messages: Vec<String> = GoGenerator
+			.check(&schema)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec!["`User.Status` and `UserStatus` are both named `UserStatus` in Go"]
+		);
+	}
+
+	#[test]
+	fn exports_keyword_names() {
+		let schema = analyze(
+			"
+			interface type {
+				required func: String;
+				optional range: type.chan;
+
+				enum chan {
+					default: default;
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		let output = GoGenerator.generate(&schema);
+
+		assert!(output.contains("type Type struct {\n\tFunc  string    `json:\"func\"`\n\tRange *TypeChan `json:\"range,omitempty\"`\n}"));
+		assert!(output.contains("\tTypeChanDefault TypeChan = \"default\"\n"));
+	}
+
+	#[test]
+	fn flattens_nested_names_and_aliases_to_unions() {
+		let schema = analyze(
+			"
+			interface User {
+				required status: User.Status;
+				required scores: Map<Int, Int>;
+				optional default: Contact;
+
+				enum Status {
+					Active: active;
+				};
+			};
+
+			union Account = User;
+
+			alias Contact = Account;
+			",
+		)
+		.unwrap();
+
+		let output = GoGenerator.generate(&schema);
+
+		assert!(output.contains("type User struct {\n\tStatus  UserStatus      `json:\"status\"`\n\tScores  map[int64]int64 `json:\"scores\"`\n\tDefault Contact         `json:\"default,omitempty\"`\n}"));
+		assert!(output.contains("type UserStatus string\n"));
+		// Interfaces are nil-able, so optional unions aren't pointers
+		assert!(output.contains("type Contact = Account\n"));
+		assert!(GoGenerator.check(&schema).is_empty());
+	}
+}
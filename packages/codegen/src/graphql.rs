@@ -0,0 +1,488 @@
+use std::collections::BTreeSet;
+
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Declaration, DeclarationId, DeclarationKind, Field, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	helpers::{flat_name, to_snake_case},
+	quote,
+	writer::Writer,
+	Generator,
+};
+
+//
+// GraphQL generator
+//
+// Interfaces become object types (with inherited variables copied in),
+// enums become enums and unions become unions. GraphQL has no namespaces,
+// so nested declarations are flattened (`User.Status` becomes `UserStatus`),
+// aliases are replaced with their types and maps become `JSON` scalar.
+// Note that GraphQL `Int` is only 32 bits wide.
+//
+// Interfaces marked with `#graphql_input` also get an input type
+// (`UserInput`), as well as every interface they reference.
+//
+// Enum values have to be identifiers, so `Registered: "registered"` becomes
+// `REGISTERED @value(string: "registered")`. Resolvers are expected to use
+// this directive to map values to and from their serialized form.
+pub struct GraphQlGenerator;
+
+const INPUT_ATTRIBUTE: &str = "graphql_input";
+const JSON_SCALAR: &str = "JSON";
+
+// Built-in scalars
+const RESERVED_WORDS: &[&str] = &["String", "Int", "Float", "Boolean", "ID", JSON_SCALAR];
+
+impl Generator for GraphQlGenerator {
+	fn name(&self) -> &'static str {
+		"graphql"
+	}
+
+	fn extension(&self) -> &'static str {
+		"graphql"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		ReservedWords {
+			target: "GraphQL",
+			declarations: RESERVED_WORDS,
+			members: &[],
+		}
+	}
+
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		let mut diagnostics = Vec::new();
+		let inputs = input_interfaces(schema);
+
+		for declaration in schema.declarations() {
+			let interface = match &declaration.kind {
+				DeclarationKind::Interface(interface) => interface,
+				_ => continue,
+			};
+
+			if interface.fields.is_empty() {
+				diagnostics.push(
+					Diagnostic::error(format!(
+						"interface `{}` has no variables, GraphQL types need at least one field",
+						declaration.qualified_name()
+					))
+					.with_label(declaration.span.clone(), "empty interface"),
+				);
+			};
+
+			// Keywords are valid names, but `__` prefix isn't
+			for field in interface.own_fields() {
+				if field.name.starts_with("__") {
+					diagnostics.push(
+						Diagnostic::error(format!(
+							"variable `{}` of `{}` starts with `__`, which GraphQL reserves for introspection",
+							field.name,
+							declaration.qualified_name()
+						))
+						.with_label(field.span.clone(), "can't be a GraphQL field"),
+					);
+				};
+			}
+
+			if !inputs.contains(&declaration.id) {
+				continue;
+			};
+
+			// Input types can only reference scalars, enums and other inputs
+			for field in interface.own_fields() {
+				if let Some(union) = referenced_union(schema, &field.field_type) {
+					diagnostics.push(
+						Diagnostic::error(format!(
+							"union `{}` can't be used in GraphQL input `{}Input`",
+							union.qualified_name(),
+							flat_name(declaration)
+						))
+						.with_label(field.span.clone(), "unions can't be inputs"),
+					);
+				};
+			}
+		}
+
+		diagnostics
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("  ");
+		let inputs = input_interfaces(schema);
+
+		let mut is_first = true;
+		let mut separate = |writer: &mut Writer| {
+			if !is_first {
+				writer.blank_line();
+			};
+			is_first = false;
+		};
+
+		// Declarations used by generated types
+		if uses_maps(schema) {
+			separate(&mut writer);
+			writer.line(quote("Arbitrary JSON object"));
+			writer.line(format!("scalar {}", JSON_SCALAR));
+		};
+
+		if uses_value_directive(schema) {
+			separate(&mut writer);
+			writer.line(quote("Serialized form of an enum value"));
+			writer.line("directive @value(string: String!) on ENUM_VALUE");
+		};
+
+		for declaration in schema.declarations() {
+			match &declaration.kind {
+				DeclarationKind::Interface(interface) => {
+					separate(&mut writer);
+					write_fields(&mut writer, schema, declaration, &interface.fields, false);
+
+					if inputs.contains(&declaration.id) {
+						writer.blank_line();
+						write_fields(&mut writer, schema, declaration, &interface.fields, true);
+					};
+				}
+				DeclarationKind::Enum(enumerate) => {
+					separate(&mut writer);
+					write_description(&mut writer, &declaration.docs);
+					writer.line(format!("enum {} {{", flat_name(declaration)));
+					writer.indent();
+
+					for variant in enumerate.variants.iter() {
+						write_description(&mut writer, &variant.docs);
+
+						let name = value_name(&variant.name);
+						let value = variant.value.as_ref().unwrap_or(&variant.name);
+
+						if &name == value {
+							writer.line(name);
+						} else {
+							writer.line(format!("{} @value(string: {})", name, quote(value)));
+						};
+					}
+
+					writer.dedent();
+					writer.line("}");
+				}
+				DeclarationKind::Union(union) => {
+					separate(&mut writer);
+					write_description(&mut writer, &declaration.docs);
+
+					let members: Vec<String> = union
+						.members
+						.iter()
+						.map(|member| flat_name(schema.get(*member)))
+						.collect();
+
+					writer.line(format!(
+						"union {} = {}",
+						flat_name(declaration),
+						members.join(" | ")
+					));
+				}
+				DeclarationKind::Alias(_) => {}
+			};
+		}
+
+		writer.finish()
+	}
+}
+
+// Object type or input type of an interface
+fn write_fields(
+	writer: &mut Writer,
+	schema: &Schema,
+	declaration: &Declaration,
+	fields: &[Field],
+	is_input: bool,
+) {
+	write_description(writer, &declaration.docs);
+
+	if is_input {
+		writer.line(format!("input {}Input {{", flat_name(declaration)));
+	} else {
+		writer.line(format!("type {} {{", flat_name(declaration)));
+	};
+	writer.indent();
+
+	for field in fields.iter() {
+		write_description(writer, &field.docs);
+
+		let non_null = if field.is_required { "!" } else { "" };
+		writer.line(format!(
+			"{}: {}{}",
+			field.name,
+			type_name(schema, &field.field_type, is_input),
+			non_null
+		));
+	}
+
+	writer.dedent();
+	writer.line("}");
+}
+
+pub fn type_name(schema: &Schema, field_type: &Type, is_input: bool) -> String {
+	match schema.resolve_type(field_type) {
+		Type::String(_) => "String".to_string(),
+		Type::Boolean => "Boolean".to_string(),
+		Type::Integer => "Int".to_string(),
+		Type::List(item_type) => format!("[{}!]", type_name(schema, item_type, is_input)),
+		Type::Map(_, _) => JSON_SCALAR.to_string(),
+		Type::Reference(id) => {
+			let declaration = schema.get(*id);
+
+			match declaration.kind {
+				DeclarationKind::Interface(_) if is_input => {
+					format!("{}Input", flat_name(declaration))
+				}
+				_ => flat_name(declaration),
+			}
+		}
+	}
+}
+
+// `Registered` becomes `REGISTERED`
+fn value_name(name: &str) -> String {
+	to_snake_case(name).to_uppercase()
+}
+
+fn write_description(writer: &mut Writer, docs: &Option<String>) {
+	let docs = match docs {
+		Some(docs) => docs,
+		None => return,
+	};
+
+	if docs.contains('\n') {
+		writer.line("\"\"\"");
+		for line in docs.lines() {
+			writer.line(line.replace("\"\"\"", "\\\"\"\""));
+		}
+		writer.line("\"\"\"");
+	} else {
+		writer.line(quote(docs));
+	};
+}
+
+// Interfaces marked as inputs and everything they reference
+fn input_interfaces(schema: &Schema) -> BTreeSet<DeclarationId> {
+	let mut inputs = BTreeSet::new();
+	let mut pending: Vec<DeclarationId> = schema
+		.declarations()
+		.iter()
+		.filter(|declaration| declaration.attribute(INPUT_ATTRIBUTE).is_some())
+		.filter(|declaration| matches!(declaration.kind, DeclarationKind::Interface(_)))
+		.map(|declaration| declaration.id)
+		.collect();
+
+	while let Some(id) = pending.pop() {
+		if !inputs.insert(id) {
+			continue;
+		};
+
+		if let DeclarationKind::Interface(interface) = &schema.get(id).kind {
+			for field in interface.fields.iter() {
+				let mut field_type = schema.resolve_type(&field.field_type);
+
+				while let Type::List(item_type) = field_type {
+					field_type = schema.resolve_type(item_type);
+				}
+
+				if let Type::Reference(id) = field_type {
+					if let DeclarationKind::Interface(_) = schema.get(*id).kind {
+						pending.push(*id);
+					};
+				};
+			}
+		};
+	}
+
+	inputs
+}
+
+fn referenced_union<'a>(schema: &'a Schema, field_type: &'a Type) -> Option<&'a Declaration> {
+	match schema.resolve_type(field_type) {
+		Type::List(item_type) => referenced_union(schema, item_type),
+		Type::Reference(id) => match schema.get(*id).kind {
+			DeclarationKind::Union(_) => Option::Some(schema.get(*id)),
+			_ => Option::None,
+		},
+		_ => Option::None,
+	}
+}
+
+fn uses_maps(schema: &Schema) -> bool {
+	fn is_map(schema: &Schema, field_type: &Type) -> bool {
+		match schema.resolve_type(field_type) {
+			Type::Map(_, _) => true,
+			Type::List(item_type) => is_map(schema, item_type),
+			_ => false,
+		}
+	}
+
+	schema
+		.declarations()
+		.iter()
+		.any(|declaration| match &declaration.kind {
+			DeclarationKind::Interface(interface) => interface
+				.fields
+				.iter()
+				.any(|field| is_map(schema, &field.field_type)),
+			_ => false,
+		})
+}
+
+fn uses_value_directive(schema: &Schema) -> bool {
+	schema
+		.declarations()
+		.iter()
+		.any(|declaration| match &declaration.kind {
+			DeclarationKind::Enum(enumerate) => enumerate.variants.iter().any(|variant| {
+				variant.value.as_ref().unwrap_or(&variant.name) != &value_name(&variant.name)
+			}),
+			_ => false,
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_types_inputs_enums_and_unions() {
+		let schema = analyze(
+			"
+			/// Registered user
+			#graphql_input
+			interface User {
+				required id: String;
+				optional tags: List<String>;
+				optional extra: Map<String, Int>;
+				required address: User.Address;
+
+				interface Address {
+					required city: String;
+				};
+			};
+
+			interface Post {
+				required title: String;
+				required status: Status;
+			};
+
+			enum Status {
+				DRAFT: DRAFT;
+				Published: published;
+			};
+
+			union SearchResult = User | Post;
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			GraphQlGenerator.generate(&schema),
+			r#""Arbitrary JSON object"
+scalar JSON
+
+"Serialized form of an enum value"
+directive @value(string: String!) on ENUM_VALUE
+
+"Registered user"
+type User {
+  id: String!
+  tags: [String!]
+  extra: JSON
+  address: UserAddress!
+}
+
+"Registered user"
+input UserInput {
+  id: String!
+  tags: [String!]
+  extra: JSON
+  address: UserAddressInput!
+}
+
+type UserAddress {
+  city: String!
+}
+
+input UserAddressInput {
+  city: String!
+}
+
+type Post {
+  title: String!
+  status: Status!
+}
+
+enum Status {
+  DRAFT
+  PUBLISHED @value(string: "published")
+}
+
+union SearchResult = User | Post
+"#
+		);
+	}
+
+	#[test]
+	fn reports_empty_types_and_union_inputs() {
+		let schema = analyze(
+			"
+			interface Empty {};
+			interface User { required id: String; optional __typename: String; };
+			union Result = User;
+
+			#graphql_input
+			interface Query {
+				required result: Result;
+			};
+			",
+		)
+		.unwrap();
+
+		let messages: Vec<String> = GraphQlGenerator
+			.check(&schema)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec![
+				"interface `Empty` has no variables, GraphQL types need at least one field",
+				"variable `__typename` of `User` starts with `__`, which GraphQL reserves for introspection",
+				"union `Result` can't be used in GraphQL input `QueryInput`",
+			]
+		);
+	}
+
+	#[test]
+	fn keeps_keyword_names() {
+		let schema = analyze(
+			"
+			interface type {
+				required input: String;
+				required enum: type.fragment;
+
+				enum fragment {
+					true: true;
+					null: null;
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		let output = GraphQlGenerator.generate(&schema);
+
+		assert!(output.contains("type Type {\n  input: String!\n  enum: TypeFragment!\n}"));
+		assert!(output.contains("enum TypeFragment {\n  TRUE @value(string: \"true\")\n  NULL @value(string: \"null\")\n}"));
+	}
+}
@@ -0,0 +1,334 @@
+use std::collections::BTreeSet;
+
+use semantic::ir::{Declaration, DeclarationKind, Schema, Type};
+
+use crate::{quote, typescript::qualified_name, writer::Writer};
+
+//
+// Guards
+//
+// Runtime type checks of TypeScript target (`typescript-guards`). Every
+// declaration gets a check function (`check$User(value, path, errors)`),
+// collecting path-qualified errors like `$.friends[0].id: expected string`.
+// Exported guards and parsers are built on top of it:
+// export function isUser(value: unknown): value is User
+// export function parseUser(json: string): ParseResult<User>
+//
+// Checks are composed of small helpers, only the used ones are written,
+// so that generated code passes `noUnusedLocals`.
+//
+
+// Helper functions, in the order they're written
+const HELPERS: &[(&str, &str)] = &[
+	(
+		"checkString",
+		"function checkString(value: unknown, path: string, errors: string[]): void {
+  if (typeof value !== \"string\") {
+    errors.push(path + \": expected string\");
+  }
+}",
+	),
+	(
+		"checkInteger",
+		"function checkInteger(value: unknown, path: string, errors: string[]): void {
+  if (typeof value !== \"number\" || !Number.isInteger(value)) {
+    errors.push(path + \": expected integer\");
+  }
+}",
+	),
+	(
+		"checkIntegerKey",
+		"function checkIntegerKey(value: unknown, path: string, errors: string[]): void {
+  if (typeof value !== \"string\" || !/^-?[0-9]+$/.test(value)) {
+    errors.push(path + \": expected integer key\");
+  }
+}",
+	),
+	(
+		"checkBoolean",
+		"function checkBoolean(value: unknown, path: string, errors: string[]): void {
+  if (typeof value !== \"boolean\") {
+    errors.push(path + \": expected boolean\");
+  }
+}",
+	),
+	(
+		"checkLiteral",
+		"function checkLiteral(expected: string): Check {
+  return (value, path, errors) => {
+    if (value !== expected) {
+      errors.push(path + \": expected \" + JSON.stringify(expected));
+    }
+  };
+}",
+	),
+	(
+		"checkOneOf",
+		"function checkOneOf(expected: string[]): Check {
+  return (value, path, errors) => {
+    if (typeof value !== \"string\" || !expected.includes(value)) {
+      errors.push(path + \": expected one of \" + expected.map((item) => JSON.stringify(item)).join(\", \"));
+    }
+  };
+}",
+	),
+	(
+		"checkList",
+		"function checkList(item: Check): Check {
+  return (value, path, errors) => {
+    if (!Array.isArray(value)) {
+      errors.push(path + \": expected array\");
+      return;
+    }
+    value.forEach((element, index) => item(element, path + \"[\" + index + \"]\", errors));
+  };
+}",
+	),
+	(
+		"checkMap",
+		"function checkMap(key: Check, item: Check): Check {
+  return (value, path, errors) => {
+    if (typeof value !== \"object\" || value === null || Array.isArray(value)) {
+      errors.push(path + \": expected object\");
+      return;
+    }
+    for (const [name, element] of Object.entries(value)) {
+      key(name, path + \"[\" + JSON.stringify(name) + \"]\", errors);
+      item(element, path + \"[\" + JSON.stringify(name) + \"]\", errors);
+    }
+  };
+}",
+	),
+	(
+		"checkObject",
+		"function checkObject(value: unknown, path: string, errors: string[], fields: [string, boolean, Check][]): void {
+  if (typeof value !== \"object\" || value === null || Array.isArray(value)) {
+    errors.push(path + \": expected object\");
+    return;
+  }
+  const object = value as Record<string, unknown>;
+  for (const [name, isRequired, check] of fields) {
+    if (object[name] === undefined) {
+      if (isRequired) {
+        errors.push(path + \".\" + name + \": required\");
+      }
+      continue;
+    }
+    check(object[name], path + \".\" + name, errors);
+  }
+}",
+	),
+	(
+		"checkUnion",
+		"function checkUnion(name: string, members: Check[]): Check {
+  return (value, path, errors) => {
+    if (!members.some((member) => matches(value, member))) {
+      errors.push(path + \": expected \" + name);
+    }
+  };
+}",
+	),
+];
+
+// Always written, guards and parsers of every declaration use them
+const PRELUDE: &str =
+	"export type ParseResult<T> = { ok: true; value: T } | { ok: false; errors: string[] };
+
+type Check = (value: unknown, path: string, errors: string[]) => void;
+
+function matches(value: unknown, check: Check): boolean {
+  const errors: string[] = [];
+  check(value, \"$\", errors);
+  return errors.length === 0;
+}
+
+function parseWith<T>(json: string, check: Check): ParseResult<T> {
+  let value: unknown;
+  try {
+    value = JSON.parse(json);
+  } catch {
+    return { ok: false, errors: [\"$: invalid JSON\"] };
+  }
+  const errors: string[] = [];
+  check(value, \"$\", errors);
+  return errors.length === 0 ? { ok: true, value: value as T } : { ok: false, errors };
+}";
+
+// Guard and parser of a declaration, written next to it
+pub fn write_guards(writer: &mut Writer, declaration: &Declaration) {
+	let check = check_name(declaration);
+	let qualified_name = qualified_name(declaration);
+
+	writer.line(format!(
+		"export function is{}(value: unknown): value is {} {{",
+		declaration.name, qualified_name
+	));
+	writer.indent();
+	writer.line(format!("return matches(value, {});", check));
+	writer.dedent();
+	writer.line("}");
+
+	writer.blank_line();
+	writer.line(format!(
+		"export function parse{}(json: string): ParseResult<{}> {{",
+		declaration.name, qualified_name
+	));
+	writer.indent();
+	writer.line(format!(
+		"return parseWith<{}>(json, {});",
+		qualified_name, check
+	));
+	writer.dedent();
+	writer.line("}");
+}
+
+// Helpers at the top of generated code
+pub fn write_prelude(writer: &mut Writer, schema: &Schema) {
+	let mut used = BTreeSet::new();
+	for declaration in schema.declarations() {
+		check_body(schema, declaration, &mut used);
+	}
+
+	for line in PRELUDE.lines() {
+		writer.line(line);
+	}
+
+	for (name, helper) in HELPERS.iter() {
+		if used.contains(name) {
+			writer.blank_line();
+			for line in helper.lines() {
+				writer.line(line);
+			}
+		};
+	}
+}
+
+// Check functions of every declaration, at the bottom of generated code.
+// They're module-level, so nested guards can use them as well
+pub fn write_checks(writer: &mut Writer, schema: &Schema) {
+	for (index, declaration) in schema.declarations().iter().enumerate() {
+		if index > 0 {
+			writer.blank_line();
+		};
+
+		writer.line(format!(
+			"function {}(value: unknown, path: string, errors: string[]): void {{",
+			check_name(declaration)
+		));
+		writer.indent();
+		for line in check_body(schema, declaration, &mut BTreeSet::new()).lines() {
+			writer.line(line);
+		}
+		writer.dedent();
+		writer.line("}");
+	}
+}
+
+fn check_body(
+	schema: &Schema,
+	declaration: &Declaration,
+	used: &mut BTreeSet<&'static str>,
+) -> String {
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			used.insert("checkObject");
+
+			let fields: Vec<String> = interface
+				.fields
+				.iter()
+				.map(|field| {
+					format!(
+						"  [{}, {}, {}],",
+						quote(&field.name),
+						field.is_required,
+						check_expression(schema, &field.field_type, used)
+					)
+				})
+				.collect();
+
+			if fields.is_empty() {
+				"checkObject(value, path, errors, []);".to_string()
+			} else {
+				format!(
+					"checkObject(value, path, errors, [\n{}\n]);",
+					fields.join("\n")
+				)
+			}
+		}
+		DeclarationKind::Enum(enumerate) => {
+			used.insert("checkOneOf");
+
+			let values: Vec<String> = enumerate
+				.variants
+				.iter()
+				.map(|variant| quote(variant.value.as_ref().unwrap_or(&variant.name)))
+				.collect();
+
+			format!("checkOneOf([{}])(value, path, errors);", values.join(", "))
+		}
+		DeclarationKind::Alias(alias) => format!(
+			"{}(value, path, errors);",
+			check_expression(schema, &alias.target, used)
+		),
+		DeclarationKind::Union(union) => {
+			used.insert("checkUnion");
+
+			let members: Vec<String> = union
+				.members
+				.iter()
+				.map(|member| check_name(schema.get(*member)))
+				.collect();
+
+			format!(
+				"checkUnion({}, [{}])(value, path, errors);",
+				quote(&declaration.qualified_name()),
+				members.join(", ")
+			)
+		}
+	}
+}
+
+// Function checking a value of the given type
+fn check_expression(
+	schema: &Schema,
+	field_type: &Type,
+	used: &mut BTreeSet<&'static str>,
+) -> String {
+	let helper = |used: &mut BTreeSet<&'static str>, name: &'static str| {
+		used.insert(name);
+		name.to_string()
+	};
+
+	match field_type {
+		Type::String(None) => helper(used, "checkString"),
+		Type::String(Some(value)) => format!("{}({})", helper(used, "checkLiteral"), quote(value)),
+		Type::Boolean => helper(used, "checkBoolean"),
+		Type::Integer => helper(used, "checkInteger"),
+		Type::List(item_type) => format!(
+			"{}({})",
+			helper(used, "checkList"),
+			check_expression(schema, item_type, used)
+		),
+		Type::Map(key_type, value_type) => {
+			// Object keys are always strings
+			let key = match schema.resolve_type(key_type) {
+				Type::Integer => helper(used, "checkIntegerKey"),
+				_ => check_expression(schema, key_type, used),
+			};
+
+			format!(
+				"{}({}, {})",
+				helper(used, "checkMap"),
+				key,
+				check_expression(schema, value_type, used)
+			)
+		}
+		Type::Reference(id) => check_name(schema.get(*id)),
+	}
+}
+
+// Named after the whole path, `$` can't be in a declaration's name, so
+// `User.Status` (`check$User$Status`) can't collide with `UserStatus` or helpers
+fn check_name(declaration: &Declaration) -> String {
+	format!("check${}", declaration.path.join("$"))
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use parser::errors::Diagnostic;
+use semantic::ir::Declaration;
+
+// Splits identifier into lowercase words
+// (`user_name`, `userName`, `UserName` and `USER_NAME` are all ["user", "name"])
+pub fn split_words(name: &str) -> Vec<String> {
+	let chars: Vec<char> = name.chars().collect();
+	let mut words = Vec::<String>::new();
+	let mut current = String::new();
+
+	for (index, char) in chars.iter().enumerate() {
+		if *char == '_' || *char == '-' {
+			if !current.is_empty() {
+				words.push(std::mem::take(&mut current));
+			};
+			continue;
+		};
+
+		if char.is_uppercase() && !current.is_empty() {
+			let previous = chars[index - 1];
+			let next = chars.get(index + 1);
+
+			// `userName` -> user|Name, `HTTPServer` -> HTTP|Server
+			let is_boundary = previous.is_lowercase()
+				|| previous.is_ascii_digit()
+				|| (previous.is_uppercase() && next.is_some_and(|next| next.is_lowercase()));
+
+			if is_boundary {
+				words.push(std::mem::take(&mut current));
+			};
+		};
+
+		current.extend(char.to_lowercase());
+	}
+
+	if !current.is_empty() {
+		words.push(current);
+	};
+
+	words
+}
+
+pub fn to_snake_case(name: &str) -> String {
+	split_words(name).join("_")
+}
+
+pub fn to_pascal_case(name: &str) -> String {
+	split_words(name)
+		.iter()
+		.map(|word| capitalize(word))
+		.collect()
+}
+
+pub fn to_camel_case(name: &str) -> String {
+	let pascal_case = to_pascal_case(name);
+	let mut chars = pascal_case.chars();
+
+	match chars.next() {
+		Some(first) => first.to_lowercase().chain(chars).collect(),
+		None => pascal_case,
+	}
+}
+
+fn capitalize(word: &str) -> String {
+	let mut chars = word.chars();
+
+	match chars.next() {
+		Some(first) => first.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
+
+// Name for targets without namespaces: `User.Status` becomes `UserStatus`
+pub fn flat_name(declaration: &Declaration) -> String {
+	declaration
+		.path
+		.iter()
+		.map(|segment| to_pascal_case(segment))
+		.collect()
+}
+
+// Declarations which end up with the same generated name in targets without
+// namespaces (`User.Status` and `UserStatus` are both `UserStatus`), `name`
+// is the target's flattening function
+pub fn check_flat_names<'a>(
+	declarations: impl IntoIterator<Item = &'a Declaration>,
+	target: &str,
+	name: impl Fn(&Declaration) -> String,
+) -> Vec<Diagnostic> {
+	let mut diagnostics = Vec::new();
+	let mut first = HashMap::<String, &Declaration>::new();
+
+	for declaration in declarations {
+		let flat = name(declaration);
+
+		match first.get(&flat) {
+			Some(previous) => diagnostics.push(
+				Diagnostic::error(format!(
+					"`{}` and `{}` are both named `{}` in {}",
+					previous.qualified_name(),
+					declaration.qualified_name(),
+					flat,
+					target
+				))
+				.with_label(
+					declaration.name_span.clone(),
+					format!("also becomes `{}`", flat),
+				)
+				.with_label(previous.name_span.clone(), "first declared here"),
+			),
+			None => {
+				first.insert(flat, declaration);
+			}
+		};
+	}
+
+	diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn converts_case() {
+		assert_eq!(split_words("userName"), vec!["user", "name"]);
+		assert_eq!(split_words("HTTPServer_2"), vec!["http", "server", "2"]);
+		assert_eq!(to_snake_case("isRegistered"), "is_registered");
+		assert_eq!(to_pascal_case("REGISTERED"), "Registered");
+		assert_eq!(to_camel_case("const_variable"), "constVariable");
+	}
+
+	#[test]
+	fn reports_flat_name_collisions() {
+		let source = "interface User {\n\tenum Status { A: a; };\n};\nenum UserStatus { B: b; };\n";
+		let schema = analyze(source).unwrap();
+
+		let diagnostics = check_flat_names(schema.declarations(), "Go", flat_name);
+
+		assert_eq!(diagnostics.len(), 1);
+		assert_eq!(
+			diagnostics[0].message,
+			"`User.Status` and `UserStatus` are both named `UserStatus` in Go"
+		);
+		assert_eq!(&source[diagnostics[0].labels[0].span.clone()], "UserStatus");
+	}
+}
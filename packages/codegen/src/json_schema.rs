@@ -0,0 +1,379 @@
+use semantic::{
+	ir::{Constraint, Declaration, DeclarationKind, Format, Schema, Type},
+	ReservedWords,
+};
+use serde_json::{json, Map, Value};
+
+use crate::Generator;
+
+//
+// JSON Schema generator
+//
+// Emits a single draft 2020-12 document, where every top-level declaration
+// is a definition in `$defs`. Nested declarations are placed into their
+// parent's `$defs`, so `User.Status` is referenced as
+// `#/$defs/User/$defs/Status`.
+//
+// Inherited variables are copied into every interface, constraints become
+// validation keywords (`minLength`, `pattern`, `format`, ...) and doc
+// comments become `description`s. Unions become `anyOf`.
+pub struct JsonSchemaGenerator;
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+impl Generator for JsonSchemaGenerator {
+	fn name(&self) -> &'static str {
+		"json-schema"
+	}
+
+	fn extension(&self) -> &'static str {
+		"json"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		// Any name can be a JSON key
+		ReservedWords {
+			target: "JSON Schema",
+			declarations: &[],
+			members: &[],
+		}
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut definitions = Map::new();
+
+		for declaration in schema.roots() {
+			definitions.insert(
+				declaration.name.clone(),
+				nested_definition(schema, declaration),
+			);
+		}
+
+		let document = json!({
+			"$schema": DIALECT,
+			"$defs": definitions,
+		});
+
+		let mut output = serde_json::to_string_pretty(&document).unwrap();
+		output.push('\n');
+		output
+	}
+}
+
+// Definition with nested declarations in it's own `$defs`
+fn nested_definition(schema: &Schema, declaration: &Declaration) -> Value {
+	let mut object = definition(schema, declaration, &reference);
+
+	if !declaration.children.is_empty() {
+		let mut definitions = Map::new();
+
+		for child in schema.children(declaration) {
+			definitions.insert(child.name.clone(), nested_definition(schema, child));
+		}
+
+		object.insert("$defs".to_string(), Value::Object(definitions));
+	};
+
+	Value::Object(object)
+}
+
+// Schema of a single declaration, without it's nested declarations.
+// `reference` returns URI of a referenced declaration, it's shared with
+// other targets which embed JSON Schema (OpenAPI)
+pub fn definition(
+	schema: &Schema,
+	declaration: &Declaration,
+	reference: &dyn Fn(&Declaration) -> String,
+) -> Map<String, Value> {
+	let mut object = Map::new();
+
+	if let Some(docs) = &declaration.docs {
+		object.insert("description".to_string(), json!(docs));
+	};
+
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			let mut properties = Map::new();
+			let mut required = Vec::new();
+
+			for field in interface.fields.iter() {
+				let mut property = Map::new();
+
+				if let Some(docs) = &field.docs {
+					property.insert("description".to_string(), json!(docs));
+				};
+				property.extend(type_schema(schema, &field.field_type, reference));
+
+				for constraint in field.constraints.iter() {
+					let (keyword, value) = constraint_keyword(constraint);
+					property.insert(keyword.to_string(), value);
+				}
+
+				properties.insert(field.name.clone(), Value::Object(property));
+
+				if field.is_required {
+					required.push(json!(field.name));
+				};
+			}
+
+			object.insert("type".to_string(), json!("object"));
+			object.insert("properties".to_string(), Value::Object(properties));
+
+			if !required.is_empty() {
+				object.insert("required".to_string(), Value::Array(required));
+			};
+		}
+		DeclarationKind::Enum(enumerate) => {
+			let values: Vec<Value> = enumerate
+				.variants
+				.iter()
+				.map(|variant| json!(variant.value.as_ref().unwrap_or(&variant.name)))
+				.collect();
+
+			object.insert("type".to_string(), json!("string"));
+			object.insert("enum".to_string(), Value::Array(values));
+		}
+		DeclarationKind::Alias(alias) => {
+			object.extend(type_schema(schema, &alias.target, reference));
+		}
+		DeclarationKind::Union(union) => {
+			let members: Vec<Value> = union
+				.members
+				.iter()
+				.map(|member| json!({ "$ref": reference(schema.get(*member)) }))
+				.collect();
+
+			// Data of a subtype matches it's parent as well, so `oneOf` would reject it
+			object.insert("anyOf".to_string(), Value::Array(members));
+		}
+	};
+
+	object
+}
+
+pub fn type_schema(
+	schema: &Schema,
+	field_type: &Type,
+	reference: &dyn Fn(&Declaration) -> String,
+) -> Map<String, Value> {
+	let value = match field_type {
+		Type::String(None) => json!({ "type": "string" }),
+		Type::String(Some(value)) => json!({ "type": "string", "const": value }),
+		Type::Boolean => json!({ "type": "boolean" }),
+		Type::Integer => json!({ "type": "integer" }),
+		Type::List(item_type) => json!({
+			"type": "array",
+			"items": type_schema(schema, item_type, reference),
+		}),
+		// JSON object keys are always strings, so integer keys are only checked by pattern
+		Type::Map(key_type, value_type) => match schema.resolve_type(key_type) {
+			Type::Integer => json!({
+				"type": "object",
+				"propertyNames": { "pattern": "^-?[0-9]+$" },
+				"additionalProperties": type_schema(schema, value_type, reference),
+			}),
+			_ => json!({
+				"type": "object",
+				"additionalProperties": type_schema(schema, value_type, reference),
+			}),
+		},
+		Type::Reference(id) => json!({ "$ref": reference(schema.get(*id)) }),
+	};
+
+	match value {
+		Value::Object(object) => object,
+		_ => unreachable!(),
+	}
+}
+
+// JSON pointer to a declaration's definition
+pub fn reference(declaration: &Declaration) -> String {
+	format!("#/$defs/{}", declaration.path.join("/$defs/"))
+}
+
+fn constraint_keyword(constraint: &Constraint) -> (&'static str, Value) {
+	match constraint {
+		Constraint::MinLength(length) => ("minLength", json!(length)),
+		Constraint::MaxLength(length) => ("maxLength", json!(length)),
+		Constraint::Pattern(pattern) => ("pattern", json!(pattern)),
+		Constraint::Format(Format::Email) => ("format", json!("email")),
+		Constraint::Format(Format::Url) => ("format", json!("uri")),
+		Constraint::Format(Format::Uuid) => ("format", json!("uuid")),
+		Constraint::Minimum(value) => ("minimum", json!(value)),
+		Constraint::Maximum(value) => ("maximum", json!(value)),
+		Constraint::MinItems(count) => ("minItems", json!(count)),
+		Constraint::MaxItems(count) => ("maxItems", json!(count)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_definitions() {
+		let schema = analyze(
+			"
+			/// Identifier of a user
+			alias UserId = String;
+
+			interface User {
+				#format = uuid
+				required id: UserId;
+				/// Public name
+				optional username: String;
+				required kind: String { \"user\" };
+				#max_items = 10
+				optional tags: List<String>;
+				required status: User.Status;
+
+				enum Status {
+					Registered: registered;
+					Banned: banned;
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			JsonSchemaGenerator.generate(&schema),
+			r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "$defs": {
+    "UserId": {
+      "description": "Identifier of a user",
+      "type": "string"
+    },
+    "User": {
+      "type": "object",
+      "properties": {
+        "id": {
+          "$ref": "#/$defs/UserId",
+          "format": "uuid"
+        },
+        "username": {
+          "description": "Public name",
+          "type": "string"
+        },
+        "kind": {
+          "type": "string",
+          "const": "user"
+        },
+        "tags": {
+          "type": "array",
+          "items": {
+            "type": "string"
+          },
+          "maxItems": 10
+        },
+        "status": {
+          "$ref": "#/$defs/User/$defs/Status"
+        }
+      },
+      "required": [
+        "id",
+        "kind",
+        "status"
+      ],
+      "$defs": {
+        "Status": {
+          "type": "string",
+          "enum": [
+            "registered",
+            "banned"
+          ]
+        }
+      }
+    }
+  }
+}
+"##
+		);
+	}
+
+	#[test]
+	fn accepts_subtypes_in_unions() {
+		let schema = analyze(
+			"
+			interface User {
+				required id: String;
+			};
+
+			interface Admin extends User {
+				required level: Int;
+			};
+
+			union Anyone = Admin | User;
+			",
+		)
+		.unwrap();
+
+		let document: Value = serde_json::from_str(&JsonSchemaGenerator.generate(&schema)).unwrap();
+
+		// Admin data matches both members, which `oneOf` would reject
+		assert_eq!(
+			document["$defs"]["Anyone"],
+			json!({
+				"anyOf": [
+					{ "$ref": "#/$defs/Admin" },
+					{ "$ref": "#/$defs/User" }
+				]
+			})
+		);
+	}
+
+	#[test]
+	fn references_nested_declarations_aliases_and_maps() {
+		let schema = analyze(
+			"
+			interface User {
+				required type: User.enum;
+				required scores: Map<Int, Int>;
+				optional required: Contact;
+
+				enum enum {
+					Active: active;
+				};
+			};
+
+			union Account = User;
+
+			alias Contact = Account;
+			",
+		)
+		.unwrap();
+
+		let document: Value = serde_json::from_str(&JsonSchemaGenerator.generate(&schema)).unwrap();
+		let user = &document["$defs"]["User"];
+
+		assert_eq!(
+			user["properties"]["type"],
+			json!({ "$ref": "#/$defs/User/$defs/enum" })
+		);
+		assert_eq!(
+			user["$defs"]["enum"],
+			json!({ "type": "string", "enum": ["active"] })
+		);
+		// JSON keys are strings, so Int keys are checked by their pattern
+		assert_eq!(
+			user["properties"]["scores"],
+			json!({
+				"type": "object",
+				"propertyNames": { "pattern": "^-?[0-9]+$" },
+				"additionalProperties": { "type": "integer" }
+			})
+		);
+		assert_eq!(
+			user["properties"]["required"],
+			json!({ "$ref": "#/$defs/Contact" })
+		);
+		assert_eq!(user["required"], json!(["type", "scores"]));
+		assert_eq!(
+			document["$defs"]["Contact"],
+			json!({ "$ref": "#/$defs/Account" })
+		);
+	}
+}
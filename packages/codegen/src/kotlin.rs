@@ -0,0 +1,487 @@
+use semantic::{
+	ir::{Declaration, DeclarationKind, Field, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	helpers::{flat_name, to_camel_case, to_snake_case},
+	quote,
+	writer::Writer,
+	Generator,
+};
+
+//
+// Kotlin generator
+//
+// Interfaces become `@Serializable data class`es (kotlinx.serialization)
+// with every inherited variable copied in, because data classes can't be
+// extended. Enums become `enum class`es and nested declarations become
+// nested classes, so `User.Status` is a valid Kotlin type. Names are
+// converted to Kotlin conventions, original ones are kept with `@SerialName`.
+//
+// Kotlin only allows top-level type aliases, so aliases are flattened
+// (`UserId`). Unions become sealed interfaces implemented by their members.
+// They're encoded as the member itself, without kotlinx.serialization's
+// `type` discriminator, and decoded as the first member which fits, same
+// as in other targets.
+pub struct KotlinGenerator;
+
+// Hard keywords can only be used as names with backticks
+const KEYWORDS: &[&str] = &[
+	"as",
+	"break",
+	"class",
+	"continue",
+	"do",
+	"else",
+	"false",
+	"for",
+	"fun",
+	"if",
+	"in",
+	"interface",
+	"is",
+	"null",
+	"object",
+	"package",
+	"return",
+	"super",
+	"this",
+	"throw",
+	"true",
+	"try",
+	"typealias",
+	"typeof",
+	"val",
+	"var",
+	"when",
+	"while",
+];
+
+// Types used by generated code, keywords are escaped like any other name
+const RESERVED_TYPES: &[&str] = &[
+	"Any",
+	"Boolean",
+	"List",
+	"Long",
+	"Map",
+	"SerialName",
+	"Serializable",
+	"String",
+	"Unit",
+];
+
+impl Generator for KotlinGenerator {
+	fn name(&self) -> &'static str {
+		"kotlin"
+	}
+
+	fn extension(&self) -> &'static str {
+		"kt"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		ReservedWords {
+			target: "Kotlin",
+			declarations: RESERVED_TYPES,
+			// Properties and enum constants are escaped with backticks
+			members: &[],
+		}
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("    ");
+
+		let mut is_first = true;
+		let roots = schema
+			.roots()
+			.filter(|declaration| !matches!(declaration.kind, DeclarationKind::Alias(_)));
+
+		for declaration in roots {
+			if !is_first {
+				writer.blank_line();
+			};
+			is_first = false;
+
+			write_declaration(&mut writer, schema, declaration);
+		}
+
+		// Aliases can't be nested
+		for declaration in schema.declarations() {
+			if let DeclarationKind::Alias(alias) = &declaration.kind {
+				if !is_first {
+					writer.blank_line();
+				};
+				is_first = false;
+
+				write_docs(&mut writer, &declaration.docs);
+				writer.line(format!(
+					"typealias {} = {}",
+					flat_name(declaration),
+					type_name(schema, &alias.target)
+				));
+			};
+		}
+
+		let body = writer.finish();
+
+		let has_unions = body.contains("JsonContentPolymorphicSerializer");
+
+		let mut output = String::new();
+		if has_unions {
+			output.push_str("import kotlinx.serialization.DeserializationStrategy\n");
+		};
+		if body.contains("@SerialName") {
+			output.push_str("import kotlinx.serialization.SerialName\n");
+		};
+		output.push_str("import kotlinx.serialization.Serializable\n");
+		if has_unions {
+			output.push_str("import kotlinx.serialization.SerializationException\n");
+			output.push_str("import kotlinx.serialization.json.Json\n");
+			output.push_str("import kotlinx.serialization.json.JsonContentPolymorphicSerializer\n");
+			output.push_str("import kotlinx.serialization.json.JsonElement\n");
+		};
+		output.push('\n');
+		output.push_str(&body);
+		output
+	}
+}
+
+// Aliases are written separately, at the top level
+fn write_declaration(writer: &mut Writer, schema: &Schema, declaration: &Declaration) {
+	write_docs(writer, &declaration.docs);
+	if !matches!(declaration.kind, DeclarationKind::Union(_)) {
+		writer.line("@Serializable");
+	};
+
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			// Unions this interface is a member of
+			let unions: Vec<String> = schema
+				.declarations()
+				.iter()
+				.filter(|union| match &union.kind {
+					DeclarationKind::Union(union) => union.members.contains(&declaration.id),
+					_ => false,
+				})
+				.map(qualified_name)
+				.collect();
+
+			let mut suffix = if unions.is_empty() {
+				String::new()
+			} else {
+				format!(" : {}", unions.join(", "))
+			};
+
+			// Nested declarations go into the class body
+			let children: Vec<&Declaration> = schema
+				.children(declaration)
+				.filter(|child| !matches!(child.kind, DeclarationKind::Alias(_)))
+				.collect();
+
+			if !children.is_empty() {
+				suffix.push_str(" {");
+			};
+
+			// Data classes need at least one property
+			if interface.fields.is_empty() {
+				writer.line(format!("class {}{}", escape(&declaration.name), suffix));
+			} else {
+				writer.line(format!("data class {}(", escape(&declaration.name)));
+				writer.indent();
+
+				for field in interface.fields.iter() {
+					write_property(writer, schema, field);
+				}
+
+				writer.dedent();
+				writer.line(format!("){}", suffix));
+			};
+
+			if !children.is_empty() {
+				writer.indent();
+
+				for (index, child) in children.into_iter().enumerate() {
+					if index > 0 {
+						writer.blank_line();
+					};
+
+					write_declaration(writer, schema, child);
+				}
+
+				writer.dedent();
+				writer.line("}");
+			};
+		}
+		DeclarationKind::Enum(enumerate) => {
+			writer.line(format!("enum class {} {{", escape(&declaration.name)));
+			writer.indent();
+
+			for variant in enumerate.variants.iter() {
+				write_docs(writer, &variant.docs);
+
+				let value = variant.value.as_ref().unwrap_or(&variant.name);
+				let name = to_snake_case(&variant.name).to_uppercase();
+
+				if &name != value {
+					writer.line(format!("@SerialName({})", quote(value)));
+				};
+				writer.line(format!("{},", escape(&name)));
+			}
+
+			writer.dedent();
+			writer.line("}");
+		}
+		DeclarationKind::Union(union) => {
+			let members: Vec<String> = union
+				.members
+				.iter()
+				.map(|member| format!("{}.serializer()", qualified_name(schema.get(*member))))
+				.collect();
+
+			writer.line(format!(
+				"@Serializable(with = {}.Serializer::class)",
+				escape(&declaration.name)
+			));
+			writer.line(format!("sealed interface {} {{", escape(&declaration.name)));
+			writer.indent();
+			writer.line(format!(
+				"object Serializer : JsonContentPolymorphicSerializer<{0}>({0}::class) {{",
+				qualified_name(declaration)
+			));
+			writer.indent();
+			writer.line(format!(
+				"override fun selectDeserializer(element: JsonElement): DeserializationStrategy<{}> =",
+				qualified_name(declaration)
+			));
+			writer.indent();
+			writer.line(format!(
+				"listOf<DeserializationStrategy<{}>>({})",
+				qualified_name(declaration),
+				members.join(", ")
+			));
+			writer.indent();
+			writer.line(
+				".firstOrNull { member -> runCatching { Json.decodeFromJsonElement(member, element) }.isSuccess }",
+			);
+			writer.line(format!(
+				"?: throw SerializationException({})",
+				quote(&format!(
+					"no member of {} matches",
+					declaration.qualified_name()
+				))
+			));
+			writer.dedent();
+			writer.dedent();
+			writer.dedent();
+			writer.line("}");
+			writer.dedent();
+			writer.line("}");
+		}
+		DeclarationKind::Alias(_) => {}
+	};
+}
+
+// Example:
+// @SerialName("user_name")
+// val userName: String? = null,
+fn write_property(writer: &mut Writer, schema: &Schema, field: &Field) {
+	write_docs(writer, &field.docs);
+
+	let name = to_camel_case(&field.name);
+	if name != field.name {
+		writer.line(format!("@SerialName({})", quote(&field.name)));
+	};
+
+	let field_type = type_name(schema, &field.field_type);
+
+	if field.is_required {
+		writer.line(format!("val {}: {},", escape(&name), field_type));
+	} else {
+		writer.line(format!("val {}: {}? = null,", escape(&name), field_type));
+	};
+}
+
+pub fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match field_type {
+		Type::String(_) => "String".to_string(),
+		Type::Boolean => "Boolean".to_string(),
+		Type::Integer => "Long".to_string(),
+		Type::List(item_type) => format!("List<{}>", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => format!(
+			"Map<{}, {}>",
+			type_name(schema, key_type),
+			type_name(schema, value_type)
+		),
+		Type::Reference(id) => {
+			let declaration = schema.get(*id);
+
+			match declaration.kind {
+				DeclarationKind::Alias(_) => flat_name(declaration),
+				_ => qualified_name(declaration),
+			}
+		}
+	}
+}
+
+// Dotted path to a nested class, escaping every segment
+fn qualified_name(declaration: &Declaration) -> String {
+	declaration
+		.path
+		.iter()
+		.map(|name| escape(name))
+		.collect::<Vec<String>>()
+		.join(".")
+}
+
+fn escape(name: &str) -> String {
+	if KEYWORDS.contains(&name) {
+		format!("`{}`", name)
+	} else {
+		name.to_string()
+	}
+}
+
+fn write_docs(writer: &mut Writer, docs: &Option<String>) {
+	if docs.is_some() {
+		writer.line("/**");
+		writer.docs(docs, " * ");
+		writer.line(" */");
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_data_classes_and_nested_enums() {
+		let schema = analyze(
+			"
+			/// Registered user
+			interface User {
+				required user_name: String;
+				optional tags: List<String>;
+				required status: User.Status;
+
+				enum Status {
+					Registered: registered;
+					BANNED: BANNED;
+				};
+			};
+
+			interface Admin extends User {
+				optional level: Int;
+				required object: Boolean;
+			};
+
+			union Account = Admin | User;
+			alias Accounts = List<Account>;
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			KotlinGenerator.generate(&schema),
+			r#"import kotlinx.serialization.DeserializationStrategy
+import kotlinx.serialization.SerialName
+import kotlinx.serialization.Serializable
+import kotlinx.serialization.SerializationException
+import kotlinx.serialization.json.Json
+import kotlinx.serialization.json.JsonContentPolymorphicSerializer
+import kotlinx.serialization.json.JsonElement
+
+/**
+ * Registered user
+ */
+@Serializable
+data class User(
+    @SerialName("user_name")
+    val userName: String,
+    val tags: List<String>? = null,
+    val status: User.Status,
+) : Account {
+    @Serializable
+    enum class Status {
+        @SerialName("registered")
+        REGISTERED,
+        BANNED,
+    }
+}
+
+@Serializable
+data class Admin(
+    @SerialName("user_name")
+    val userName: String,
+    val tags: List<String>? = null,
+    val status: User.Status,
+    val level: Long? = null,
+    val `object`: Boolean,
+) : Account
+
+@Serializable(with = Account.Serializer::class)
+sealed interface Account {
+    object Serializer : JsonContentPolymorphicSerializer<Account>(Account::class) {
+        override fun selectDeserializer(element: JsonElement): DeserializationStrategy<Account> =
+            listOf<DeserializationStrategy<Account>>(Admin.serializer(), User.serializer())
+                .firstOrNull { member -> runCatching { Json.decodeFromJsonElement(member, element) }.isSuccess }
+                ?: throw SerializationException("no member of Account matches")
+    }
+}
+
+typealias Accounts = List<Account>
+"#
+		);
+	}
+
+	#[test]
+	fn escapes_keyword_class_names() {
+		let schema = analyze(
+			"
+			interface package {
+				optional return: package.object;
+
+				interface object {
+					required value: Int;
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		let output = KotlinGenerator.generate(&schema);
+
+		assert!(output
+			.contains("data class `package`(\n    val `return`: `package`.`object`? = null,\n) {"));
+		assert!(output.contains("    data class `object`(\n"));
+	}
+
+	#[test]
+	fn nests_declarations_and_aliases_unions() {
+		let schema = analyze(
+			"
+			interface User {
+				required status: User.Status;
+				required scores: Map<Int, Int>;
+				optional default: Contact;
+
+				enum Status {
+					Active: active;
+				};
+			};
+
+			union Account = User;
+
+			alias Contact = Account;
+			",
+		)
+		.unwrap();
+
+		let output = KotlinGenerator.generate(&schema);
+
+		assert!(output.contains("data class User(\n    val status: User.Status,\n    val scores: Map<Long, Long>,\n    val default: Contact? = null,\n) : Account {\n    @Serializable\n    enum class Status {"));
+		assert!(output.contains("typealias Contact = Account\n"));
+	}
+}
@@ -1,13 +1,122 @@
-pub fn codegen() -> String {
-	"codegen".into()
+use lock::Lock;
+use parser::errors::Diagnostic;
+use semantic::{ir::Schema, ReservedWords};
+
+pub mod csharp;
+pub mod dart;
+pub mod go;
+pub mod graphql;
+pub mod guards;
+pub mod helpers;
+pub mod json_schema;
+pub mod kotlin;
+pub mod lock;
+pub mod openapi;
+pub mod proto;
+pub mod python;
+pub mod rust;
+pub mod swift;
+pub mod typescript;
+pub mod validators;
+pub mod writer;
+pub mod yaml;
+pub mod zod;
+
+//
+// Generator
+//
+// Every output target implements this trait. Generators only receive
+// Schema IR, so references are already resolved and inherited variables
+// are already copied into interfaces.
+pub trait Generator {
+	// Name used to select this generator (`typm generate --target typescript`)
+	fn name(&self) -> &'static str;
+
+	// Extension of generated files, without the leading dot
+	fn extension(&self) -> &'static str;
+
+	// Names which would produce invalid code, checked by semantic validation
+	fn reserved_words(&self) -> ReservedWords;
+
+	// Problems specific to this target, e.g. types it can't represent
+	fn check(&self, _schema: &Schema) -> Vec<Diagnostic> {
+		Vec::new()
+	}
+
+	fn generate(&self, schema: &Schema) -> String;
+
+	// Whether generated code depends on values kept in a lock file
+	fn uses_lock(&self) -> bool {
+		false
+	}
+
+	// Copy of this generator placing code into the given namespace
+	// (`typm generate --namespace`), `None` for targets without one
+	fn with_namespace(&self, _namespace: &str) -> Option<Box<dyn Generator>> {
+		Option::None
+	}
+
+	// Same as `generate`, but reads previously assigned values (protobuf
+	// field numbers) from the lock and records new ones in it
+	fn generate_locked(&self, schema: &Schema, _lock: &mut Lock) -> String {
+		self.generate(schema)
+	}
 }
 
-#[cfg(test)]
-mod tests {
-	use super::*;
+pub fn generators() -> Vec<Box<dyn Generator>> {
+	vec![
+		Box::new(typescript::TypeScriptGenerator {
+			mode: typescript::TypeScriptMode::Types,
+		}),
+		Box::new(typescript::TypeScriptGenerator {
+			mode: typescript::TypeScriptMode::Guards,
+		}),
+		Box::new(rust::RustGenerator),
+		Box::new(dart::DartGenerator),
+		Box::new(json_schema::JsonSchemaGenerator),
+		Box::new(openapi::OpenApiGenerator {
+			format: openapi::OpenApiFormat::Yaml,
+		}),
+		Box::new(openapi::OpenApiGenerator {
+			format: openapi::OpenApiFormat::Json,
+		}),
+		Box::new(proto::ProtoGenerator),
+		Box::new(graphql::GraphQlGenerator),
+		Box::new(python::PythonGenerator {
+			style: python::PythonStyle::Pydantic,
+		}),
+		Box::new(python::PythonGenerator {
+			style: python::PythonStyle::Dataclass,
+		}),
+		Box::new(go::GoGenerator),
+		Box::new(kotlin::KotlinGenerator),
+		Box::new(swift::SwiftGenerator),
+		Box::new(csharp::CSharpGenerator::default()),
+		Box::new(zod::ZodGenerator),
+	]
+}
 
-	#[test]
-	fn it_works() {
-		assert_eq!(codegen(), "codegen".to_string());
+pub fn get_generator(name: &str) -> Option<Box<dyn Generator>> {
+	generators()
+		.into_iter()
+		.find(|generator| generator.name() == name)
+}
+
+// Double-quoted string literal, escaped the way most C-like languages expect
+pub fn quote(value: &str) -> String {
+	let mut result = String::from("\"");
+
+	for char in value.chars() {
+		match char {
+			'"' => result.push_str("\\\""),
+			'\\' => result.push_str("\\\\"),
+			'\n' => result.push_str("\\n"),
+			'\r' => result.push_str("\\r"),
+			'\t' => result.push_str("\\t"),
+			char => result.push(char),
+		};
 	}
+
+	result.push('"');
+	result
 }
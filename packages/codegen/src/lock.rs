@@ -0,0 +1,46 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+//
+// Lock
+//
+// Values which have to stay the same between runs (protobuf field numbers),
+// kept in a lock file next to the .typm file. Entries are grouped by target
+// and declaration:
+// { "proto": { "User": { "id": 1, "username": 2 } } }
+//
+// Members removed from the schema stay in the lock, so that their numbers
+// are never given to anything else.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Lock {
+	targets: BTreeMap<String, BTreeMap<String, BTreeMap<String, u32>>>,
+}
+
+impl Lock {
+	pub fn parse(source: &str) -> Result<Lock, String> {
+		serde_json::from_str(source).map_err(|error| error.to_string())
+	}
+
+	pub fn to_json(&self) -> String {
+		let mut output = serde_json::to_string_pretty(self).unwrap();
+		output.push('\n');
+		output
+	}
+
+	// Recorded numbers of declaration's members
+	pub fn numbers(&self, target: &str, declaration: &str) -> Option<&BTreeMap<String, u32>> {
+		self.targets
+			.get(target)
+			.and_then(|declarations| declarations.get(declaration))
+	}
+
+	pub fn numbers_mut(&mut self, target: &str, declaration: &str) -> &mut BTreeMap<String, u32> {
+		self.targets
+			.entry(target.to_string())
+			.or_default()
+			.entry(declaration.to_string())
+			.or_default()
+	}
+}
@@ -0,0 +1,240 @@
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Declaration, Schema},
+	ReservedWords,
+};
+use serde_json::{json, Map, Value};
+
+use crate::{json_schema::definition, yaml::to_yaml, Generator};
+
+//
+// OpenAPI generator
+//
+// Emits an OpenAPI 3.1 document with every declaration in
+// `components.schemas`. Nested declarations are named by their
+// fully-qualified name (`User.Status`), schemas themselves are the same
+// as in JSON Schema target, because OpenAPI 3.1 uses JSON Schema 2020-12.
+//
+// Only `components` section carries generated information, so it can be
+// merged into a hand-written spec as is.
+pub struct OpenApiGenerator {
+	pub format: OpenApiFormat,
+}
+
+pub enum OpenApiFormat {
+	Yaml,
+	Json,
+}
+
+const VERSION: &str = "3.1.0";
+
+impl Generator for OpenApiGenerator {
+	fn name(&self) -> &'static str {
+		match self.format {
+			OpenApiFormat::Yaml => "openapi",
+			OpenApiFormat::Json => "openapi-json",
+		}
+	}
+
+	fn extension(&self) -> &'static str {
+		match self.format {
+			OpenApiFormat::Yaml => "yaml",
+			OpenApiFormat::Json => "json",
+		}
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		ReservedWords {
+			target: "OpenAPI",
+			declarations: &[],
+			members: &[],
+		}
+	}
+
+	// Component names are limited to `^[a-zA-Z0-9._-]+$`
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		schema
+			.declarations()
+			.iter()
+			.filter(|declaration| {
+				!declaration
+					.qualified_name()
+					.chars()
+					.all(|char| char.is_ascii_alphanumeric() || "._-".contains(char))
+			})
+			.map(|declaration| {
+				Diagnostic::error(format!(
+					"`{}` can't be an OpenAPI component name, only ASCII letters, digits, `.`, `_` and `-` are allowed",
+					declaration.qualified_name()
+				))
+				.with_label(declaration.span.clone(), "not a valid component name")
+			})
+			.collect()
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut schemas = Map::new();
+
+		for declaration in schema.declarations() {
+			schemas.insert(
+				declaration.qualified_name(),
+				Value::Object(definition(schema, declaration, &reference)),
+			);
+		}
+
+		// `info` is required for the document to be valid on it's own
+		let document = json!({
+			"openapi": VERSION,
+			"info": {
+				"title": "Schemas",
+				"version": "0.0.0",
+			},
+			"components": {
+				"schemas": schemas,
+			},
+		});
+
+		match self.format {
+			OpenApiFormat::Yaml => to_yaml(&document),
+			OpenApiFormat::Json => {
+				let mut output = serde_json::to_string_pretty(&document).unwrap();
+				output.push('\n');
+				output
+			}
+		}
+	}
+}
+
+pub fn reference(declaration: &Declaration) -> String {
+	format!("#/components/schemas/{}", declaration.qualified_name())
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_yaml_components() {
+		let schema = analyze(
+			"
+			/// Registered user
+			/// of the forum
+			interface User {
+				/// Public name
+				#pattern = \"^[a-z]+: .*$\"
+				required username: String;
+				required status: User.Status;
+				optional flags: List<Boolean>;
+
+				enum Status {
+					Registered: registered;
+					Off: \"off\";
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			OpenApiGenerator {
+				format: OpenApiFormat::Yaml
+			}
+			.generate(&schema),
+			r##"openapi: "3.1.0"
+info:
+  title: Schemas
+  version: "0.0.0"
+components:
+  schemas:
+    User:
+      description: "Registered user\nof the forum"
+      type: object
+      properties:
+        username:
+          description: Public name
+          type: string
+          pattern: "^[a-z]+: .*$"
+        status:
+          $ref: "#/components/schemas/User.Status"
+        flags:
+          type: array
+          items:
+            type: boolean
+      required:
+        - username
+        - status
+    User.Status:
+      type: string
+      enum:
+        - registered
+        - "off"
+"##
+		);
+	}
+
+	#[test]
+	fn references_nested_components_aliases_and_maps() {
+		let schema = analyze(
+			"
+			interface User {
+				required type: User.enum;
+				required scores: Map<Int, Int>;
+				optional required: Contact;
+
+				enum enum {
+					Active: active;
+				};
+			};
+
+			union Account = User;
+
+			alias Contact = Account;
+			",
+		)
+		.unwrap();
+
+		let generator = OpenApiGenerator {
+			format: OpenApiFormat::Json,
+		};
+		let document: Value = serde_json::from_str(&generator.generate(&schema)).unwrap();
+		let schemas = &document["components"]["schemas"];
+
+		assert_eq!(
+			schemas["User"]["properties"]["type"],
+			json!({ "$ref": "#/components/schemas/User.enum" })
+		);
+		assert_eq!(
+			schemas["User.enum"],
+			json!({ "type": "string", "enum": ["active"] })
+		);
+		assert_eq!(
+			schemas["User"]["properties"]["scores"]["propertyNames"],
+			json!({ "pattern": "^-?[0-9]+$" })
+		);
+		assert_eq!(
+			schemas["Contact"],
+			json!({ "$ref": "#/components/schemas/Account" })
+		);
+		assert!(generator.check(&schema).is_empty());
+	}
+
+	#[test]
+	fn reports_invalid_component_names() {
+		let schema = analyze("interface Ñandú { required id: String; };").unwrap();
+
+		let messages: Vec<String> = OpenApiGenerator {
+			format: OpenApiFormat::Yaml,
+		}
+		.check(&schema)
+		.into_iter()
+		.map(|diagnostic| diagnostic.message)
+		.collect();
+
+		assert_eq!(
+			messages,
+			vec!["`Ñandú` can't be an OpenAPI component name, only ASCII letters, digits, `.`, `_` and `-` are allowed"]
+		);
+	}
+}
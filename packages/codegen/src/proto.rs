@@ -0,0 +1,613 @@
+use std::{collections::BTreeSet, collections::HashMap, ops::RangeInclusive};
+
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Attribute, Declaration, DeclarationKind, Field, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	helpers::{flat_name, to_camel_case, to_snake_case},
+	lock::Lock,
+	quote,
+	writer::Writer,
+	Generator,
+};
+
+//
+// Protocol Buffers generator
+//
+// Interfaces become proto3 messages (with inherited variables copied in),
+// enums become enums with `<NAME>_UNSPECIFIED = 0` value, unions become
+// messages with a single `oneof` and nested declarations become nested
+// messages and enums. Protobuf has no aliases,
+// so they're replaced with their types.
+//
+// Field and enum value numbers never change once assigned: explicit
+// `#proto_number = 3` attribute wins, otherwise the number is taken from
+// the lock file, otherwise the next unused one is given and recorded.
+// Numbers of removed members are emitted as `reserved`.
+pub struct ProtoGenerator;
+
+// Lock file section
+const LOCK_TARGET: &str = "proto";
+
+const NUMBER_ATTRIBUTE: &str = "proto_number";
+const MAX_NUMBER: u32 = 536_870_911;
+// Reserved by protobuf itself
+const IMPLEMENTATION_NUMBERS: RangeInclusive<u32> = 19_000..=19_999;
+
+// Scalar types, which can't be used as message names
+const RESERVED_WORDS: &[&str] = &[
+	"double", "float", "int32", "int64", "uint32", "uint64", "sint32", "sint64", "fixed32",
+	"fixed64", "sfixed32", "sfixed64", "bool", "string", "bytes",
+];
+
+// Keywords starting a statement in a message body, a field type named after
+// one of them is written fully qualified (`.message`) to stay a type
+const KEYWORDS: &[&str] = &[
+	"enum",
+	"extend",
+	"extensions",
+	"group",
+	"map",
+	"message",
+	"oneof",
+	"option",
+	"optional",
+	"repeated",
+	"required",
+	"reserved",
+];
+
+impl Generator for ProtoGenerator {
+	fn name(&self) -> &'static str {
+		"proto"
+	}
+
+	fn extension(&self) -> &'static str {
+		"proto"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		ReservedWords {
+			target: "Protocol Buffers",
+			declarations: RESERVED_WORDS,
+			members: &[],
+		}
+	}
+
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		let mut diagnostics = Vec::new();
+
+		for declaration in schema.declarations() {
+			match &declaration.kind {
+				DeclarationKind::Interface(interface) => {
+					let mut numbers = HashMap::<u32, &Field>::new();
+
+					for field in interface.fields.iter() {
+						// Inherited variables are reported on their own interface
+						let is_own = field.inherited_from.is_none();
+
+						if is_own && is_nested_collection(schema, &field.field_type) {
+							diagnostics.push(
+								Diagnostic::error(format!(
+									"variable `{}` of `{}` can't be represented in protobuf",
+									field.name,
+									declaration.qualified_name()
+								))
+								.with_label(
+									field.span.clone(),
+									"nested lists and maps aren't supported",
+								),
+							);
+						};
+
+						let number = match explicit_number(field.attribute(NUMBER_ATTRIBUTE)) {
+							Ok(Some(number)) => number,
+							Ok(None) => continue,
+							Err(message) => {
+								if is_own {
+									diagnostics.push(
+										Diagnostic::error(message)
+											.with_label(field.span.clone(), "invalid field number"),
+									);
+								};
+								continue;
+							}
+						};
+
+						match numbers.get(&number) {
+							Some(first) if is_own => diagnostics.push(
+								Diagnostic::error(format!(
+									"field number {} is used more than once in `{}`",
+									number,
+									declaration.qualified_name()
+								))
+								.with_label(field.span.clone(), "reused here")
+								.with_label(first.span.clone(), "first used here"),
+							),
+							Some(_) => {}
+							None => {
+								numbers.insert(number, field);
+							}
+						};
+					}
+				}
+				DeclarationKind::Enum(enumerate) => {
+					let mut numbers = HashMap::<u32, core::ops::Range<usize>>::new();
+
+					for variant in enumerate.variants.iter() {
+						let number = match explicit_number(variant.attribute(NUMBER_ATTRIBUTE)) {
+							Ok(Some(number)) => number,
+							Ok(None) => continue,
+							Err(message) => {
+								diagnostics.push(
+									Diagnostic::error(message)
+										.with_label(variant.span.clone(), "invalid value number"),
+								);
+								continue;
+							}
+						};
+
+						match numbers.get(&number) {
+							Some(first) => diagnostics.push(
+								Diagnostic::error(format!(
+									"value number {} is used more than once in `{}`",
+									number,
+									declaration.qualified_name()
+								))
+								.with_label(variant.span.clone(), "reused here")
+								.with_label(first.clone(), "first used here"),
+							),
+							None => {
+								numbers.insert(number, variant.span.clone());
+							}
+						};
+					}
+				}
+				DeclarationKind::Alias(_) | DeclarationKind::Union(_) => {}
+			};
+		}
+
+		diagnostics
+	}
+
+	fn uses_lock(&self) -> bool {
+		true
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		self.generate_locked(schema, &mut Lock::default())
+	}
+
+	fn generate_locked(&self, schema: &Schema, lock: &mut Lock) -> String {
+		let mut writer = Writer::new("  ");
+
+		writer.line("syntax = \"proto3\";");
+
+		for declaration in schema.roots() {
+			if let DeclarationKind::Alias(_) = declaration.kind {
+				continue;
+			};
+
+			writer.blank_line();
+			write_declaration(&mut writer, schema, lock, declaration);
+		}
+
+		writer.finish()
+	}
+}
+
+fn write_declaration(
+	writer: &mut Writer,
+	schema: &Schema,
+	lock: &mut Lock,
+	declaration: &Declaration,
+) {
+	writer.docs(&declaration.docs, "// ");
+
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			writer.line(format!("message {} {{", declaration.name));
+			writer.indent();
+
+			let members: Vec<(&str, Option<u32>)> = interface
+				.fields
+				.iter()
+				.map(|field| {
+					let number = explicit_number(field.attribute(NUMBER_ATTRIBUTE));
+					(field.name.as_str(), number.ok().flatten())
+				})
+				.collect();
+			let (numbers, removed) = assign_numbers(lock, &declaration.qualified_name(), &members);
+
+			for (field, number) in interface.fields.iter().zip(numbers) {
+				writer.docs(&field.docs, "// ");
+				writer.line(field_line(schema, field, number));
+			}
+
+			write_reserved(writer, &removed);
+
+			// Nested declarations
+			for child in schema.children(declaration) {
+				if let DeclarationKind::Alias(_) = child.kind {
+					continue;
+				};
+
+				writer.blank_line();
+				write_declaration(writer, schema, lock, child);
+			}
+
+			writer.dedent();
+			writer.line("}");
+		}
+		DeclarationKind::Enum(enumerate) => {
+			// Enum values share their parent's scope, so they're prefixed
+			let prefix = to_snake_case(&declaration.name).to_uppercase();
+
+			writer.line(format!("enum {} {{", declaration.name));
+			writer.indent();
+			writer.line(format!("{}_UNSPECIFIED = 0;", prefix));
+
+			let members: Vec<(&str, Option<u32>)> = enumerate
+				.variants
+				.iter()
+				.map(|variant| {
+					let number = explicit_number(variant.attribute(NUMBER_ATTRIBUTE));
+					(variant.name.as_str(), number.ok().flatten())
+				})
+				.collect();
+			let (numbers, removed) = assign_numbers(lock, &declaration.qualified_name(), &members);
+
+			for (variant, number) in enumerate.variants.iter().zip(numbers) {
+				writer.docs(&variant.docs, "// ");
+				writer.line(format!(
+					"{}_{} = {};",
+					prefix,
+					to_snake_case(&variant.name).to_uppercase(),
+					number
+				));
+			}
+
+			write_reserved(writer, &removed);
+
+			writer.dedent();
+			writer.line("}");
+		}
+		DeclarationKind::Union(union) => {
+			writer.line(format!("message {} {{", declaration.name));
+			writer.indent();
+			writer.line("oneof value {");
+			writer.indent();
+
+			let names: Vec<String> = union
+				.members
+				.iter()
+				.map(|member| schema.get(*member).qualified_name())
+				.collect();
+			let members: Vec<(&str, Option<u32>)> = names
+				.iter()
+				.map(|name| (name.as_str(), Option::None))
+				.collect();
+			let (numbers, removed) = assign_numbers(lock, &declaration.qualified_name(), &members);
+
+			for (member, number) in union.members.iter().zip(numbers) {
+				let member = schema.get(*member);
+
+				writer.line(format!(
+					"{} {} = {};",
+					reference_name(member),
+					to_snake_case(&flat_name(member)),
+					number
+				));
+			}
+
+			writer.dedent();
+			writer.line("}");
+
+			write_reserved(writer, &removed);
+
+			writer.dedent();
+			writer.line("}");
+		}
+		DeclarationKind::Alias(_) => {}
+	};
+}
+
+fn field_line(schema: &Schema, field: &Field, number: u32) -> String {
+	let name = to_snake_case(&field.name);
+
+	// Repeated and map fields can't be optional
+	let (label, field_type) = match schema.resolve_type(&field.field_type) {
+		Type::List(item_type) => ("repeated ", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => (
+			"",
+			format!(
+				"map<{}, {}>",
+				type_name(schema, key_type),
+				type_name(schema, value_type)
+			),
+		),
+		_ if field.is_required => ("", type_name(schema, &field.field_type)),
+		_ => ("optional ", type_name(schema, &field.field_type)),
+	};
+
+	// JSON mapping uses lowerCamelCase field names by default
+	let options = if to_camel_case(&name) != field.name {
+		format!(" [json_name = {}]", quote(&field.name))
+	} else {
+		String::new()
+	};
+
+	format!("{}{} {} = {}{};", label, field_type, name, number, options)
+}
+
+pub fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match schema.resolve_type(field_type) {
+		Type::String(_) => "string".to_string(),
+		Type::Boolean => "bool".to_string(),
+		Type::Integer => "int64".to_string(),
+		Type::Reference(id) => reference_name(schema.get(*id)),
+		Type::List(_) | Type::Map(_, _) => {
+			unreachable!("nested lists and maps are reported by check")
+		}
+	}
+}
+
+fn reference_name(declaration: &Declaration) -> String {
+	if KEYWORDS.contains(&declaration.path[0].as_str()) {
+		format!(".{}", declaration.qualified_name())
+	} else {
+		declaration.qualified_name()
+	}
+}
+
+fn is_nested_collection(schema: &Schema, field_type: &Type) -> bool {
+	match schema.resolve_type(field_type) {
+		Type::List(item_type) | Type::Map(_, item_type) => matches!(
+			schema.resolve_type(item_type),
+			Type::List(_) | Type::Map(_, _)
+		),
+		_ => false,
+	}
+}
+
+// Number from `#proto_number` attribute, if it's present
+fn explicit_number(attribute: Option<&Attribute>) -> Result<Option<u32>, String> {
+	let attribute = match attribute {
+		Some(attribute) => attribute,
+		None => return Ok(Option::None),
+	};
+
+	let value = attribute.value.as_deref().unwrap_or_default();
+
+	match value.parse::<u32>() {
+		Ok(number) if IMPLEMENTATION_NUMBERS.contains(&number) => Err(format!(
+			"`{}` can't be in {}..={}, these numbers are reserved by protobuf",
+			NUMBER_ATTRIBUTE,
+			IMPLEMENTATION_NUMBERS.start(),
+			IMPLEMENTATION_NUMBERS.end()
+		)),
+		Ok(number) if (1..=MAX_NUMBER).contains(&number) => Ok(Option::Some(number)),
+		_ => Err(format!(
+			"invalid `{}` value `{}`, expected an integer from 1 to {}",
+			NUMBER_ATTRIBUTE, value, MAX_NUMBER
+		)),
+	}
+}
+
+//
+// Numbers assignment
+//
+// Explicit numbers go first, then the ones recorded in the lock (if they
+// weren't taken by explicit ones) and then new members get numbers after
+// the greatest number ever recorded. Returns numbers of members and members,
+// which were recorded but don't exist anymore.
+fn assign_numbers(
+	lock: &mut Lock,
+	declaration: &str,
+	members: &[(&str, Option<u32>)],
+) -> (Vec<u32>, Vec<(String, u32)>) {
+	let recorded = lock.numbers_mut(LOCK_TARGET, declaration);
+
+	let mut taken: BTreeSet<u32> = members.iter().filter_map(|(_, number)| *number).collect();
+	let mut numbers = vec![0; members.len()];
+	let mut pending = Vec::new();
+
+	for (index, (name, explicit)) in members.iter().enumerate() {
+		let number = match explicit {
+			Some(number) => Option::Some(*number),
+			None => recorded
+				.get(*name)
+				.copied()
+				.filter(|number| !taken.contains(number)),
+		};
+
+		match number {
+			Some(number) => {
+				taken.insert(number);
+				numbers[index] = number;
+			}
+			None => pending.push(index),
+		};
+	}
+
+	let mut next = recorded
+		.values()
+		.chain(taken.iter())
+		.max()
+		.map_or(1, |number| number + 1);
+
+	for index in pending {
+		while taken.contains(&next) || IMPLEMENTATION_NUMBERS.contains(&next) {
+			next += 1;
+		}
+
+		numbers[index] = next;
+		taken.insert(next);
+		next += 1;
+	}
+
+	for ((name, _), number) in members.iter().zip(numbers.iter()) {
+		recorded.insert(name.to_string(), *number);
+	}
+
+	let removed = recorded
+		.iter()
+		.filter(|(name, number)| {
+			!members.iter().any(|(member, _)| member == name) && !taken.contains(number)
+		})
+		.map(|(name, number)| (name.clone(), *number))
+		.collect();
+
+	(numbers, removed)
+}
+
+fn write_reserved(writer: &mut Writer, removed: &[(String, u32)]) {
+	if removed.is_empty() {
+		return;
+	};
+
+	let numbers: Vec<String> = removed
+		.iter()
+		.map(|(_, number)| number.to_string())
+		.collect();
+	let names: Vec<String> = removed.iter().map(|(name, _)| quote(name)).collect();
+
+	writer.blank_line();
+	writer.line(format!("reserved {};", numbers.join(", ")));
+	writer.line(format!("reserved {};", names.join(", ")));
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_messages_with_stable_numbers() {
+		let schema = analyze(
+			"
+			alias Tags = List<String>;
+
+			/// Registered user
+			interface User {
+				required id: String;
+				optional user_name: String;
+				#proto_number = 10
+				required tags: Tags;
+				required scores: Map<String, Int>;
+				required status: User.Status;
+
+				enum Status {
+					Registered: registered;
+					Banned: banned;
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		let mut lock = Lock::parse(
+			r#"{
+				"proto": {
+					"User": { "id": 1, "email": 2, "status": 3 },
+					"User.Status": { "Banned": 2 }
+				}
+			}"#,
+		)
+		.unwrap();
+
+		assert_eq!(
+			ProtoGenerator.generate_locked(&schema, &mut lock),
+			r#"syntax = "proto3";
+
+// Registered user
+message User {
+  string id = 1;
+  optional string user_name = 11 [json_name = "user_name"];
+  repeated string tags = 10;
+  map<string, int64> scores = 12;
+  User.Status status = 3;
+
+  reserved 2;
+  reserved "email";
+
+  enum Status {
+    STATUS_UNSPECIFIED = 0;
+    STATUS_REGISTERED = 3;
+    STATUS_BANNED = 2;
+  }
+}
+"#
+		);
+
+		assert_eq!(
+			lock.numbers(LOCK_TARGET, "User").unwrap().get("user_name"),
+			Some(&11)
+		);
+	}
+
+	#[test]
+	fn reports_unsupported_types_and_numbers() {
+		let schema = analyze(
+			"
+			interface Matrix {
+				required rows: List<List<Int>>;
+				#proto_number = 19500
+				required width: Int;
+				#proto_number = 1
+				required height: Int;
+				#proto_number = 1
+				required depth: Int;
+			};
+			",
+		)
+		.unwrap();
+
+		let messages: Vec<String> = ProtoGenerator
+			.check(&schema)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec![
+				"variable `rows` of `Matrix` can't be represented in protobuf",
+				"`proto_number` can't be in 19000..=19999, these numbers are reserved by protobuf",
+				"field number 1 is used more than once in `Matrix`",
+			]
+		);
+	}
+
+	#[test]
+	fn qualifies_types_named_after_keywords() {
+		let schema = analyze(
+			"
+			interface option {
+				required message: message;
+				required items: List<option.map>;
+
+				interface map {
+					required value: Int;
+				};
+			};
+
+			enum message {
+				A: a;
+			};
+			",
+		)
+		.unwrap();
+
+		let output = ProtoGenerator.generate(&schema);
+
+		assert!(output.contains(
+			"message option {\n  .message message = 1;\n  repeated .option.map items = 2;\n"
+		));
+		assert!(output.contains("enum message {\n"));
+	}
+}
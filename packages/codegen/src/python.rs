@@ -0,0 +1,661 @@
+use std::collections::{BTreeSet, HashSet};
+
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Constraint, Declaration, DeclarationId, DeclarationKind, Field, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	helpers::{check_flat_names, flat_name, to_snake_case},
+	quote,
+	writer::Writer,
+	Generator,
+};
+
+//
+// Python generator
+//
+// Interfaces become pydantic v2 models or plain dataclasses, enums become
+// `str` enums, aliases and unions become module-level type aliases.
+// Nested declarations are flattened (`UserStatus`), variable names are
+// converted to snake_case. Names clashing with keywords, built-in types or
+// imported names get an underscore (`Enum_`, `class_`).
+//
+// Original names are kept as field aliases: `Field(alias=...)` for pydantic
+// (models also accept python names thanks to `populate_by_name`) and
+// `field(metadata={"alias": ...})` for dataclasses, which is understood by
+// serialization libraries like dataclasses-json. Pydantic models also
+// enforce validation constraints through `Field` arguments.
+pub struct PythonGenerator {
+	pub style: PythonStyle,
+}
+
+pub enum PythonStyle {
+	Pydantic,
+	Dataclass,
+}
+
+const RESERVED_WORDS: &[&str] = &[
+	"False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+	"def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+	"in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+	"with", "yield",
+];
+
+// Reserved words, built-in types and imported names, which
+// would be shadowed by a class
+const RESERVED_TYPES: &[&str] = &[
+	"False",
+	"None",
+	"True",
+	"and",
+	"as",
+	"assert",
+	"async",
+	"await",
+	"break",
+	"class",
+	"continue",
+	"def",
+	"del",
+	"elif",
+	"else",
+	"except",
+	"finally",
+	"for",
+	"from",
+	"global",
+	"if",
+	"import",
+	"in",
+	"is",
+	"lambda",
+	"nonlocal",
+	"not",
+	"or",
+	"pass",
+	"raise",
+	"return",
+	"try",
+	"while",
+	"with",
+	"yield",
+	"bool",
+	"dict",
+	"int",
+	"list",
+	"str",
+	"BaseModel",
+	"ConfigDict",
+	"Dict",
+	"Enum",
+	"Field",
+	"List",
+	"Literal",
+	"Optional",
+	"Union",
+	"dataclass",
+	"field",
+];
+
+// Names of generated code and the modules they're imported from
+#[derive(Default)]
+struct Imports {
+	typing: BTreeSet<&'static str>,
+	dataclasses: BTreeSet<&'static str>,
+	pydantic: BTreeSet<&'static str>,
+	uses_enum: bool,
+}
+
+impl Generator for PythonGenerator {
+	fn name(&self) -> &'static str {
+		match self.style {
+			PythonStyle::Pydantic => "python",
+			PythonStyle::Dataclass => "python-dataclass",
+		}
+	}
+
+	fn extension(&self) -> &'static str {
+		"py"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		ReservedWords {
+			target: "Python",
+			// Classes and variables get an underscore (`Enum_`, `class_`),
+			// see `class_name` and `field_name`
+			declarations: &[],
+			members: &[],
+		}
+	}
+
+	// Flattened names have to stay unique
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		check_flat_names(schema.declarations(), "Python", class_name)
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("    ");
+		let mut imports = Imports::default();
+		let mut written = HashSet::new();
+
+		for declaration in schema.declarations() {
+			self.write_declaration(&mut writer, &mut imports, &mut written, schema, declaration);
+		}
+
+		let mut output = String::from("from __future__ import annotations\n");
+
+		// Standard library first, then third-party packages
+		let mut standard = Vec::new();
+		if !imports.dataclasses.is_empty() {
+			standard.push(import_line("dataclasses", &imports.dataclasses));
+		};
+		if imports.uses_enum {
+			standard.push("from enum import Enum".to_string());
+		};
+		if !imports.typing.is_empty() {
+			standard.push(import_line("typing", &imports.typing));
+		};
+
+		if !standard.is_empty() {
+			output.push('\n');
+			output.push_str(&standard.join("\n"));
+			output.push('\n');
+		};
+
+		if !imports.pydantic.is_empty() {
+			output.push('\n');
+			output.push_str(&import_line("pydantic", &imports.pydantic));
+			output.push('\n');
+		};
+
+		output.push_str(&writer.finish());
+		output
+	}
+}
+
+impl PythonGenerator {
+	// Parents are written before their children, because a class
+	// can only inherit from an already defined one
+	fn write_declaration(
+		&self,
+		writer: &mut Writer,
+		imports: &mut Imports,
+		written: &mut HashSet<DeclarationId>,
+		schema: &Schema,
+		declaration: &Declaration,
+	) {
+		if written.contains(&declaration.id) {
+			return;
+		};
+
+		if let DeclarationKind::Interface(interface) = &declaration.kind {
+			if let Some(parent) = interface.extends {
+				self.write_declaration(writer, imports, written, schema, schema.get(parent));
+			};
+		};
+
+		written.insert(declaration.id);
+
+		// Top-level definitions are separated by two blank lines
+		writer.blank_line();
+		writer.blank_line();
+
+		match &declaration.kind {
+			DeclarationKind::Interface(interface) => {
+				self.write_class(writer, imports, schema, declaration, interface.extends);
+			}
+			DeclarationKind::Enum(enumerate) => {
+				imports.uses_enum = true;
+
+				writer.line(format!("class {}(str, Enum):", class_name(declaration)));
+				writer.indent();
+				write_docstring(writer, &declaration.docs);
+
+				for variant in enumerate.variants.iter() {
+					writer.docs(&variant.docs, "# ");
+
+					let value = variant.value.as_ref().unwrap_or(&variant.name);
+					writer.line(format!(
+						"{} = {}",
+						to_snake_case(&variant.name).to_uppercase(),
+						quote(value)
+					));
+				}
+
+				writer.dedent();
+			}
+			DeclarationKind::Alias(alias) => {
+				writer.docs(&declaration.docs, "# ");
+				writer.line(format!(
+					"{} = {}",
+					class_name(declaration),
+					type_name(schema, imports, &alias.target, true)
+				));
+			}
+			DeclarationKind::Union(union) => {
+				imports.typing.insert("Union");
+
+				let members: Vec<String> = union
+					.members
+					.iter()
+					.map(|member| quote(&class_name(schema.get(*member))))
+					.collect();
+
+				writer.docs(&declaration.docs, "# ");
+				writer.line(format!(
+					"{} = Union[{}]",
+					class_name(declaration),
+					members.join(", ")
+				));
+			}
+		};
+	}
+
+	fn write_class(
+		&self,
+		writer: &mut Writer,
+		imports: &mut Imports,
+		schema: &Schema,
+		declaration: &Declaration,
+		extends: Option<DeclarationId>,
+	) {
+		let fields: Vec<&Field> = match &declaration.kind {
+			DeclarationKind::Interface(interface) => interface.own_fields().collect(),
+			_ => Vec::new(),
+		};
+
+		let parent = extends.map(|parent| class_name(schema.get(parent)));
+		let name = class_name(declaration);
+
+		match self.style {
+			PythonStyle::Pydantic => {
+				imports.pydantic.insert("BaseModel");
+				writer.line(format!(
+					"class {}({}):",
+					name,
+					parent.unwrap_or("BaseModel".to_string())
+				));
+			}
+			PythonStyle::Dataclass => {
+				// Keyword-only, so that optional parent fields can be
+				// followed by required ones
+				imports.dataclasses.insert("dataclass");
+				writer.line("@dataclass(kw_only=True)");
+				match parent {
+					Some(parent) => writer.line(format!("class {}({}):", name, parent)),
+					None => writer.line(format!("class {}:", name)),
+				};
+			}
+		};
+		writer.indent();
+
+		write_docstring(writer, &declaration.docs);
+		if declaration.docs.is_some() && !fields.is_empty() {
+			writer.blank_line();
+		};
+
+		let has_aliases = fields
+			.iter()
+			.any(|field| field_name(&field.name) != field.name);
+
+		if has_aliases && matches!(self.style, PythonStyle::Pydantic) {
+			imports.pydantic.insert("ConfigDict");
+			writer.line("model_config = ConfigDict(populate_by_name=True)");
+			if !fields.is_empty() {
+				writer.blank_line();
+			};
+		};
+
+		for field in fields.iter() {
+			writer.docs(&field.docs, "# ");
+			writer.line(self.field_line(imports, schema, field));
+		}
+
+		if fields.is_empty() && declaration.docs.is_none() && !has_aliases {
+			writer.line("pass");
+		};
+
+		writer.dedent();
+	}
+
+	// Example:
+	// user_name: Optional[str] = Field(default=None, alias="userName", min_length=3)
+	fn field_line(&self, imports: &mut Imports, schema: &Schema, field: &Field) -> String {
+		let name = field_name(&field.name);
+
+		let mut annotation = type_name(schema, imports, &field.field_type, false);
+		if !field.is_required {
+			imports.typing.insert("Optional");
+			annotation = format!("Optional[{}]", annotation);
+		};
+
+		let mut arguments = Vec::new();
+		if !field.is_required {
+			arguments.push("default=None".to_string());
+		};
+
+		let alias = if name != field.name {
+			Option::Some(quote(&field.name))
+		} else {
+			Option::None
+		};
+
+		let function = match self.style {
+			PythonStyle::Pydantic => {
+				if let Some(alias) = alias {
+					arguments.push(format!("alias={}", alias));
+				};
+
+				arguments.extend(field.constraints.iter().map(constraint_argument));
+				"Field"
+			}
+			PythonStyle::Dataclass => {
+				if let Some(alias) = alias {
+					arguments.push(format!("metadata={{\"alias\": {}}}", alias));
+				};
+				"field"
+			}
+		};
+
+		match arguments.as_slice() {
+			[] => format!("{}: {}", name, annotation),
+			[default] if default == "default=None" => format!("{}: {} = None", name, annotation),
+			_ => {
+				match self.style {
+					PythonStyle::Pydantic => imports.pydantic.insert(function),
+					PythonStyle::Dataclass => imports.dataclasses.insert(function),
+				};
+
+				format!(
+					"{}: {} = {}({})",
+					name,
+					annotation,
+					function,
+					arguments.join(", ")
+				)
+			}
+		}
+	}
+}
+
+// Flattened name, one clashing with a keyword or an imported name gets
+// an underscore (`Enum_`), flattening can produce those even from
+// nested names (`Base.Model`)
+fn class_name(declaration: &Declaration) -> String {
+	let name = flat_name(declaration);
+
+	if RESERVED_TYPES.contains(&name.as_str()) {
+		format!("{}_", name)
+	} else {
+		name
+	}
+}
+
+// References are quoted in module-level aliases, where they're evaluated
+// before every class is defined
+// snake_case name, reserved words get an underscore (`class_`) as PEP 8
+// suggests, the original name is kept as an alias
+fn field_name(name: &str) -> String {
+	let name = to_snake_case(name);
+
+	if RESERVED_WORDS.contains(&name.as_str()) {
+		format!("{}_", name)
+	} else {
+		name
+	}
+}
+
+fn type_name(
+	schema: &Schema,
+	imports: &mut Imports,
+	field_type: &Type,
+	is_forward: bool,
+) -> String {
+	match field_type {
+		Type::String(None) => "str".to_string(),
+		Type::String(Some(value)) => {
+			imports.typing.insert("Literal");
+			format!("Literal[{}]", quote(value))
+		}
+		Type::Boolean => "bool".to_string(),
+		Type::Integer => "int".to_string(),
+		Type::List(item_type) => {
+			imports.typing.insert("List");
+			format!(
+				"List[{}]",
+				type_name(schema, imports, item_type, is_forward)
+			)
+		}
+		Type::Map(key_type, value_type) => {
+			imports.typing.insert("Dict");
+			format!(
+				"Dict[{}, {}]",
+				type_name(schema, imports, key_type, is_forward),
+				type_name(schema, imports, value_type, is_forward)
+			)
+		}
+		Type::Reference(id) if is_forward => quote(&class_name(schema.get(*id))),
+		Type::Reference(id) => class_name(schema.get(*id)),
+	}
+}
+
+fn constraint_argument(constraint: &Constraint) -> String {
+	match constraint {
+		Constraint::MinLength(length) | Constraint::MinItems(length) => {
+			format!("min_length={}", length)
+		}
+		Constraint::MaxLength(length) | Constraint::MaxItems(length) => {
+			format!("max_length={}", length)
+		}
+		Constraint::Pattern(pattern) => format!("pattern={}", quote(pattern)),
+		Constraint::Format(format) => format!("pattern={}", quote(format.pattern())),
+		Constraint::Minimum(value) => format!("ge={}", value),
+		Constraint::Maximum(value) => format!("le={}", value),
+	}
+}
+
+fn write_docstring(writer: &mut Writer, docs: &Option<String>) {
+	let docs = match docs {
+		Some(docs) => docs.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\""),
+		None => return,
+	};
+
+	if docs.contains('\n') {
+		writer.line("\"\"\"");
+		for line in docs.lines() {
+			writer.line(line);
+		}
+		writer.line("\"\"\"");
+	} else {
+		writer.line(format!("\"\"\"{}\"\"\"", docs));
+	};
+}
+
+fn import_line(module: &str, names: &BTreeSet<&'static str>) -> String {
+	format!(
+		"from {} import {}",
+		module,
+		names.iter().copied().collect::<Vec<&str>>().join(", ")
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	const SOURCE: &str = "
+		/// Registered user
+		interface User {
+			#min_length = 3
+			required userName: String;
+			optional tags: List<String>;
+			required status: User.Status;
+
+			enum Status {
+				Registered: registered;
+			};
+		};
+
+		interface Admin extends User {
+			required kind: String { \"admin\" };
+		};
+
+		union Anyone = Admin | User;
+		";
+
+	#[test]
+	fn generates_pydantic_models() {
+		let schema = analyze(SOURCE).unwrap();
+		let generator = PythonGenerator {
+			style: PythonStyle::Pydantic,
+		};
+
+		assert_eq!(
+			generator.generate(&schema),
+			r#"from __future__ import annotations
+
+from enum import Enum
+from typing import List, Literal, Optional, Union
+
+from pydantic import BaseModel, ConfigDict, Field
+
+
+class User(BaseModel):
+    """Registered user"""
+
+    model_config = ConfigDict(populate_by_name=True)
+
+    user_name: str = Field(alias="userName", min_length=3)
+    tags: Optional[List[str]] = None
+    status: UserStatus
+
+
+class UserStatus(str, Enum):
+    REGISTERED = "registered"
+
+
+class Admin(User):
+    kind: Literal["admin"]
+
+
+Anyone = Union["Admin", "User"]
+"#
+		);
+	}
+
+	#[test]
+	fn generates_dataclasses() {
+		let schema = analyze(SOURCE).unwrap();
+		let generator = PythonGenerator {
+			style: PythonStyle::Dataclass,
+		};
+
+		assert_eq!(
+			generator.generate(&schema),
+			r#"from __future__ import annotations
+
+from dataclasses import dataclass, field
+from enum import Enum
+from typing import List, Literal, Optional, Union
+
+
+@dataclass(kw_only=True)
+class User:
+    """Registered user"""
+
+    user_name: str = field(metadata={"alias": "userName"})
+    tags: Optional[List[str]] = None
+    status: UserStatus
+
+
+class UserStatus(str, Enum):
+    REGISTERED = "registered"
+
+
+@dataclass(kw_only=True)
+class Admin(User):
+    kind: Literal["admin"]
+
+
+Anyone = Union["Admin", "User"]
+"#
+		);
+	}
+
+	#[test]
+	fn escapes_reserved_words() {
+		let schema =
+			analyze("interface Import { required from: String; optional class: Int; };").unwrap();
+		let generator = PythonGenerator {
+			style: PythonStyle::Pydantic,
+		};
+		let output = generator.generate(&schema);
+
+		assert!(output.contains("    from_: str = Field(alias=\"from\")\n"));
+		assert!(
+			output.contains("    class_: Optional[int] = Field(default=None, alias=\"class\")\n")
+		);
+	}
+
+	#[test]
+	fn escapes_imported_names() {
+		let schema = analyze(
+			"
+			enum Enum {
+				A: a;
+			};
+
+			interface Base {
+				required kind: Enum;
+
+				interface Model {};
+			};
+			",
+		)
+		.unwrap();
+		let generator = PythonGenerator {
+			style: PythonStyle::Pydantic,
+		};
+		let output = generator.generate(&schema);
+
+		assert!(output.contains("class Enum_(str, Enum):\n"));
+		assert!(output.contains("    kind: Enum_\n"));
+		assert!(output.contains("class BaseModel_(BaseModel):\n"));
+	}
+
+	#[test]
+	fn reports_flattened_name_collisions() {
+		let schema = analyze(
+			"
+			interface User {
+				enum Status {
+					A: a;
+				};
+			};
+
+			enum UserStatus {
+				B: b;
+			};
+			",
+		)
+		.unwrap();
+		let generator = PythonGenerator {
+			style: PythonStyle::Dataclass,
+		};
+
+		let messages: Vec<String> = generator
+			.check(&schema)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec!["`User.Status` and `UserStatus` are both named `UserStatus` in Python"]
+		);
+	}
+}
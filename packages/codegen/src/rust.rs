@@ -0,0 +1,630 @@
+use std::collections::HashSet;
+
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Constraint, Declaration, DeclarationId, DeclarationKind, Field, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	helpers::{check_flat_names, flat_name, to_pascal_case, to_snake_case},
+	quote,
+	validators::{describe, nested_validator, validated_interfaces, NestedValidator},
+	writer::Writer,
+	Generator,
+};
+
+//
+// Rust generator
+//
+// Interfaces become serde structs with every inherited variable copied in,
+// enums become unit enums, aliases become `type` aliases and unions become
+// untagged enums. Rust has no nested types, so nested declarations
+// are flattened with their parent's name as prefix (`User.Status` becomes
+// `UserStatus`). Names are converted to Rust conventions and original ones
+// are kept with `#[serde(rename)]`.
+//
+// Interfaces with constrained variables get `validate()` method, which uses
+// `regex` crate for patterns and formats. Every pattern is compiled once,
+// into a static, the header notes the dependency when there's any.
+pub struct RustGenerator;
+
+const RESERVED_WORDS: &[&str] = &[
+	"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+	"false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+	"ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+	"unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+	"override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+// Keywords which can't be raw identifiers
+const PATH_KEYWORDS: &[&str] = &["crate", "self", "Self", "super"];
+
+impl Generator for RustGenerator {
+	fn name(&self) -> &'static str {
+		"rust"
+	}
+
+	fn extension(&self) -> &'static str {
+		"rs"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		ReservedWords {
+			target: "Rust",
+			declarations: RESERVED_WORDS,
+			// Variables and variants are escaped (`r#type`)
+			members: &[],
+		}
+	}
+
+	// Flattened names have to stay unique
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		check_flat_names(schema.declarations(), "Rust", flat_name)
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("    ");
+		let validated = validated_interfaces(schema);
+
+		let uses_regex = schema
+			.declarations()
+			.iter()
+			.any(|declaration| match &declaration.kind {
+				DeclarationKind::Interface(interface) if validated.contains(&declaration.id) => {
+					interface
+						.fields
+						.iter()
+						.flat_map(|field| field.constraints.iter())
+						.any(|constraint| regex_source(constraint).is_some())
+				}
+				_ => false,
+			});
+
+		if uses_regex {
+			writer.line("// Validators use the `regex` crate for patterns and formats");
+		};
+		writer.line("use serde::{Deserialize, Serialize};");
+
+		for declaration in schema.declarations() {
+			writer.blank_line();
+
+			match &declaration.kind {
+				DeclarationKind::Interface(interface) => {
+					write_struct(&mut writer, schema, declaration, &interface.fields);
+
+					if validated.contains(&declaration.id) {
+						writer.blank_line();
+						write_validator(
+							&mut writer,
+							schema,
+							&validated,
+							declaration,
+							&interface.fields,
+						);
+					};
+				}
+				DeclarationKind::Enum(enumerate) => {
+					writer.docs(&declaration.docs, "/// ");
+					writer.line("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]");
+					writer.line(format!("pub enum {} {{", flat_name(declaration)));
+					writer.indent();
+
+					for variant in enumerate.variants.iter() {
+						writer.docs(&variant.docs, "/// ");
+
+						let value = variant.value.as_ref().unwrap_or(&variant.name);
+						let name = escape(to_pascal_case(&variant.name));
+
+						if &name != value {
+							writer.line(format!("#[serde(rename = {})]", quote(value)));
+						};
+						writer.line(format!("{},", name));
+					}
+
+					writer.dedent();
+					writer.line("}");
+				}
+				DeclarationKind::Alias(alias) => {
+					writer.docs(&declaration.docs, "/// ");
+					writer.line(format!(
+						"pub type {} = {};",
+						flat_name(declaration),
+						type_name(schema, &alias.target)
+					));
+				}
+				DeclarationKind::Union(union) => {
+					// Members are tried in order while deserializing
+					writer.docs(&declaration.docs, "/// ");
+					writer.line("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]");
+					writer.line("#[serde(untagged)]");
+					writer.line(format!("pub enum {} {{", flat_name(declaration)));
+					writer.indent();
+
+					for member in union.members.iter() {
+						let name = flat_name(schema.get(*member));
+						writer.line(format!("{}({}),", name, name));
+					}
+
+					writer.dedent();
+					writer.line("}");
+				}
+			};
+		}
+
+		writer.finish()
+	}
+}
+
+fn write_struct(writer: &mut Writer, schema: &Schema, declaration: &Declaration, fields: &[Field]) {
+	writer.docs(&declaration.docs, "/// ");
+	writer.line("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]");
+	writer.line(format!("pub struct {} {{", flat_name(declaration)));
+	writer.indent();
+
+	for field in fields.iter() {
+		writer.docs(&field.docs, "/// ");
+
+		// Serde drops `r#` of raw identifiers
+		let name = escape(to_snake_case(&field.name));
+		if name.strip_prefix("r#").unwrap_or(&name) != field.name {
+			writer.line(format!("#[serde(rename = {})]", quote(&field.name)));
+		};
+
+		let mut field_type = type_name(schema, &field.field_type);
+		if is_recursive(schema, &field.field_type, declaration.id, &mut Vec::new()) {
+			field_type = format!("Box<{}>", field_type);
+		};
+
+		if field.is_required {
+			writer.line(format!("pub {}: {},", name, field_type));
+		} else {
+			writer.line("#[serde(default, skip_serializing_if = \"Option::is_none\")]");
+			writer.line(format!("pub {}: Option<{}>,", name, field_type));
+		};
+	}
+
+	writer.dedent();
+	writer.line("}");
+}
+
+// Keywords become raw identifiers (`r#type`), the ones which can't be raw
+// get an underscore (`self_`)
+fn escape(name: String) -> String {
+	if PATH_KEYWORDS.contains(&name.as_str()) {
+		format!("{}_", name)
+	} else if RESERVED_WORDS.contains(&name.as_str()) {
+		format!("r#{}", name)
+	} else {
+		name
+	}
+}
+
+pub fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match field_type {
+		Type::String(_) => "String".to_string(),
+		Type::Boolean => "bool".to_string(),
+		Type::Integer => "i64".to_string(),
+		Type::List(item_type) => format!("Vec<{}>", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => format!(
+			"std::collections::HashMap<{}, {}>",
+			type_name(schema, key_type),
+			type_name(schema, value_type)
+		),
+		Type::Reference(id) => flat_name(schema.get(*id)),
+	}
+}
+
+// Whether a struct would contain itself without indirection
+fn is_recursive(
+	schema: &Schema,
+	field_type: &Type,
+	target: DeclarationId,
+	visited: &mut Vec<DeclarationId>,
+) -> bool {
+	let id = match field_type {
+		Type::Reference(id) => *id,
+		// Vec and HashMap are already an indirection
+		_ => return false,
+	};
+
+	if id == target {
+		return true;
+	};
+
+	if visited.contains(&id) {
+		return false;
+	};
+	visited.push(id);
+
+	match &schema.get(id).kind {
+		DeclarationKind::Interface(interface) => interface
+			.fields
+			.iter()
+			.any(|field| is_recursive(schema, &field.field_type, target, visited)),
+		DeclarationKind::Alias(alias) => is_recursive(schema, &alias.target, target, visited),
+		DeclarationKind::Union(union) => union
+			.members
+			.iter()
+			.any(|member| is_recursive(schema, &Type::Reference(*member), target, visited)),
+		DeclarationKind::Enum(_) => false,
+	}
+}
+
+//
+// Validator
+//
+// Example:
+// impl User {
+//     pub fn validate(&self) -> Result<(), Vec<String>> {
+//         let mut errors = Vec::new();
+//         if self.username.chars().count() < 3 {
+//             errors.push("username: must be at least 3 characters long".to_string());
+//         }
+//         ...
+//     }
+// }
+fn write_validator(
+	writer: &mut Writer,
+	schema: &Schema,
+	validated: &HashSet<DeclarationId>,
+	declaration: &Declaration,
+	fields: &[Field],
+) {
+	writer.line(format!("impl {} {{", flat_name(declaration)));
+	writer.indent();
+	writer.line("pub fn validate(&self) -> Result<(), Vec<String>> {");
+	writer.indent();
+
+	// Statics are numbered in the order of checks
+	let patterns = fields
+		.iter()
+		.flat_map(|field| field.constraints.iter())
+		.filter(|constraint| regex_source(constraint).is_some())
+		.count();
+
+	for index in 0..patterns {
+		writer.line(format!(
+			"static PATTERN_{}: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();",
+			index
+		));
+	}
+	if patterns > 0 {
+		writer.blank_line();
+	};
+
+	writer.line("let mut errors: Vec<String> = Vec::new();");
+	let mut pattern = 0;
+
+	for field in fields.iter() {
+		let nested = nested_validator(schema, &field.field_type, validated);
+		// Zero lower bounds can't fail
+		let constraints: Vec<&Constraint> = field
+			.constraints
+			.iter()
+			.filter(|constraint| {
+				!matches!(
+					constraint,
+					Constraint::MinLength(0) | Constraint::MinItems(0)
+				)
+			})
+			.collect();
+
+		if constraints.is_empty() && nested.is_none() {
+			continue;
+		};
+
+		let name = escape(to_snake_case(&field.name));
+
+		// Optional values are checked only when present. `value` is used
+		// in comparisons, `reference` is passed to regex
+		writer.blank_line();
+		let (value, reference) = if field.is_required {
+			(format!("self.{}", name), format!("&self.{}", name))
+		} else {
+			let borrow = if schema.resolve_type(&field.field_type) == &Type::Integer {
+				""
+			} else {
+				"&"
+			};
+
+			// Not shadowing validator's own variables
+			let binding = match name.as_str() {
+				"errors" | "nested" | "error" | "index" | "item" => format!("{}_value", name),
+				_ => name.clone(),
+			};
+
+			writer.line(format!(
+				"if let Some({}) = {}self.{} {{",
+				binding, borrow, name
+			));
+			writer.indent();
+			(binding.clone(), binding)
+		};
+
+		for constraint in constraints.into_iter() {
+			writer.line(format!(
+				"if {} {{",
+				failed_check(&value, &reference, constraint, &mut pattern)
+			));
+			writer.indent();
+			writer.line(format!(
+				"errors.push({}.to_string());",
+				quote(&format!("{}: {}", field.name, describe(constraint)))
+			));
+			writer.dedent();
+			writer.line("}");
+		}
+
+		match nested {
+			Some(NestedValidator::Value(_)) => {
+				writer.line(format!("if let Err(nested) = {}.validate() {{", value));
+				writer.indent();
+				writer.line(format!(
+					"errors.extend(nested.into_iter().map(|error| format!(\"{}.{{}}\", error)));",
+					field.name
+				));
+				writer.dedent();
+				writer.line("}");
+			}
+			Some(NestedValidator::Items(_)) => {
+				writer.line(format!(
+					"for (index, item) in {}.iter().enumerate() {{",
+					value
+				));
+				writer.indent();
+				writer.line("if let Err(nested) = item.validate() {");
+				writer.indent();
+				writer.line(format!(
+					"errors.extend(nested.into_iter().map(|error| format!(\"{}[{{}}].{{}}\", index, error)));",
+					field.name
+				));
+				writer.dedent();
+				writer.line("}");
+				writer.dedent();
+				writer.line("}");
+			}
+			None => {}
+		};
+
+		if !field.is_required {
+			writer.dedent();
+			writer.line("}");
+		};
+	}
+
+	writer.blank_line();
+	writer.line("if errors.is_empty() {");
+	writer.indent();
+	writer.line("Ok(())");
+	writer.dedent();
+	writer.line("} else {");
+	writer.indent();
+	writer.line("Err(errors)");
+	writer.dedent();
+	writer.line("}");
+	writer.dedent();
+	writer.line("}");
+	writer.dedent();
+	writer.line("}");
+}
+
+// Regex checking a string constraint
+fn regex_source(constraint: &Constraint) -> Option<&str> {
+	match constraint {
+		Constraint::Pattern(pattern) => Option::Some(pattern),
+		Constraint::Format(format) => Option::Some(format.pattern()),
+		_ => Option::None,
+	}
+}
+
+// Condition which is true when the constraint is violated. `pattern` is
+// the number of the next `PATTERN_` static, patterns are checked by typm
+// so compiling them can't fail
+fn failed_check(
+	value: &str,
+	reference: &str,
+	constraint: &Constraint,
+	pattern: &mut usize,
+) -> String {
+	if let Some(source) = regex_source(constraint) {
+		*pattern += 1;
+
+		return format!(
+			"!PATTERN_{}.get_or_init(|| regex::Regex::new({}).unwrap()).is_match({})",
+			*pattern - 1,
+			quote(source),
+			reference
+		);
+	};
+
+	// Length of strings is the number of their characters (code points)
+	match constraint {
+		Constraint::MinLength(1) | Constraint::MinItems(1) => format!("{}.is_empty()", value),
+		Constraint::MaxLength(0) | Constraint::MaxItems(0) => format!("!{}.is_empty()", value),
+		Constraint::MinLength(length) => format!("{}.chars().count() < {}", value, length),
+		Constraint::MaxLength(length) => format!("{}.chars().count() > {}", value, length),
+		Constraint::MinItems(count) => format!("{}.len() < {}", value, count),
+		Constraint::MaxItems(count) => format!("{}.len() > {}", value, count),
+		// Checked above
+		Constraint::Pattern(_) | Constraint::Format(_) => unreachable!(),
+		Constraint::Minimum(minimum) => format!("{} < {}", value, minimum),
+		Constraint::Maximum(maximum) => format!("{} > {}", value, maximum),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_structs_enums_and_validators() {
+		let schema = analyze(
+			"
+			interface User {
+				#min_length = 3
+				required userName: String;
+				#format = email
+				optional email: String;
+				required status: User.Status;
+				#max_items = 2
+				optional friends: List<User>;
+
+				enum Status {
+					REGISTERED: registered;
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			RustGenerator.generate(&schema),
+			r#"// Validators use the `regex` crate for patterns and formats
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    #[serde(rename = "userName")]
+    pub user_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub status: UserStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub friends: Option<Vec<User>>,
+}
+
+impl User {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        static PATTERN_0: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+
+        let mut errors: Vec<String> = Vec::new();
+
+        if self.user_name.chars().count() < 3 {
+            errors.push("userName: must be at least 3 characters long".to_string());
+        }
+
+        if let Some(email) = &self.email {
+            if !PATTERN_0.get_or_init(|| regex::Regex::new("^[^\\s@]+@[^\\s@]+\\.[^\\s@]+$").unwrap()).is_match(email) {
+                errors.push("email: must be a valid email address".to_string());
+            }
+        }
+
+        if let Some(friends) = &self.friends {
+            if friends.len() > 2 {
+                errors.push("friends: must contain at most 2 items".to_string());
+            }
+            for (index, item) in friends.iter().enumerate() {
+                if let Err(nested) = item.validate() {
+                    errors.extend(nested.into_iter().map(|error| format!("friends[{}].{}", index, error)));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UserStatus {
+    #[serde(rename = "registered")]
+    Registered,
+}
+"#
+		);
+	}
+
+	#[test]
+	fn escapes_keywords() {
+		let schema = analyze(
+			"
+			interface Item {
+				#min_length = 1
+				required type: String;
+				optional self: Int;
+				required enum: Item.Kind;
+
+				enum Kind {
+					self: self;
+					String: string;
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			RustGenerator.generate(&schema),
+			r#"use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Item {
+    pub r#type: String,
+    #[serde(rename = "self")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub self_: Option<i64>,
+    pub r#enum: ItemKind,
+}
+
+impl Item {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors: Vec<String> = Vec::new();
+
+        if self.r#type.is_empty() {
+            errors.push("type: must be at least 1 characters long".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ItemKind {
+    #[serde(rename = "self")]
+    Self_,
+    #[serde(rename = "string")]
+    String,
+}
+"#
+		);
+	}
+
+	#[test]
+	fn reports_flattened_name_collisions() {
+		let schema = analyze(
+			"
+			interface User {
+				enum Status {
+					A: a;
+				};
+			};
+
+			enum UserStatus {
+				B: b;
+			};
+			",
+		)
+		.unwrap();
+
+		let messages: Vec<String> = RustGenerator
+			.check(&schema)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec!["`User.Status` and `UserStatus` are both named `UserStatus` in Rust"]
+		);
+	}
+}
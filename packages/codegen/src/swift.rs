@@ -0,0 +1,543 @@
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Declaration, DeclarationId, DeclarationKind, Field, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{helpers::to_camel_case, quote, writer::Writer, Generator};
+
+//
+// Swift generator
+//
+// Interfaces become `Codable` structs with every inherited variable copied
+// in, enums become `String` enums, aliases become type aliases. Nested
+// declarations become nested types, so `User.Status` is a valid Swift type.
+// Names are converted to Swift conventions, original ones are kept in
+// `CodingKeys`.
+//
+// Unions become enums with associated values, decoded by trying every
+// member in order. Structs can't contain themselves, so interfaces which
+// reference themselves outside of collections are reported by `check`.
+pub struct SwiftGenerator;
+
+const CONFORMANCES: &str = "Codable, Equatable, Hashable";
+
+// Keywords, which have to be escaped with backticks in names
+const KEYWORDS: &[&str] = &[
+	"associatedtype",
+	"break",
+	"case",
+	"catch",
+	"class",
+	"continue",
+	"default",
+	"defer",
+	"deinit",
+	"do",
+	"else",
+	"enum",
+	"extension",
+	"fallthrough",
+	"false",
+	"fileprivate",
+	"for",
+	"func",
+	"guard",
+	"if",
+	"import",
+	"in",
+	"init",
+	"inout",
+	"internal",
+	"is",
+	"let",
+	"nil",
+	"operator",
+	"private",
+	"protocol",
+	"public",
+	"repeat",
+	"rethrows",
+	"return",
+	"self",
+	"Self",
+	"static",
+	"struct",
+	"subscript",
+	"super",
+	"switch",
+	"throw",
+	"throws",
+	"true",
+	"try",
+	"typealias",
+	"var",
+	"where",
+	"while",
+];
+
+// Types used by generated code and `Self`, which can't name a type even
+// with backticks, other keywords are escaped
+const RESERVED_TYPES: &[&str] = &[
+	"Self",
+	"Bool",
+	"Codable",
+	"CodingKey",
+	"CodingKeys",
+	"Decoder",
+	"DecodingError",
+	"Encoder",
+	"Equatable",
+	"Hashable",
+	"Int",
+	"String",
+	"Type",
+];
+
+impl Generator for SwiftGenerator {
+	fn name(&self) -> &'static str {
+		"swift"
+	}
+
+	fn extension(&self) -> &'static str {
+		"swift"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		ReservedWords {
+			target: "Swift",
+			declarations: RESERVED_TYPES,
+			// Properties and cases are escaped with backticks
+			members: &[],
+		}
+	}
+
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		let mut diagnostics = Vec::new();
+
+		for declaration in schema.declarations() {
+			if let DeclarationKind::Interface(interface) = &declaration.kind {
+				let field = interface.fields.iter().find(|field| {
+					contains(schema, &field.field_type, declaration.id, &mut Vec::new())
+				});
+
+				if let Some(field) = field {
+					diagnostics.push(
+						Diagnostic::error(format!(
+							"interface `{}` contains itself, which Swift structs can't represent",
+							declaration.qualified_name()
+						))
+						.with_label(field.span.clone(), "use a List here"),
+					);
+				};
+			};
+		}
+
+		diagnostics
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("    ");
+
+		writer.line("import Foundation");
+
+		for declaration in schema.roots() {
+			writer.blank_line();
+			write_declaration(&mut writer, schema, declaration);
+		}
+
+		writer.finish()
+	}
+}
+
+fn write_declaration(writer: &mut Writer, schema: &Schema, declaration: &Declaration) {
+	writer.docs(&declaration.docs, "/// ");
+
+	let name = escape(&declaration.name);
+
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			if interface.fields.is_empty() && declaration.children.is_empty() {
+				writer.line(format!("struct {}: {} {{}}", name, CONFORMANCES));
+				return;
+			};
+
+			writer.line(format!("struct {}: {} {{", name, CONFORMANCES));
+			writer.indent();
+
+			for field in interface.fields.iter() {
+				write_property(writer, schema, field);
+			}
+
+			// Needed only when some property is renamed
+			let is_renamed = interface
+				.fields
+				.iter()
+				.any(|field| to_camel_case(&field.name) != field.name);
+
+			if is_renamed {
+				writer.blank_line();
+				writer.line("enum CodingKeys: String, CodingKey {");
+				writer.indent();
+
+				for field in interface.fields.iter() {
+					let name = to_camel_case(&field.name);
+
+					if name == field.name {
+						writer.line(format!("case {}", escape(&name)));
+					} else {
+						writer.line(format!("case {} = {}", escape(&name), quote(&field.name)));
+					};
+				}
+
+				writer.dedent();
+				writer.line("}");
+			};
+
+			for child in schema.children(declaration) {
+				writer.blank_line();
+				write_declaration(writer, schema, child);
+			}
+
+			writer.dedent();
+			writer.line("}");
+		}
+		DeclarationKind::Enum(enumerate) => {
+			writer.line(format!("enum {}: String, {} {{", name, CONFORMANCES));
+			writer.indent();
+
+			for variant in enumerate.variants.iter() {
+				writer.docs(&variant.docs, "/// ");
+
+				let value = variant.value.as_ref().unwrap_or(&variant.name);
+				let case = to_camel_case(&variant.name);
+
+				if &case == value {
+					writer.line(format!("case {}", escape(&case)));
+				} else {
+					writer.line(format!("case {} = {}", escape(&case), quote(value)));
+				};
+			}
+
+			writer.dedent();
+			writer.line("}");
+		}
+		DeclarationKind::Alias(alias) => writer.line(format!(
+			"typealias {} = {}",
+			name,
+			type_name(schema, &alias.target)
+		)),
+		DeclarationKind::Union(union) => {
+			write_union(writer, schema, declaration, &union.members);
+		}
+	};
+}
+
+fn write_property(writer: &mut Writer, schema: &Schema, field: &Field) {
+	writer.docs(&field.docs, "/// ");
+
+	let optional = if field.is_required { "" } else { "?" };
+	writer.line(format!(
+		"let {}: {}{}",
+		escape(&to_camel_case(&field.name)),
+		type_name(schema, &field.field_type),
+		optional
+	));
+}
+
+//
+// Union
+//
+// Example:
+// indirect enum Account: Codable, Equatable, Hashable {
+//     case user(User)
+//
+//     init(from decoder: Decoder) throws {
+//         let container = try decoder.singleValueContainer()
+//         if let value = try? container.decode(User.self) {
+//             self = .user(value)
+//         } else {
+//             throw DecodingError.dataCorruptedError(...)
+//         }
+//     }
+//
+//     func encode(to encoder: Encoder) throws { ... }
+// }
+//
+// Enums are `indirect`, because members may reference their union.
+fn write_union(
+	writer: &mut Writer,
+	schema: &Schema,
+	declaration: &Declaration,
+	members: &[DeclarationId],
+) {
+	let cases: Vec<(String, String)> = members
+		.iter()
+		.map(|member| {
+			let member = schema.get(*member);
+			(
+				escape(&to_camel_case(&member.path.join("_"))),
+				qualified_name(member),
+			)
+		})
+		.collect();
+
+	writer.line(format!(
+		"indirect enum {}: {} {{",
+		escape(&declaration.name),
+		CONFORMANCES
+	));
+	writer.indent();
+
+	for (case, member) in cases.iter() {
+		writer.line(format!("case {}({})", case, member));
+	}
+
+	writer.blank_line();
+	writer.line("init(from decoder: Decoder) throws {");
+	writer.indent();
+	writer.line("let container = try decoder.singleValueContainer()");
+
+	for (index, (case, member)) in cases.iter().enumerate() {
+		let keyword = if index == 0 { "if" } else { "} else if" };
+
+		writer.line(format!(
+			"{} let value = try? container.decode({}.self) {{",
+			keyword, member
+		));
+		writer.indent();
+		writer.line(format!("self = .{}(value)", case));
+		writer.dedent();
+	}
+
+	writer.line("} else {");
+	writer.indent();
+	writer.line(format!(
+		"throw DecodingError.dataCorruptedError(in: container, debugDescription: {})",
+		quote(&format!(
+			"Value doesn't match any member of {}",
+			declaration.qualified_name()
+		))
+	));
+	writer.dedent();
+	writer.line("}");
+	writer.dedent();
+	writer.line("}");
+
+	writer.blank_line();
+	writer.line("func encode(to encoder: Encoder) throws {");
+	writer.indent();
+	writer.line("var container = encoder.singleValueContainer()");
+	writer.line("switch self {");
+
+	for (case, _) in cases.iter() {
+		writer.line(format!("case .{}(let value):", case));
+		writer.indent();
+		writer.line("try container.encode(value)");
+		writer.dedent();
+	}
+
+	writer.line("}");
+	writer.dedent();
+	writer.line("}");
+	writer.dedent();
+	writer.line("}");
+}
+
+pub fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match field_type {
+		Type::String(_) => "String".to_string(),
+		Type::Boolean => "Bool".to_string(),
+		Type::Integer => "Int".to_string(),
+		Type::List(item_type) => format!("[{}]", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => format!(
+			"[{}: {}]",
+			type_name(schema, key_type),
+			type_name(schema, value_type)
+		),
+		Type::Reference(id) => qualified_name(schema.get(*id)),
+	}
+}
+
+// Whether a struct would store target inline. Arrays, dictionaries
+// and indirect enums are indirections
+fn contains(
+	schema: &Schema,
+	field_type: &Type,
+	target: DeclarationId,
+	visited: &mut Vec<DeclarationId>,
+) -> bool {
+	let id = match schema.resolve_type(field_type) {
+		Type::Reference(id) => *id,
+		_ => return false,
+	};
+
+	if id == target {
+		return true;
+	};
+
+	if visited.contains(&id) {
+		return false;
+	};
+	visited.push(id);
+
+	match &schema.get(id).kind {
+		DeclarationKind::Interface(interface) => interface
+			.fields
+			.iter()
+			.any(|field| contains(schema, &field.field_type, target, visited)),
+		_ => false,
+	}
+}
+
+// Dotted path to a nested type, escaping every segment
+fn qualified_name(declaration: &Declaration) -> String {
+	declaration
+		.path
+		.iter()
+		.map(|name| escape(name))
+		.collect::<Vec<String>>()
+		.join(".")
+}
+
+fn escape(name: &str) -> String {
+	if KEYWORDS.contains(&name) {
+		format!("`{}`", name)
+	} else {
+		name.to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_structs_enums_and_unions() {
+		let schema = analyze(
+			"
+			/// Registered user
+			interface User {
+				required user_name: String;
+				optional tags: List<String>;
+				required status: User.Status;
+
+				enum Status {
+					Registered: registered;
+					DEFAULT: default;
+				};
+			};
+
+			interface Admin {
+				optional level: Int;
+			};
+
+			union Account = User | Admin;
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			SwiftGenerator.generate(&schema),
+			r#"import Foundation
+
+/// Registered user
+struct User: Codable, Equatable, Hashable {
+    let userName: String
+    let tags: [String]?
+    let status: User.Status
+
+    enum CodingKeys: String, CodingKey {
+        case userName = "user_name"
+        case tags
+        case status
+    }
+
+    enum Status: String, Codable, Equatable, Hashable {
+        case registered
+        case `default`
+    }
+}
+
+struct Admin: Codable, Equatable, Hashable {
+    let level: Int?
+}
+
+indirect enum Account: Codable, Equatable, Hashable {
+    case user(User)
+    case admin(Admin)
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.singleValueContainer()
+        if let value = try? container.decode(User.self) {
+            self = .user(value)
+        } else if let value = try? container.decode(Admin.self) {
+            self = .admin(value)
+        } else {
+            throw DecodingError.dataCorruptedError(in: container, debugDescription: "Value doesn't match any member of Account")
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.singleValueContainer()
+        switch self {
+        case .user(let value):
+            try container.encode(value)
+        case .admin(let value):
+            try container.encode(value)
+        }
+    }
+}
+"#
+		);
+	}
+
+	#[test]
+	fn reports_self_containing_structs() {
+		let schema = analyze(
+			"
+			interface User {
+				optional referrer: User;
+				optional friends: List<User>;
+			};
+			",
+		)
+		.unwrap();
+
+		let messages: Vec<String> = SwiftGenerator
+			.check(&schema)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec!["interface `User` contains itself, which Swift structs can't represent"]
+		);
+	}
+
+	#[test]
+	fn escapes_keyword_type_names() {
+		let schema = analyze(
+			"
+			interface class {
+				optional return: class.struct;
+
+				interface struct {
+					required value: Int;
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		let output = SwiftGenerator.generate(&schema);
+
+		assert!(output.contains(
+			"struct `class`: Codable, Equatable, Hashable {\n    let `return`: `class`.`struct`?\n"
+		));
+		assert!(output.contains("    struct `struct`: Codable, Equatable, Hashable {\n"));
+	}
+}
@@ -0,0 +1,690 @@
+use std::collections::HashSet;
+
+use semantic::{
+	ir::{Constraint, Declaration, DeclarationId, DeclarationKind, Field, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	guards::{write_checks, write_guards, write_prelude},
+	quote,
+	validators::{describe, nested_validator, validated_interfaces, NestedValidator},
+	writer::Writer,
+	Generator,
+};
+
+//
+// TypeScript generator
+//
+// Interfaces become `export interface`s, enums become string `export enum`s,
+// aliases and unions become `export type`s.
+// Nested declarations are placed into a namespace named after their parent,
+// so that `User.Status` is a valid TypeScript type as well.
+//
+// Interfaces with constrained variables also get a validator function
+// (`validateUser(value: User): string[]`) returning all found errors.
+//
+// `typescript-guards` target also emits runtime type guards and parsers
+// of JSON for every declaration (see `guards` module).
+pub struct TypeScriptGenerator {
+	pub mode: TypeScriptMode,
+}
+
+pub enum TypeScriptMode {
+	Types,
+	Guards,
+}
+
+// Reserved words and built-in type names, which can't name an interface,
+// an enum or a namespace, so declarations named after them get an underscore
+const RESERVED_WORDS: &[&str] = &[
+	"any",
+	"as",
+	"boolean",
+	"break",
+	"case",
+	"catch",
+	"class",
+	"const",
+	"continue",
+	"debugger",
+	"default",
+	"delete",
+	"do",
+	"else",
+	"enum",
+	"export",
+	"extends",
+	"false",
+	"finally",
+	"for",
+	"function",
+	"if",
+	"implements",
+	"import",
+	"in",
+	"instanceof",
+	"interface",
+	"let",
+	"never",
+	"new",
+	"null",
+	"number",
+	"object",
+	"package",
+	"private",
+	"protected",
+	"public",
+	"return",
+	"static",
+	"string",
+	"super",
+	"switch",
+	"symbol",
+	"this",
+	"throw",
+	"true",
+	"try",
+	"typeof",
+	"undefined",
+	"unknown",
+	"var",
+	"void",
+	"while",
+	"with",
+	"yield",
+];
+
+impl Generator for TypeScriptGenerator {
+	fn name(&self) -> &'static str {
+		match self.mode {
+			TypeScriptMode::Types => "typescript",
+			TypeScriptMode::Guards => "typescript-guards",
+		}
+	}
+
+	fn extension(&self) -> &'static str {
+		"ts"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		ReservedWords {
+			target: "TypeScript",
+			declarations: &[],
+			// Property names and enum members may be reserved words
+			members: &[],
+		}
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("  ");
+		let validated = validated_interfaces(schema);
+		let is_guarded = matches!(self.mode, TypeScriptMode::Guards);
+
+		if is_guarded {
+			write_prelude(&mut writer, schema);
+			writer.blank_line();
+		};
+
+		for (index, declaration) in schema.roots().enumerate() {
+			if index > 0 {
+				writer.blank_line();
+			};
+
+			write_declaration(&mut writer, schema, &validated, is_guarded, declaration);
+		}
+
+		if is_guarded {
+			writer.blank_line();
+			write_checks(&mut writer, schema);
+		};
+
+		writer.finish()
+	}
+}
+
+fn write_declaration(
+	writer: &mut Writer,
+	schema: &Schema,
+	validated: &HashSet<DeclarationId>,
+	is_guarded: bool,
+	declaration: &Declaration,
+) {
+	write_docs(writer, &declaration.docs);
+
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			match interface.extends {
+				Some(parent) => writer.line(format!(
+					"export interface {} extends {} {{",
+					declaration_name(&declaration.name),
+					qualified_name(schema.get(parent))
+				)),
+				None => writer.line(format!(
+					"export interface {} {{",
+					declaration_name(&declaration.name)
+				)),
+			};
+
+			writer.indent();
+			for field in interface.own_fields() {
+				write_field(writer, schema, field);
+			}
+			writer.dedent();
+
+			writer.line("}");
+
+			if validated.contains(&declaration.id) {
+				writer.blank_line();
+				write_validator(writer, schema, validated, declaration, &interface.fields);
+			};
+		}
+		DeclarationKind::Enum(enumerate) => {
+			writer.line(format!(
+				"export enum {} {{",
+				declaration_name(&declaration.name)
+			));
+
+			writer.indent();
+			for variant in enumerate.variants.iter() {
+				write_docs(writer, &variant.docs);
+
+				let value = variant.value.as_ref().unwrap_or(&variant.name);
+				writer.line(format!("{} = {},", variant.name, quote(value)));
+			}
+			writer.dedent();
+
+			writer.line("}");
+		}
+		DeclarationKind::Alias(alias) => writer.line(format!(
+			"export type {} = {};",
+			declaration_name(&declaration.name),
+			type_name(schema, &alias.target)
+		)),
+		DeclarationKind::Union(union) => {
+			let members: Vec<String> = union
+				.members
+				.iter()
+				.map(|member| qualified_name(schema.get(*member)))
+				.collect();
+
+			writer.line(format!(
+				"export type {} = {};",
+				declaration_name(&declaration.name),
+				members.join(" | ")
+			));
+		}
+	};
+
+	if is_guarded {
+		writer.blank_line();
+		write_guards(writer, declaration);
+	};
+
+	// Nested declarations
+	if !declaration.children.is_empty() {
+		writer.blank_line();
+		writer.line(format!(
+			"export namespace {} {{",
+			declaration_name(&declaration.name)
+		));
+		writer.indent();
+
+		for (index, child) in schema.children(declaration).enumerate() {
+			if index > 0 {
+				writer.blank_line();
+			};
+
+			write_declaration(writer, schema, validated, is_guarded, child);
+		}
+
+		writer.dedent();
+		writer.line("}");
+	};
+}
+
+fn write_field(writer: &mut Writer, schema: &Schema, field: &Field) {
+	write_docs(writer, &field.docs);
+
+	let name = if is_identifier(&field.name) {
+		field.name.clone()
+	} else {
+		quote(&field.name)
+	};
+	let optional = if field.is_required { "" } else { "?" };
+
+	writer.line(format!(
+		"{}{}: {};",
+		name,
+		optional,
+		type_name(schema, &field.field_type)
+	));
+}
+
+pub fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match field_type {
+		Type::String(None) => "string".to_string(),
+		Type::String(Some(value)) => quote(value),
+		Type::Boolean => "boolean".to_string(),
+		Type::Integer => "number".to_string(),
+		Type::List(item_type) => format!("{}[]", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => format!(
+			"Record<{}, {}>",
+			type_name(schema, key_type),
+			type_name(schema, value_type)
+		),
+		Type::Reference(id) => qualified_name(schema.get(*id)),
+	}
+}
+
+//
+// Validator
+//
+// Example:
+// export function validateUser(value: User): string[] {
+//   const errors: string[] = [];
+//   if (value.username.length < 3) {
+//     errors.push("username: must be at least 3 characters long");
+//   }
+//   return errors;
+// }
+fn write_validator(
+	writer: &mut Writer,
+	schema: &Schema,
+	validated: &HashSet<DeclarationId>,
+	declaration: &Declaration,
+	fields: &[Field],
+) {
+	writer.line(format!(
+		"export function {}(value: {}): string[] {{",
+		validator_name(declaration.name.as_str()),
+		qualified_name(declaration)
+	));
+	writer.indent();
+	writer.line("const errors: string[] = [];");
+
+	for field in fields.iter() {
+		let nested = nested_validator(schema, &field.field_type, validated);
+
+		if field.constraints.is_empty() && nested.is_none() {
+			continue;
+		};
+
+		let access = if is_identifier(&field.name) {
+			format!("value.{}", field.name)
+		} else {
+			format!("value[{}]", quote(&field.name))
+		};
+
+		writer.blank_line();
+		if !field.is_required {
+			writer.line(format!("if ({} !== undefined) {{", access));
+			writer.indent();
+		};
+
+		for constraint in field.constraints.iter() {
+			writer.line(format!("if ({}) {{", failed_check(&access, constraint)));
+			writer.indent();
+			writer.line(format!(
+				"errors.push({});",
+				quote(&format!("{}: {}", field.name, describe(constraint)))
+			));
+			writer.dedent();
+			writer.line("}");
+		}
+
+		match nested {
+			Some(NestedValidator::Value(id)) => writer.line(format!(
+				"errors.push(...{}({}).map((error) => {} + error));",
+				qualified_validator_name(schema, id),
+				access,
+				quote(&format!("{}.", field.name))
+			)),
+			Some(NestedValidator::Items(id)) => writer.line(format!(
+				"{}.forEach((item, index) => errors.push(...{}(item).map((error) => {} + index + \"].\" + error)));",
+				access,
+				qualified_validator_name(schema, id),
+				quote(&format!("{}[", field.name))
+			)),
+			None => {}
+		};
+
+		if !field.is_required {
+			writer.dedent();
+			writer.line("}");
+		};
+	}
+
+	writer.blank_line();
+	writer.line("return errors;");
+	writer.dedent();
+	writer.line("}");
+}
+
+// Condition which is true when the constraint is violated. String length
+// is counted in code points (`[...value]`), not UTF-16 units
+fn failed_check(access: &str, constraint: &Constraint) -> String {
+	match constraint {
+		Constraint::MinLength(length) => format!("[...{}].length < {}", access, length),
+		Constraint::MaxLength(length) => format!("[...{}].length > {}", access, length),
+		Constraint::MinItems(count) => format!("{}.length < {}", access, count),
+		Constraint::MaxItems(count) => format!("{}.length > {}", access, count),
+		Constraint::Pattern(pattern) => format!("!new RegExp({}).test({})", quote(pattern), access),
+		Constraint::Format(format) => {
+			format!("!new RegExp({}).test({})", quote(format.pattern()), access)
+		}
+		Constraint::Minimum(value) => format!("{} < {}", access, value),
+		Constraint::Maximum(value) => format!("{} > {}", access, value),
+	}
+}
+
+fn validator_name(name: &str) -> String {
+	format!("validate{}", name)
+}
+
+// Validators of nested interfaces live in their parent's namespace
+fn qualified_validator_name(schema: &Schema, id: DeclarationId) -> String {
+	let declaration = schema.get(id);
+
+	match declaration.parent {
+		Some(parent) => format!(
+			"{}.{}",
+			qualified_name(schema.get(parent)),
+			validator_name(&declaration.name)
+		),
+		None => validator_name(&declaration.name),
+	}
+}
+
+fn write_docs(writer: &mut Writer, docs: &Option<String>) {
+	if docs.is_some() {
+		writer.line("/**");
+		writer.docs(docs, " * ");
+		writer.line(" */");
+	};
+}
+
+// Name of a declaration in it's scope, reserved words get an underscore (`enum_`)
+fn declaration_name(name: &str) -> String {
+	if RESERVED_WORDS.contains(&name) {
+		format!("{}_", name)
+	} else {
+		name.to_string()
+	}
+}
+
+// Dotted path to a declaration through namespaces of it's parents
+pub fn qualified_name(declaration: &Declaration) -> String {
+	declaration
+		.path
+		.iter()
+		.map(|name| declaration_name(name))
+		.collect::<Vec<String>>()
+		.join(".")
+}
+
+pub fn is_identifier(name: &str) -> bool {
+	let mut chars = name.chars();
+
+	match chars.next() {
+		Some(char) if char.is_ascii_alphabetic() || char == '_' || char == '$' => {}
+		_ => return false,
+	};
+
+	chars.all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '$')
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_interfaces_and_nested_enums() {
+		let schema = analyze(
+			"
+			/// Registered user
+			interface User {
+				required id: String;
+				optional username: String;
+				required status: User.Status;
+				required kind: String { \"user\" };
+
+				enum Status {
+					Registered: \"registered\";
+					Banned: banned;
+				};
+			};
+
+			interface Admin extends User {
+				required level: Int;
+				optional is_root: Boolean;
+			};
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			TypeScriptGenerator {
+				mode: TypeScriptMode::Types,
+			}
+			.generate(&schema),
+			r#"/**
+ * Registered user
+ */
+export interface User {
+  id: string;
+  username?: string;
+  status: User.Status;
+  kind: "user";
+}
+
+export namespace User {
+  export enum Status {
+    Registered = "registered",
+    Banned = "banned",
+  }
+}
+
+export interface Admin extends User {
+  level: number;
+  is_root?: boolean;
+}
+"#
+		);
+	}
+
+	#[test]
+	fn generates_validators() {
+		let schema = analyze(
+			"
+			interface User {
+				#min_length = 3
+				required username: String;
+				optional friends: List<User>;
+
+				interface Address {
+					#pattern = \"^[0-9]{5}$\"
+					required zip: String;
+				};
+			};
+
+			interface Order {
+				#min = 1
+				required amount: Int;
+				optional address: User.Address;
+			};
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			TypeScriptGenerator {
+				mode: TypeScriptMode::Types,
+			}
+			.generate(&schema),
+			r#"export interface User {
+  username: string;
+  friends?: User[];
+}
+
+export function validateUser(value: User): string[] {
+  const errors: string[] = [];
+
+  if ([...value.username].length < 3) {
+    errors.push("username: must be at least 3 characters long");
+  }
+
+  if (value.friends !== undefined) {
+    value.friends.forEach((item, index) => errors.push(...validateUser(item).map((error) => "friends[" + index + "]." + error)));
+  }
+
+  return errors;
+}
+
+export namespace User {
+  export interface Address {
+    zip: string;
+  }
+
+  export function validateAddress(value: User.Address): string[] {
+    const errors: string[] = [];
+
+    if (!new RegExp("^[0-9]{5}$").test(value.zip)) {
+      errors.push("zip: must match pattern ^[0-9]{5}$");
+    }
+
+    return errors;
+  }
+}
+
+export interface Order {
+  amount: number;
+  address?: User.Address;
+}
+
+export function validateOrder(value: Order): string[] {
+  const errors: string[] = [];
+
+  if (value.amount < 1) {
+    errors.push("amount: must be greater than or equal to 1");
+  }
+
+  if (value.address !== undefined) {
+    errors.push(...User.validateAddress(value.address).map((error) => "address." + error));
+  }
+
+  return errors;
+}
+"#
+		);
+	}
+
+	#[test]
+	fn generates_guards_and_parsers() {
+		let schema = analyze(
+			"
+			interface User {
+				required id: String;
+				optional tags: List<String>;
+			};
+			",
+		)
+		.unwrap();
+
+		let output = TypeScriptGenerator {
+			mode: TypeScriptMode::Guards,
+		}
+		.generate(&schema);
+
+		// Only used helpers are written
+		assert!(output.contains("function checkString("));
+		assert!(output.contains("function checkList("));
+		assert!(!output.contains("function checkInteger("));
+
+		assert!(output.ends_with(
+			r#"export interface User {
+  id: string;
+  tags?: string[];
+}
+
+export function isUser(value: unknown): value is User {
+  return matches(value, check$User);
+}
+
+export function parseUser(json: string): ParseResult<User> {
+  return parseWith<User>(json, check$User);
+}
+
+function check$User(value: unknown, path: string, errors: string[]): void {
+  checkObject(value, path, errors, [
+    ["id", true, checkString],
+    ["tags", false, checkList(checkString)],
+  ]);
+}
+"#
+		));
+	}
+
+	#[test]
+	fn names_guards_after_declaration_path() {
+		let schema = analyze(
+			"
+			interface User {
+				required status: Status;
+
+				enum Status {
+					A: a;
+				};
+			};
+
+			enum UserStatus {
+				B: b;
+			};
+			",
+		)
+		.unwrap();
+
+		let output = TypeScriptGenerator {
+			mode: TypeScriptMode::Guards,
+		}
+		.generate(&schema);
+
+		assert!(output.contains("export function isStatus(value: unknown): value is User.Status {\n    return matches(value, check$User$Status);"));
+		assert!(output.contains("export function isUserStatus(value: unknown): value is UserStatus {\n  return matches(value, check$UserStatus);"));
+		assert!(output.contains("[\"status\", true, check$User$Status]"));
+		assert_eq!(output.matches("function check$User$Status(").count(), 1);
+		assert_eq!(output.matches("function check$UserStatus(").count(), 1);
+	}
+
+	#[test]
+	fn renames_reserved_declaration_names() {
+		let schema = analyze(
+			"
+			interface delete {
+				required type: String;
+				optional return: delete.object;
+
+				interface object {
+					required value: Int;
+				};
+			};
+
+			union function = delete.object | delete;
+			",
+		)
+		.unwrap();
+
+		let output = TypeScriptGenerator {
+			mode: TypeScriptMode::Types,
+		}
+		.generate(&schema);
+
+		assert!(output.contains(
+			"export interface delete_ {\n  type: string;\n  return?: delete_.object_;\n}"
+		));
+		assert!(output.contains("export namespace delete_ {\n  export interface object_ {"));
+		assert!(output.contains("export type function_ = delete_.object_ | delete_;"));
+	}
+}
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use semantic::ir::{Constraint, DeclarationId, DeclarationKind, Field, Format, Schema, Type};
+
+//
+// Validators
+//
+// Shared logic for generated runtime validators. Every generator turns
+// constraints into checks of it's own language, but uses the same error
+// messages (`username: must be at least 3 characters long`) and patterns of
+// formats (`Format::pattern`).
+//
+
+// Error message of a failed check, without the variable name
+pub fn describe(constraint: &Constraint) -> String {
+	match constraint {
+		Constraint::MinLength(length) => format!("must be at least {} characters long", length),
+		Constraint::MaxLength(length) => format!("must be at most {} characters long", length),
+		Constraint::Pattern(pattern) => format!("must match pattern {}", pattern),
+		Constraint::Format(Format::Email) => "must be a valid email address".to_string(),
+		Constraint::Format(Format::Url) => "must be a valid URL".to_string(),
+		Constraint::Format(Format::Uuid) => "must be a valid UUID".to_string(),
+		Constraint::Minimum(value) => format!("must be greater than or equal to {}", value),
+		Constraint::Maximum(value) => format!("must be less than or equal to {}", value),
+		Constraint::MinItems(count) => format!("must contain at least {} items", count),
+		Constraint::MaxItems(count) => format!("must contain at most {} items", count),
+	}
+}
+
+// Interface a variable should be validated with (directly or as list items).
+// Aliases are followed, so it's always an interface
+pub enum NestedValidator {
+	Value(DeclarationId),
+	Items(DeclarationId),
+}
+
+pub fn nested_validator(
+	schema: &Schema,
+	field_type: &Type,
+	validated: &HashSet<DeclarationId>,
+) -> Option<NestedValidator> {
+	match schema.resolve_type(field_type) {
+		Type::Reference(id) if validated.contains(id) => Option::Some(NestedValidator::Value(*id)),
+		Type::List(item_type) => match schema.resolve_type(item_type) {
+			Type::Reference(id) if validated.contains(id) => {
+				Option::Some(NestedValidator::Items(*id))
+			}
+			_ => Option::None,
+		},
+		_ => Option::None,
+	}
+}
+
+// Whether variable has anything to check
+pub fn is_validated(schema: &Schema, field: &Field, validated: &HashSet<DeclarationId>) -> bool {
+	!field.constraints.is_empty()
+		|| nested_validator(schema, &field.field_type, validated).is_some()
+}
+
+// Interfaces which get a validator: the ones with constrained variables
+// and the ones referencing other validated interfaces
+pub fn validated_interfaces(schema: &Schema) -> HashSet<DeclarationId> {
+	let mut validated = HashSet::new();
+
+	loop {
+		let mut changed = false;
+
+		for declaration in schema.declarations() {
+			if validated.contains(&declaration.id) {
+				continue;
+			};
+
+			if let DeclarationKind::Interface(interface) = &declaration.kind {
+				if interface
+					.fields
+					.iter()
+					.any(|field| is_validated(schema, field, &validated))
+				{
+					validated.insert(declaration.id);
+					changed = true;
+				};
+			};
+		}
+
+		if !changed {
+			return validated;
+		};
+	}
+}
@@ -0,0 +1,58 @@
+//
+// Writer
+//
+// Small helper for generators: keeps track of indentation, so that
+// generators only care about what to write.
+pub struct Writer {
+	output: String,
+	indent: &'static str,
+	level: usize,
+}
+
+impl Writer {
+	pub fn new(indent: &'static str) -> Writer {
+		Self {
+			output: String::new(),
+			indent,
+			level: 0,
+		}
+	}
+
+	pub fn line(&mut self, line: impl AsRef<str>) {
+		let line = line.as_ref();
+
+		if !line.is_empty() {
+			for _ in 0..self.level {
+				self.output.push_str(self.indent);
+			}
+		};
+
+		self.output.push_str(line);
+		self.output.push('\n');
+	}
+
+	pub fn blank_line(&mut self) {
+		self.output.push('\n');
+	}
+
+	pub fn indent(&mut self) {
+		self.level += 1;
+	}
+
+	pub fn dedent(&mut self) {
+		self.level -= 1;
+	}
+
+	// Writes doc comment using given prefix for every line (`/// `, ` * `...)
+	pub fn docs(&mut self, docs: &Option<String>, prefix: &str) {
+		if let Some(docs) = docs {
+			for line in docs.lines() {
+				self.line(format!("{}{}", prefix, line).trim_end());
+			}
+		};
+	}
+
+	pub fn finish(self) -> String {
+		self.output
+	}
+}
@@ -0,0 +1,115 @@
+use serde_json::Value;
+
+//
+// YAML
+//
+// Minimal block-style YAML emitter for JSON values. Strings are written
+// plain when they can't be confused with anything else, double-quoted
+// (with JSON escapes, which are valid in YAML) otherwise.
+//
+pub fn to_yaml(value: &Value) -> String {
+	let mut output = String::new();
+
+	match value {
+		Value::Object(object) if !object.is_empty() => write_block(&mut output, value, 0, true),
+		Value::Array(items) if !items.is_empty() => write_block(&mut output, value, 0, true),
+		_ => {
+			output.push_str(&inline(value));
+			output.push('\n');
+		}
+	};
+
+	output
+}
+
+// Writes non-empty object or array, every line indented by `indent` spaces.
+// First line isn't indented when it continues a sequence item (`- key: value`)
+fn write_block(output: &mut String, value: &Value, indent: usize, indent_first: bool) {
+	let mut is_first = true;
+
+	let mut push_indent = |output: &mut String| {
+		if !is_first || indent_first {
+			output.push_str(&" ".repeat(indent));
+		};
+		is_first = false;
+	};
+
+	match value {
+		Value::Object(object) => {
+			for (key, value) in object.iter() {
+				push_indent(output);
+				output.push_str(&scalar(key));
+				output.push(':');
+
+				if is_block(value) {
+					output.push('\n');
+					write_block(output, value, indent + 2, true);
+				} else {
+					output.push(' ');
+					output.push_str(&inline(value));
+					output.push('\n');
+				};
+			}
+		}
+		Value::Array(items) => {
+			for item in items.iter() {
+				push_indent(output);
+				output.push('-');
+
+				if is_block(item) {
+					output.push(' ');
+					write_block(output, item, indent + 2, false);
+				} else {
+					output.push(' ');
+					output.push_str(&inline(item));
+					output.push('\n');
+				};
+			}
+		}
+		_ => unreachable!(),
+	};
+}
+
+fn is_block(value: &Value) -> bool {
+	match value {
+		Value::Object(object) => !object.is_empty(),
+		Value::Array(items) => !items.is_empty(),
+		_ => false,
+	}
+}
+
+// Scalars and empty collections
+fn inline(value: &Value) -> String {
+	match value {
+		Value::Null => "null".to_string(),
+		Value::Bool(value) => value.to_string(),
+		Value::Number(value) => value.to_string(),
+		Value::String(value) => scalar(value),
+		Value::Array(_) => "[]".to_string(),
+		Value::Object(_) => "{}".to_string(),
+	}
+}
+
+fn scalar(value: &str) -> String {
+	if is_plain(value) {
+		value.to_string()
+	} else {
+		Value::String(value.to_string()).to_string()
+	}
+}
+
+// Whether string can be written without quotes and still be read as the same string
+fn is_plain(value: &str) -> bool {
+	const KEYWORDS: &[&str] = &["true", "false", "null", "yes", "no", "on", "off", "y", "n"];
+
+	let mut chars = value.chars();
+
+	match chars.next() {
+		Some(char) if char.is_ascii_alphabetic() || char == '_' || char == '$' => {}
+		_ => return false,
+	};
+
+	!value.ends_with(' ')
+		&& !KEYWORDS.contains(&value.to_lowercase().as_str())
+		&& chars.all(|char| char.is_ascii_alphanumeric() || "_-./$ ".contains(char))
+}
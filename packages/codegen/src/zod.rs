@@ -0,0 +1,596 @@
+use std::collections::HashSet;
+
+use parser::errors::Diagnostic;
+use semantic::{
+	ir::{Constraint, Declaration, DeclarationId, DeclarationKind, Field, Format, Schema, Type},
+	ReservedWords,
+};
+
+use crate::{
+	helpers::{check_flat_names, flat_name},
+	quote,
+	typescript::is_identifier,
+	validators::describe,
+	writer::Writer,
+	Generator,
+};
+
+//
+// Zod generator
+//
+// Every declaration becomes a zod schema and a type inferred from it
+// (`export const User = z.object(...)` with `export type User = ...`),
+// so that API responses can be validated at runtime with `User.parse()`.
+// Constants become `z.literal`, constraints become `.min()`, `.regex()`...
+// String length is counted in code points like in other targets, zod's own
+// `.min()` counts UTF-16 units, so lengths are checked with `.refine()`.
+// Nested declarations are flattened (`UserStatus`).
+//
+// Schemas are declared before they're used. Recursive declarations
+// can't be inferred, so their types are written out and the schema
+// is wrapped into `z.lazy`.
+pub struct ZodGenerator;
+
+impl Generator for ZodGenerator {
+	fn name(&self) -> &'static str {
+		"zod"
+	}
+
+	fn extension(&self) -> &'static str {
+		"ts"
+	}
+
+	fn reserved_words(&self) -> ReservedWords {
+		// Flattened names are PascalCase, so they can't be keywords
+		ReservedWords {
+			target: "Zod",
+			declarations: &[],
+			members: &[],
+		}
+	}
+
+	// Flattened names have to stay unique
+	fn check(&self, schema: &Schema) -> Vec<Diagnostic> {
+		check_flat_names(schema.declarations(), "Zod", flat_name)
+	}
+
+	fn generate(&self, schema: &Schema) -> String {
+		let mut writer = Writer::new("  ");
+
+		writer.line("import { z } from \"zod\";");
+
+		let components = sorted_components(schema);
+
+		// Declarations whose schemas are wrapped into `z.lazy`
+		let lazy: HashSet<DeclarationId> = components
+			.iter()
+			.filter(|component| {
+				component.len() > 1
+					|| dependencies(schema.get(component[0])).contains(&component[0])
+			})
+			.flatten()
+			.copied()
+			.collect();
+
+		for component in components {
+			for id in component {
+				let declaration = schema.get(id);
+
+				writer.blank_line();
+				write_docs(&mut writer, &declaration.docs);
+
+				if lazy.contains(&id) {
+					write_recursive(&mut writer, schema, declaration, &lazy);
+				} else {
+					writer.line(format!(
+						"export const {} = {};",
+						flat_name(declaration),
+						declaration_schema(schema, declaration, &lazy)
+					));
+					writer.line(format!(
+						"export type {} = z.infer<typeof {}>;",
+						flat_name(declaration),
+						flat_name(declaration)
+					));
+				};
+			}
+		}
+
+		writer.finish()
+	}
+}
+
+// Example:
+// export type User = {
+//   friends?: User[];
+// };
+// export const User: z.ZodType<User> = z.lazy(() => z.object({ ... }));
+fn write_recursive(
+	writer: &mut Writer,
+	schema: &Schema,
+	declaration: &Declaration,
+	lazy: &HashSet<DeclarationId>,
+) {
+	let name = flat_name(declaration);
+
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			writer.line(format!("export type {} = {{", name));
+			writer.indent();
+
+			for field in interface.fields.iter() {
+				let optional = if field.is_required { "" } else { "?" };
+				writer.line(format!(
+					"{}{}: {};",
+					property_name(&field.name),
+					optional,
+					type_name(schema, &field.field_type)
+				));
+			}
+
+			writer.dedent();
+			writer.line("};");
+		}
+		DeclarationKind::Alias(alias) => writer.line(format!(
+			"export type {} = {};",
+			name,
+			type_name(schema, &alias.target)
+		)),
+		DeclarationKind::Union(union) => {
+			let members: Vec<String> = union
+				.members
+				.iter()
+				.map(|member| flat_name(schema.get(*member)))
+				.collect();
+
+			writer.line(format!("export type {} = {};", name, members.join(" | ")));
+		}
+		// Enums don't reference anything
+		DeclarationKind::Enum(_) => {}
+	};
+
+	writer.line(format!(
+		"export const {}: z.ZodType<{}> = z.lazy(() => {});",
+		name,
+		name,
+		declaration_schema(schema, declaration, lazy)
+	));
+}
+
+fn declaration_schema(
+	schema: &Schema,
+	declaration: &Declaration,
+	lazy: &HashSet<DeclarationId>,
+) -> String {
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			// Lazy schemas can't be extended, so inherited fields are
+			// written out when either side is lazy
+			let parent = interface
+				.extends
+				.filter(|parent| !lazy.contains(&declaration.id) && !lazy.contains(parent))
+				.map(|parent| schema.get(parent));
+
+			let fields: Vec<&Field> = match parent {
+				Some(_) => interface.own_fields().collect(),
+				None => interface.fields.iter().collect(),
+			};
+
+			let properties: Vec<String> = fields
+				.iter()
+				.map(|field| {
+					let mut value = type_schema(schema, &field.field_type, &field.constraints);
+					if !field.is_required {
+						value.push_str(".optional()");
+					};
+
+					format!("{}: {}", property_name(&field.name), value)
+				})
+				.collect();
+
+			let properties = if properties.is_empty() {
+				"{}".to_string()
+			} else {
+				format!("{{\n  {},\n}}", properties.join(",\n  "))
+			};
+
+			match parent {
+				Some(parent) => format!("{}.extend({})", flat_name(parent), properties),
+				None => format!("z.object({})", properties),
+			}
+		}
+		DeclarationKind::Enum(enumerate) => {
+			let values: Vec<String> = enumerate
+				.variants
+				.iter()
+				.map(|variant| quote(variant.value.as_ref().unwrap_or(&variant.name)))
+				.collect();
+
+			format!("z.enum([{}])", values.join(", "))
+		}
+		DeclarationKind::Alias(alias) => type_schema(schema, &alias.target, &[]),
+		DeclarationKind::Union(union) => {
+			let members: Vec<String> = union
+				.members
+				.iter()
+				.map(|member| flat_name(schema.get(*member)))
+				.collect();
+
+			// `z.union` needs at least two options
+			match members.as_slice() {
+				[member] => member.clone(),
+				_ => format!("z.union([{}])", members.join(", ")),
+			}
+		}
+	}
+}
+
+pub fn type_schema(schema: &Schema, field_type: &Type, constraints: &[Constraint]) -> String {
+	let mut result = match field_type {
+		Type::String(None) => "z.string()".to_string(),
+		Type::String(Some(value)) => format!("z.literal({})", quote(value)),
+		Type::Boolean => "z.boolean()".to_string(),
+		Type::Integer => "z.number().int()".to_string(),
+		Type::List(item_type) => format!("z.array({})", type_schema(schema, item_type, &[])),
+		Type::Map(key_type, value_type) => {
+			// Object keys are always strings
+			let key = match schema.resolve_type(key_type) {
+				Type::Integer => "z.string().regex(/^-?[0-9]+$/)".to_string(),
+				_ => type_schema(schema, key_type, &[]),
+			};
+
+			format!(
+				"z.record({}, {})",
+				key,
+				type_schema(schema, value_type, &[])
+			)
+		}
+		Type::Reference(id) => flat_name(schema.get(*id)),
+	};
+
+	// Refinements turn a schema into `ZodEffects`, so they go last
+	let mut refinements = Vec::new();
+
+	for constraint in constraints.iter() {
+		result.push_str(&match constraint {
+			Constraint::MinLength(length) => {
+				refinements.push(format!(
+					".refine((value) => [...value].length >= {}, {})",
+					length,
+					quote(&describe(constraint))
+				));
+				continue;
+			}
+			Constraint::MaxLength(length) => {
+				refinements.push(format!(
+					".refine((value) => [...value].length <= {}, {})",
+					length,
+					quote(&describe(constraint))
+				));
+				continue;
+			}
+			Constraint::MinItems(count) => format!(".min({})", count),
+			Constraint::MaxItems(count) => format!(".max({})", count),
+			Constraint::Minimum(value) => format!(".min({})", value),
+			Constraint::Maximum(value) => format!(".max({})", value),
+			Constraint::Pattern(pattern) => format!(".regex(new RegExp({}))", quote(pattern)),
+			Constraint::Format(Format::Email) => ".email()".to_string(),
+			Constraint::Format(Format::Url) => ".url()".to_string(),
+			Constraint::Format(Format::Uuid) => ".uuid()".to_string(),
+		});
+	}
+
+	result.extend(refinements);
+	result
+}
+
+// TypeScript type, only written for recursive declarations
+fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match field_type {
+		Type::String(None) => "string".to_string(),
+		Type::String(Some(value)) => quote(value),
+		Type::Boolean => "boolean".to_string(),
+		Type::Integer => "number".to_string(),
+		Type::List(item_type) => format!("{}[]", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => {
+			let key = match schema.resolve_type(key_type) {
+				Type::Integer => "string".to_string(),
+				_ => type_name(schema, key_type),
+			};
+
+			format!("Record<{}, {}>", key, type_name(schema, value_type))
+		}
+		Type::Reference(id) => flat_name(schema.get(*id)),
+	}
+}
+
+fn property_name(name: &str) -> String {
+	if is_identifier(name) {
+		name.to_string()
+	} else {
+		quote(name)
+	}
+}
+
+//
+// Ordering
+//
+// Declarations are grouped into strongly connected components of their
+// references (Tarjan's algorithm), which come out with dependencies first.
+// Components with more than one declaration, or with a declaration
+// referencing itself, are recursive.
+fn sorted_components(schema: &Schema) -> Vec<Vec<DeclarationId>> {
+	struct State {
+		index: usize,
+		indexes: Vec<Option<usize>>,
+		lowlinks: Vec<usize>,
+		stack: Vec<DeclarationId>,
+		on_stack: Vec<bool>,
+		components: Vec<Vec<DeclarationId>>,
+	}
+
+	fn visit(schema: &Schema, state: &mut State, id: DeclarationId) {
+		state.indexes[id.0] = Option::Some(state.index);
+		state.lowlinks[id.0] = state.index;
+		state.index += 1;
+		state.stack.push(id);
+		state.on_stack[id.0] = true;
+
+		for dependency in dependencies(schema.get(id)) {
+			match state.indexes[dependency.0] {
+				None => {
+					visit(schema, state, dependency);
+					state.lowlinks[id.0] = state.lowlinks[id.0].min(state.lowlinks[dependency.0]);
+				}
+				Some(index) if state.on_stack[dependency.0] => {
+					state.lowlinks[id.0] = state.lowlinks[id.0].min(index);
+				}
+				Some(_) => {}
+			};
+		}
+
+		if Option::Some(state.lowlinks[id.0]) == state.indexes[id.0] {
+			let mut component = Vec::new();
+
+			while let Some(member) = state.stack.pop() {
+				state.on_stack[member.0] = false;
+				component.push(member);
+
+				if member == id {
+					break;
+				};
+			}
+
+			component.sort();
+			state.components.push(component);
+		};
+	}
+
+	let count = schema.declarations().len();
+	let mut state = State {
+		index: 0,
+		indexes: vec![Option::None; count],
+		lowlinks: vec![0; count],
+		stack: Vec::new(),
+		on_stack: vec![false; count],
+		components: Vec::new(),
+	};
+
+	for declaration in schema.declarations() {
+		if state.indexes[declaration.id.0].is_none() {
+			visit(schema, &mut state, declaration.id);
+		};
+	}
+
+	state.components
+}
+
+// Declarations this one references
+fn dependencies(declaration: &Declaration) -> Vec<DeclarationId> {
+	fn collect(field_type: &Type, result: &mut Vec<DeclarationId>) {
+		match field_type {
+			Type::Reference(id) => result.push(*id),
+			Type::List(item_type) => collect(item_type, result),
+			Type::Map(key_type, value_type) => {
+				collect(key_type, result);
+				collect(value_type, result);
+			}
+			_ => {}
+		};
+	}
+
+	let mut result = Vec::new();
+
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			if let Some(parent) = interface.extends {
+				result.push(parent);
+			};
+
+			for field in interface.fields.iter() {
+				collect(&field.field_type, &mut result);
+			}
+		}
+		DeclarationKind::Alias(alias) => collect(&alias.target, &mut result),
+		DeclarationKind::Union(union) => result.extend(union.members.iter().copied()),
+		DeclarationKind::Enum(_) => {}
+	};
+
+	result
+}
+
+fn write_docs(writer: &mut Writer, docs: &Option<String>) {
+	if docs.is_some() {
+		writer.line("/**");
+		writer.docs(docs, " * ");
+		writer.line(" */");
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use semantic::analyze;
+
+	use super::*;
+
+	#[test]
+	fn generates_schemas_in_dependency_order() {
+		let schema = analyze(
+			"
+			/// Registered user
+			interface User {
+				#min_length = 3
+				required user_name: String;
+				#format = email
+				optional email: String;
+				required kind: String { \"user\" };
+				required status: User.Status;
+				optional friends: List<User>;
+
+				enum Status {
+					Registered: registered;
+				};
+			};
+
+			interface Admin extends Account {
+				#min = 1
+				required level: Int;
+			};
+
+			interface Account {
+				required id: String;
+			};
+
+			union Anyone = User | Admin;
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			ZodGenerator.generate(&schema),
+			r#"import { z } from "zod";
+
+export const UserStatus = z.enum(["registered"]);
+export type UserStatus = z.infer<typeof UserStatus>;
+
+/**
+ * Registered user
+ */
+export type User = {
+  user_name: string;
+  email?: string;
+  kind: "user";
+  status: UserStatus;
+  friends?: User[];
+};
+export const User: z.ZodType<User> = z.lazy(() => z.object({
+  user_name: z.string().refine((value) => [...value].length >= 3, "must be at least 3 characters long"),
+  email: z.string().email().optional(),
+  kind: z.literal("user"),
+  status: UserStatus,
+  friends: z.array(User).optional(),
+}));
+
+export const Account = z.object({
+  id: z.string(),
+});
+export type Account = z.infer<typeof Account>;
+
+export const Admin = Account.extend({
+  level: z.number().int().min(1),
+});
+export type Admin = z.infer<typeof Admin>;
+
+export const Anyone = z.union([User, Admin]);
+export type Anyone = z.infer<typeof Anyone>;
+"#
+		);
+	}
+
+	#[test]
+	fn inlines_fields_of_lazy_parents() {
+		let schema = analyze(
+			"
+			interface Node {
+				optional next: Node;
+			};
+
+			interface Child extends Node {
+				required value: Int;
+			};
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			ZodGenerator.generate(&schema),
+			r#"import { z } from "zod";
+
+export type Node = {
+  next?: Node;
+};
+export const Node: z.ZodType<Node> = z.lazy(() => z.object({
+  next: Node.optional(),
+}));
+
+export const Child = z.object({
+  next: Node.optional(),
+  value: z.number().int(),
+});
+export type Child = z.infer<typeof Child>;
+"#
+		);
+	}
+
+	#[test]
+	fn keeps_subtypes_first_in_unions() {
+		let schema = analyze(
+			"
+			interface User {
+				required id: String;
+			};
+
+			interface Admin extends User {
+				required level: Int;
+			};
+
+			union Anyone = Admin | User;
+			",
+		)
+		.unwrap();
+
+		// `z.object` strips unknown keys, so `User` first would lose `level`
+		assert!(ZodGenerator
+			.generate(&schema)
+			.contains("export const Anyone = z.union([Admin, User]);"));
+	}
+
+	#[test]
+	fn reports_flattened_name_collisions() {
+		let schema = analyze(
+			"
+			interface User {
+				enum Status {
+					A: a;
+				};
+			};
+
+			enum UserStatus {
+				B: b;
+			};
+			",
+		)
+		.unwrap();
+
+		let messages: Vec<String> = ZodGenerator
+			.check(&schema)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec!["`User.Status` and `UserStatus` are both named `UserStatus` in Zod"]
+		);
+	}
+}
@@ -0,0 +1,415 @@
+use lexer::tokens::TokenType;
+use parser::{
+	errors::Diagnostic,
+	syntax::{parse, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken},
+};
+
+//
+// Canonical formatting of .typm files
+//
+// Source goes through the lossless syntax tree, so comments survive
+// formatting. Rules:
+// - one member per line, indented by `indent_width` spaces per level
+// - single space around `=`, `|` and `{ }`, after `:` and `,`
+// - doc comments go before attributes, attributes are sorted by name
+// - at most one blank line between members, exactly one between
+//   top-level declarations
+// - comments keep their line, a comment inside a member is moved
+//   to the end of it's line
+//
+// Formatting is idempotent: formatted source formats to itself.
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+	// Spaces per indentation level
+	pub indent_width: usize,
+}
+
+impl Default for FormatOptions {
+	fn default() -> Self {
+		FormatOptions { indent_width: 4 }
+	}
+}
+
+// Source with syntax errors is never formatted, parse diagnostics are returned instead
+pub fn format(source: &str, options: &FormatOptions) -> Result<String, Vec<Diagnostic>> {
+	let parse = parse(source);
+
+	if !parse.diagnostics.is_empty() {
+		return Err(parse.diagnostics);
+	};
+
+	let mut formatter = Formatter {
+		options,
+		lines: Vec::new(),
+		level: 0,
+	};
+	formatter.block(&parse.root.children, true);
+
+	if formatter.lines.is_empty() {
+		return Ok(String::new());
+	};
+
+	Ok(formatter.lines.join("\n") + "\n")
+}
+
+struct Formatter<'a> {
+	options: &'a FormatOptions,
+	lines: Vec<String>,
+	level: usize,
+}
+
+impl Formatter<'_> {
+	fn line(&mut self, text: &str) {
+		let indent = " ".repeat(self.level * self.options.indent_width);
+		self.lines.push(format!("{}{}", indent, text));
+	}
+
+	fn blank_line(&mut self) {
+		self.lines.push(String::new());
+	}
+
+	// Comment at the end of the last line
+	fn trailing(&mut self, comment: &SyntaxToken) {
+		match self.lines.last_mut() {
+			Some(line) => {
+				line.push(' ');
+				line.push_str(comment.text.trim_end());
+			}
+			None => self.line(comment.text.trim_end()),
+		};
+	}
+
+	//
+	// Members of a source file, an interface or an enum
+	//
+	fn block(&mut self, elements: &[SyntaxElement], top_level: bool) {
+		let mut newlines = 0;
+		let mut first = true;
+		let mut after_item = false;
+
+		for element in elements {
+			match element {
+				SyntaxElement::Token(token) if token.kind == TokenType::Whitespace => {
+					newlines += token.text.matches('\n').count();
+				}
+				SyntaxElement::Token(token) if token.kind == TokenType::Comment => {
+					if newlines == 0 && !self.lines.is_empty() {
+						self.trailing(token);
+					} else {
+						if !first && (newlines > 1 || (top_level && after_item)) {
+							self.blank_line();
+						};
+
+						self.line(token.text.trim_end());
+						first = false;
+						after_item = false;
+					};
+
+					newlines = 0;
+				}
+				SyntaxElement::Node(node) => {
+					if !first && (newlines > 1 || (top_level && after_item)) {
+						self.blank_line();
+					};
+
+					self.item(node);
+					first = false;
+					after_item = true;
+					newlines = 0;
+				}
+				// Only trivia and items are left in a valid tree
+				SyntaxElement::Token(_) => {}
+			};
+		}
+	}
+
+	//
+	// Declaration, variable or enum variant
+	//
+	// Structure:
+	// [metadata] [header] ({ [block] } ;)?
+	fn item(&mut self, node: &SyntaxNode) {
+		let children = &node.children;
+
+		// First token after doc comments and attributes
+		let start = children
+			.iter()
+			.position(|child| match child {
+				SyntaxElement::Token(token) => {
+					!token.kind.is_trivia() && token.kind != TokenType::DocComment
+				}
+				SyntaxElement::Node(_) => false,
+			})
+			.unwrap_or(children.len());
+
+		self.metadata(&children[..start]);
+
+		let open = position(children, TokenType::RightCurlyBraces);
+		let close = position(children, TokenType::LeftCurlyBraces);
+
+		match (node.kind, open, close) {
+			(SyntaxKind::Interface | SyntaxKind::Enum, Some(open), Some(close)) => {
+				let body = &children[open + 1..close];
+				let is_empty = body.iter().all(|child| match child {
+					SyntaxElement::Token(token) => token.kind == TokenType::Whitespace,
+					SyntaxElement::Node(_) => false,
+				});
+
+				let header = render(&children[start..=open]);
+
+				if is_empty {
+					self.line(&format!("{}}};", header));
+				} else {
+					self.line(&header);
+				};
+
+				for comment in comments(&children[start..open]) {
+					self.trailing(comment);
+				}
+
+				if !is_empty {
+					self.level += 1;
+					self.block(body, false);
+					self.level -= 1;
+
+					self.line("};");
+				};
+
+				for comment in comments(&children[close..]) {
+					self.trailing(comment);
+				}
+			}
+			_ => {
+				self.line(&render(&children[start..]));
+
+				for comment in comments(&children[start..]) {
+					self.trailing(comment);
+				}
+			}
+		};
+	}
+
+	// Comments, then doc comments, then sorted attributes
+	fn metadata(&mut self, elements: &[SyntaxElement]) {
+		for comment in comments(elements) {
+			self.line(comment.text.trim_end());
+		}
+
+		for element in elements {
+			if let SyntaxElement::Token(token) = element {
+				if token.kind == TokenType::DocComment {
+					self.line(token.text.trim_end());
+				};
+			};
+		}
+
+		let mut attributes: Vec<&SyntaxNode> = elements
+			.iter()
+			.filter_map(|element| match element {
+				SyntaxElement::Node(node) if node.kind == SyntaxKind::Attribute => {
+					Option::Some(node)
+				}
+				_ => Option::None,
+			})
+			.collect();
+
+		attributes.sort_by_key(|attribute| {
+			attribute
+				.first_token(TokenType::Text)
+				.map(|token| token.text.clone())
+		});
+
+		for attribute in attributes {
+			self.line(&render(&attribute.children));
+		}
+	}
+}
+
+fn position(elements: &[SyntaxElement], kind: TokenType) -> Option<usize> {
+	elements.iter().position(|element| match element {
+		SyntaxElement::Token(token) => token.kind == kind,
+		SyntaxElement::Node(_) => false,
+	})
+}
+
+// Comments of the elements and their descendants
+fn comments(elements: &[SyntaxElement]) -> Vec<&SyntaxToken> {
+	let mut comments = Vec::new();
+
+	for element in elements {
+		match element {
+			SyntaxElement::Token(token) => {
+				if token.kind == TokenType::Comment {
+					comments.push(token);
+				};
+			}
+			SyntaxElement::Node(node) => comments.extend(
+				node.descendant_tokens()
+					.into_iter()
+					.filter(|token| token.kind == TokenType::Comment),
+			),
+		};
+	}
+
+	comments
+}
+
+//
+// Elements on a single line with normalized spacing
+//
+// Quoted strings are kept as they are.
+fn render(elements: &[SyntaxElement]) -> String {
+	let mut units: Vec<(TokenType, String)> = Vec::new();
+	collect_units(elements, &mut units);
+
+	let mut output = String::new();
+	let mut previous: Option<&TokenType> = Option::None;
+
+	for (kind, text) in units.iter() {
+		if let Some(previous) = previous {
+			if needs_space(previous, kind) {
+				output.push(' ');
+			};
+		};
+
+		output.push_str(text);
+		previous = Option::Some(kind);
+	}
+
+	output
+}
+
+fn collect_units(elements: &[SyntaxElement], units: &mut Vec<(TokenType, String)>) {
+	for element in elements {
+		match element {
+			SyntaxElement::Node(node) => collect_units(&node.children, units),
+			SyntaxElement::Token(token) => {
+				if !token.kind.is_trivia() {
+					units.push((token.kind.clone(), token.text.clone()));
+				};
+			}
+		};
+	}
+}
+
+fn needs_space(previous: &TokenType, next: &TokenType) -> bool {
+	!matches!(
+		next,
+		TokenType::Semicolon
+			| TokenType::VariableConnection
+			| TokenType::GenericSeparator
+			| TokenType::PathSeparator
+			| TokenType::GenericStart
+			| TokenType::GenericEnd
+	) && !matches!(
+		previous,
+		TokenType::PathSeparator | TokenType::GenericStart | TokenType::AttributeDeclaration
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn format_default(source: &str) -> String {
+		format(source, &FormatOptions::default()).unwrap()
+	}
+
+	#[test]
+	fn formats_declarations() {
+		let source = "interface   User extends Base . Entity{
+  required id :String;optional tags:List< String >;
+		#min=1
+		/// Age in years
+		#max = 150
+		optional age: Int;
+
+
+
+	required kind: String{\"user  kind\"};
+	enum Status{ACTIVE:active;};
+	interface Empty {
+	};
+};
+alias Scores=Map<String,Int>;
+union Anyone=User|User.Empty;";
+
+		assert_eq!(
+			format_default(source),
+			"interface User extends Base.Entity {
+    required id: String;
+    optional tags: List<String>;
+    /// Age in years
+    #max = 150
+    #min = 1
+    optional age: Int;
+
+    required kind: String { \"user  kind\" };
+    enum Status {
+        ACTIVE: active;
+    };
+    interface Empty {};
+};
+
+alias Scores = Map<String, Int>;
+
+union Anyone = User | User.Empty;
+"
+		);
+	}
+
+	#[test]
+	fn keeps_comments() {
+		let source = "// Schema of users
+/// User
+interface User { // users
+	// Identifier
+	required id: // moved
+		String;
+	#deprecated // old
+	optional name: String; // display name
+}; // end
+// Other
+alias Id = String;
+";
+
+		let formatted = format_default(source);
+
+		assert_eq!(
+			formatted,
+			"// Schema of users
+/// User
+interface User { // users
+    // Identifier
+    required id: String; // moved
+    // old
+    #deprecated
+    optional name: String; // display name
+}; // end
+
+// Other
+alias Id = String;
+"
+		);
+		assert_eq!(format_default(&formatted), formatted);
+	}
+
+	#[test]
+	fn uses_indent_width() {
+		let options = FormatOptions { indent_width: 2 };
+
+		assert_eq!(
+			format("enum A { B: b; };", &options).unwrap(),
+			"enum A {\n  B: b;\n};\n"
+		);
+	}
+
+	#[test]
+	fn rejects_invalid_source() {
+		assert!(format(
+			"interface User { required id String; };",
+			&FormatOptions::default()
+		)
+		.is_err());
+	}
+}
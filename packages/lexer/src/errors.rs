@@ -0,0 +1,20 @@
+use core::ops::Range;
+
+// Problem found while tokenizing, the span points into the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexError {
+	pub message: String,
+	// Short explanation under the span
+	pub label: String,
+	pub span: Range<usize>,
+}
+
+impl LexError {
+	pub fn new(message: impl Into<String>, label: &str, span: Range<usize>) -> LexError {
+		Self {
+			message: message.into(),
+			label: label.to_string(),
+			span,
+		}
+	}
+}
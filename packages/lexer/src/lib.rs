@@ -1,24 +1,216 @@
+use errors::LexError;
 use logos::Logos;
-use tokens::{TokenDeclaration, TokenType};
+use strings::decode_string;
+use tokens::{Token, TokenDeclaration, TokenType};
 
+pub mod errors;
+pub mod strings;
 pub mod tokens;
 
+//
+// Lazy token stream
+//
+// Tokens are produced one at a time, while the source is being read, and
+// borrow their text from it. Parsers work on this stream directly, so the
+// source is tokenized without any per-token allocation.
+//
+// Lexical problems are collected along the way, tokens are produced anyway:
+// - unexpected characters become `Error` tokens
+// - unterminated strings and invalid escapes are `StringLiteral` tokens
+// - invalid numbers (`12px`) are `Text` tokens
+pub struct Tokens<'src> {
+	lexer: logos::Lexer<'src, TokenType>,
+	diagnostics: Vec<LexError>,
+}
+
+impl<'src> Tokens<'src> {
+	// Problems found in the tokens produced so far
+	pub fn diagnostics(&self) -> &[LexError] {
+		&self.diagnostics
+	}
+
+	pub fn into_diagnostics(self) -> Vec<LexError> {
+		self.diagnostics
+	}
+
+	fn check(&mut self, token: &Token) {
+		match token.token_type {
+			TokenType::Error => {
+				// Consecutive unexpected characters are reported once
+				if let Some(last) = self.diagnostics.last_mut() {
+					if last.span.end == token.span.start && last.label == UNEXPECTED {
+						last.span.end = token.span.end;
+						last.message = format!(
+							"unexpected characters `{}`",
+							&self.lexer.source()[last.span.clone()]
+						);
+						return;
+					};
+				};
+
+				self.diagnostics.push(LexError::new(
+					format!("unexpected character `{}`", token.value),
+					UNEXPECTED,
+					token.span.clone(),
+				));
+			}
+			TokenType::StringLiteral => {
+				if let Err(error) = decode_string(token.value, token.span.start) {
+					self.diagnostics.push(error);
+				};
+			}
+			TokenType::Text
+				if token.value.starts_with(|char: char| char.is_ascii_digit())
+					&& !token.value.chars().all(|char| char.is_ascii_digit()) =>
+			{
+				self.diagnostics.push(LexError::new(
+					format!("invalid number `{}`", token.value),
+					"numbers can only contain digits and identifiers can't start with one",
+					token.span.clone(),
+				));
+			}
+			_ => {}
+		};
+	}
+}
+
+const UNEXPECTED: &str = "not a part of any token";
+
+impl<'src> Iterator for Tokens<'src> {
+	type Item = Token<'src>;
+
+	fn next(&mut self) -> Option<Token<'src>> {
+		let token_type = self.lexer.next()?;
+		let token = Token {
+			token_type,
+			value: self.lexer.slice(),
+			span: self.lexer.span(),
+		};
+
+		self.check(&token);
+		Option::Some(token)
+	}
+}
+
+pub fn tokenize(source: &str) -> Tokens<'_> {
+	Tokens {
+		lexer: TokenType::lexer(source),
+		diagnostics: Vec::new(),
+	}
+}
+
+// Every token of the source along with lexical problems
+pub fn lex(source: &str) -> (Vec<Token<'_>>, Vec<LexError>) {
+	let mut tokens = tokenize(source);
+	let collected = tokens.by_ref().collect();
+
+	(collected, tokens.into_diagnostics())
+}
+
+// Owned tokens, for callers which need them to outlive the source
 pub fn get_tokens(source: &str) -> Vec<TokenDeclaration> {
-	let mut declarations = Vec::<TokenDeclaration>::new();
-	let mut lexer = TokenType::lexer(source);
+	tokenize(source)
+		.map(|token| token.to_declaration())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		lex, tokenize,
+		tokens::{identifier_name, TokenType},
+	};
+
+	#[test]
+	fn borrows_token_text_from_source() {
+		let source = "alias Id = String; // comment";
+		let tokens: Vec<_> = tokenize(source).collect();
+
+		assert_eq!(tokens.len(), 10);
+		assert_eq!(tokens[2].token_type, TokenType::Text);
+		assert_eq!(tokens[2].value, "Id");
+		assert_eq!(tokens[2].span, 6..8);
+		assert_eq!(tokens[9].token_type, TokenType::Comment);
+
+		// Slices point into the source itself
+		assert!(std::ptr::eq(tokens[2].value, &source[6..8]));
+	}
 
-	let mut current_token = lexer.next();
+	#[test]
+	fn lexes_string_literals_as_single_tokens() {
+		let source = "\"a \\\" b\" r\"\\d\" \"\"\"\n  x \"\" y\n\"\"\" \"unclosed\nText";
+		let tokens: Vec<_> = tokenize(source)
+			.filter(|token| !token.token_type.is_trivia())
+			.map(|token| (token.token_type, token.value))
+			.collect();
 
-	while current_token != None {
-		declarations.push(TokenDeclaration {
-			token_type: current_token.unwrap(),
-			value: Option::Some(lexer.slice().to_string()),
-			span: lexer.span(),
-		});
+		assert_eq!(
+			tokens,
+			vec![
+				(TokenType::StringLiteral, "\"a \\\" b\""),
+				(TokenType::StringLiteral, "r\"\\d\""),
+				(TokenType::StringLiteral, "\"\"\"\n  x \"\" y\n\"\"\""),
+				// Unclosed string ends at the end of the line
+				(TokenType::StringLiteral, "\"unclosed"),
+				(TokenType::Text, "Text"),
+			]
+		);
+	}
+
+	#[test]
+	fn accepts_crlf_bom_and_unicode_identifiers() {
+		let source = "\u{FEFF}/// Docs\r\ninterface Пользователь { // comment\r\n\trequired café_2: String;\r\n};\r\n";
+		let (tokens, diagnostics) = lex(source);
+
+		assert!(diagnostics.is_empty());
+		assert_eq!(tokens[0].token_type, TokenType::Whitespace);
+		assert_eq!(tokens[1].value, "/// Docs");
+		assert_eq!(tokens[5].value, "Пользователь");
+		assert_eq!(tokens[9].value, "// comment");
+		assert_eq!(tokens[13].value, "café_2");
+	}
+
+	#[test]
+	fn lexes_raw_identifiers() {
+		let tokens: Vec<_> = tokenize("r#enum r\"raw\" r #x")
+			.filter(|token| !token.token_type.is_trivia())
+			.map(|token| (token.token_type, token.value))
+			.collect();
 
-		// Updating current token information
-		current_token = lexer.next();
+		assert_eq!(
+			tokens,
+			vec![
+				(TokenType::Text, "r#enum"),
+				(TokenType::StringLiteral, "r\"raw\""),
+				(TokenType::Text, "r"),
+				(TokenType::AttributeDeclaration, "#"),
+				(TokenType::Text, "x"),
+			]
+		);
+		assert_eq!(identifier_name("r#enum"), "enum");
+		assert_eq!(identifier_name("type"), "type");
 	}
 
-	declarations
+	#[test]
+	fn reports_lexical_errors() {
+		let source = "alias €€ Id = 12px; #min = 10\n\"open";
+		let (tokens, diagnostics) = lex(source);
+
+		let errors: Vec<(&str, &str)> = diagnostics
+			.iter()
+			.map(|error| (error.message.as_str(), &source[error.span.clone()]))
+			.collect();
+
+		assert_eq!(
+			errors,
+			vec![
+				("unexpected characters `€€`", "€€"),
+				("invalid number `12px`", "12px"),
+				("unterminated string", "\"open"),
+			]
+		);
+
+		// Tokens are produced anyway
+		assert_eq!(tokens.last().unwrap().token_type, TokenType::StringLiteral);
+	}
 }
@@ -0,0 +1,309 @@
+use logos::Lexer;
+
+use crate::{errors::LexError, tokens::TokenType};
+
+//
+// String literals
+//
+// Example:
+// "Escaped \"quotes\"\n and \u{1F600}"
+// r"^[a-z0-9_\-]+$"
+// """
+//     Multi-line text,
+//     common indentation is stripped
+//     """
+//
+// Regular strings support `\n`, `\r`, `\t`, `\0`, `\\`, `\"`, `\'` and
+// `\u{...}` escapes. Raw strings keep their text as is. Both end at the end
+// of the line. Triple-quoted strings can span lines: a blank first and last
+// line are dropped, the smallest indentation of the other lines is stripped
+// and then escapes are decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringKind {
+	Regular,
+	Raw,
+	Block,
+}
+
+impl StringKind {
+	// Kind of the literal, determined by it's opening quotes
+	pub fn of(text: &str) -> StringKind {
+		if text.starts_with("\"\"\"") {
+			StringKind::Block
+		} else if text.starts_with("r\"") {
+			StringKind::Raw
+		} else {
+			StringKind::Regular
+		}
+	}
+
+	fn opening(&self) -> usize {
+		match self {
+			StringKind::Regular => 1,
+			StringKind::Raw => 2,
+			StringKind::Block => 3,
+		}
+	}
+
+	fn closing(&self) -> usize {
+		match self {
+			StringKind::Regular | StringKind::Raw => 1,
+			StringKind::Block => 3,
+		}
+	}
+}
+
+// Length of the literal's body with the closing quotes,
+// `None` if the quotes are missing
+fn body_length(body: &str, kind: StringKind) -> Option<usize> {
+	let mut chars = body.char_indices();
+
+	while let Some((index, char)) = chars.next() {
+		match char {
+			'\\' if kind != StringKind::Raw => {
+				chars.next();
+			}
+			'"' if kind != StringKind::Block => return Option::Some(index + 1),
+			'"' if body[index..].starts_with("\"\"\"") => return Option::Some(index + 3),
+			'\r' | '\n' if kind != StringKind::Block => return Option::None,
+			_ => {}
+		};
+	}
+
+	Option::None
+}
+
+// Lexer callback, called after the opening quotes
+pub(crate) fn lex_string(lexer: &mut Lexer<TokenType>, kind: StringKind) {
+	let remainder = lexer.remainder();
+
+	// Unclosed literal goes until the end of the line (or of the file for
+	// triple-quoted ones) and is reported when it's decoded
+	let length = body_length(remainder, kind).unwrap_or_else(|| match kind {
+		StringKind::Block => remainder.len(),
+		_ => remainder.find(['\r', '\n']).unwrap_or(remainder.len()),
+	});
+
+	lexer.bump(length);
+}
+
+pub fn is_closed(text: &str) -> bool {
+	let kind = StringKind::of(text);
+	let body = &text[kind.opening()..];
+
+	body_length(body, kind) == Option::Some(body.len())
+}
+
+// Value of the literal. `offset` is the start of the literal in the source
+pub fn decode_string(text: &str, offset: usize) -> Result<String, LexError> {
+	let kind = StringKind::of(text);
+
+	if !is_closed(text) {
+		return Err(LexError::new(
+			"unterminated string",
+			"missing closing quotes",
+			offset..offset + text.len(),
+		));
+	};
+
+	let start = kind.opening();
+	let body = &text[start..text.len() - kind.closing()];
+
+	match kind {
+		StringKind::Raw => Ok(body.to_string()),
+		StringKind::Regular => {
+			let mut value = String::new();
+			unescape(body, offset + start, &mut value)?;
+
+			Ok(value)
+		}
+		StringKind::Block => {
+			let mut value = String::new();
+
+			for (index, (line_offset, line)) in block_lines(body).into_iter().enumerate() {
+				if index > 0 {
+					value.push('\n');
+				};
+
+				unescape(line, offset + start + line_offset, &mut value)?;
+			}
+
+			Ok(value)
+		}
+	}
+}
+
+// Lines of a triple-quoted string without the common indentation,
+// with their offsets in the body
+fn block_lines(body: &str) -> Vec<(usize, &str)> {
+	let mut lines = Vec::new();
+	let mut line_offset = 0;
+
+	for line in body.split('\n') {
+		lines.push((line_offset, line.strip_suffix('\r').unwrap_or(line)));
+		line_offset += line.len() + 1;
+	}
+
+	let is_blank = |line: &str| line.trim().is_empty();
+
+	if lines.len() > 1 && is_blank(lines[0].1) {
+		lines.remove(0);
+	};
+
+	if lines.len() > 1 && is_blank(lines[lines.len() - 1].1) {
+		lines.pop();
+	};
+
+	let indentation = lines
+		.iter()
+		.filter(|(_, line)| !is_blank(line))
+		.map(|(_, line)| line.len() - line.trim_start_matches([' ', '\t']).len())
+		.min()
+		.unwrap_or(0);
+
+	lines
+		.into_iter()
+		.map(|(line_offset, line)| match is_blank(line) {
+			true => (line_offset + line.len(), ""),
+			false => (line_offset + indentation, &line[indentation..]),
+		})
+		.collect()
+}
+
+fn unescape(text: &str, offset: usize, value: &mut String) -> Result<(), LexError> {
+	let mut chars = text.char_indices().peekable();
+
+	while let Some((index, char)) = chars.next() {
+		if char != '\\' {
+			value.push(char);
+			continue;
+		};
+
+		let start = offset + index;
+		let (escape_index, escape) = match chars.next() {
+			Some(escape) => escape,
+			None => {
+				return Err(LexError::new(
+					"unterminated escape sequence",
+					"expected a character after `\\`",
+					start..start + 1,
+				));
+			}
+		};
+		let end = offset + escape_index + escape.len_utf8();
+
+		match escape {
+			'n' => value.push('\n'),
+			'r' => value.push('\r'),
+			't' => value.push('\t'),
+			'0' => value.push('\0'),
+			'\\' | '"' | '\'' => value.push(escape),
+			'u' => {
+				let mut digits = String::new();
+				let mut end = end;
+				let mut is_closed = false;
+
+				if chars.peek().map(|(_, char)| *char) == Option::Some('{') {
+					chars.next();
+					end += 1;
+
+					while let Some((_, char)) = chars.peek().copied() {
+						if char == '}' {
+							chars.next();
+							end += 1;
+							is_closed = true;
+							break;
+						};
+
+						if !char.is_ascii_hexdigit() {
+							break;
+						};
+
+						chars.next();
+						end += 1;
+						digits.push(char);
+					}
+				};
+
+				if !is_closed || digits.is_empty() || digits.len() > 6 {
+					return Err(LexError::new(
+						"invalid unicode escape",
+						"expected 1 to 6 hex digits in braces (`\\u{1F600}`)",
+						start..end,
+					));
+				};
+
+				match u32::from_str_radix(&digits, 16)
+					.ok()
+					.and_then(char::from_u32)
+				{
+					Some(char) => value.push(char),
+					None => {
+						return Err(LexError::new(
+							format!("`\\u{{{}}}` is not a valid unicode character", digits),
+							"invalid character",
+							start..end,
+						));
+					}
+				};
+			}
+			_ => {
+				return Err(LexError::new(
+					format!("unknown escape sequence `\\{}`", escape),
+					"unknown escape",
+					start..end,
+				));
+			}
+		};
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{decode_string, is_closed};
+
+	#[test]
+	fn decodes_escapes() {
+		assert_eq!(
+			decode_string(r#""a \"b\"\n\t\\ \u{1F600}""#, 0).unwrap(),
+			"a \"b\"\n\t\\ \u{1F600}"
+		);
+		assert_eq!(decode_string(r#"r"^\d+$""#, 0).unwrap(), r"^\d+$");
+		assert_eq!(decode_string(r#""""#, 0).unwrap(), "");
+	}
+
+	#[test]
+	fn strips_block_indentation() {
+		let text = "\"\"\"\n        First line\n          indented \\\"\"\"\n\n        last\n        \"\"\"";
+
+		assert!(is_closed(text));
+		assert_eq!(
+			decode_string(text, 0).unwrap(),
+			"First line\n  indented \"\"\"\n\nlast"
+		);
+		assert_eq!(decode_string("\"\"\"inline\"\"\"", 0).unwrap(), "inline");
+	}
+
+	#[test]
+	fn reports_errors_with_spans() {
+		// `#pattern = "\d"`
+		let error = decode_string(r#""\d""#, 11).unwrap_err();
+		assert_eq!(error.message, "unknown escape sequence `\\d`");
+		assert_eq!(error.span, 12..14);
+
+		let error = decode_string(r#""ok \u{110000}""#, 0).unwrap_err();
+		assert_eq!(error.span, 4..14);
+
+		let error = decode_string(r#""\u{zz}""#, 0).unwrap_err();
+		assert_eq!(error.message, "invalid unicode escape");
+		assert_eq!(error.span, 1..4);
+
+		let error = decode_string("\"\"\"\n    a\n    \\q\n\"\"\"", 100).unwrap_err();
+		assert_eq!(error.span, 114..116);
+
+		assert!(!is_closed(r#""unclosed \""#));
+		assert_eq!(decode_string(r#""abc"#, 5).unwrap_err().span, 5..9);
+	}
+}
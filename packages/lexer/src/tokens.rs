@@ -1,6 +1,8 @@
 use core::ops::Range;
 use logos::Logos;
 
+use crate::strings::{lex_string, StringKind};
+
 #[derive(Logos, Debug, PartialEq, Clone)]
 pub enum TokenType {
 	//
@@ -9,6 +11,9 @@ pub enum TokenType {
 	#[token("interface")]
 	InterfaceDeclaration,
 
+	#[token("extends")]
+	InterfaceExtension,
+
 	#[token("{")]
 	RightCurlyBraces,
 
@@ -39,35 +44,148 @@ pub enum TokenType {
 	#[token("Boolean")]
 	BooleanType,
 
+	#[token("List")]
+	ListType,
+
+	#[token("Map")]
+	MapType,
+
+	// Generic type arguments (`List<String>`, `Map<String, Int>`)
+	#[token("<")]
+	GenericStart,
+
+	#[token(">")]
+	GenericEnd,
+
+	#[token(",")]
+	GenericSeparator,
+
 	#[token(";")]
 	Semicolon,
 
+	// Separates segments of a type path (`User.Status`)
+	#[token(".")]
+	PathSeparator,
+
 	//
 	// Enumerates
 	//
 	#[token("enum")]
 	EnumerateDeclaration,
 
-	// Text
-	#[regex("[a-zA-Z_0-9]+")]
+	//
+	// Aliases
+	//
+	#[token("alias")]
+	AliasDeclaration,
+
+	//
+	// Unions
+	//
+	#[token("union")]
+	UnionDeclaration,
+
+	// Separates union members (`union Result = User | Post;`)
+	#[token("|")]
+	UnionSeparator,
+
+	//
+	// Metadata
+	//
+	#[token("#")]
+	AttributeDeclaration,
+
+	// Also separates alias name from it's type (`alias UserId = String;`)
+	#[token("=")]
+	AttributeAssignment,
+
+	#[regex("///[^\r\n]*")]
+	DocComment,
+
+	// Identifiers and numbers. Identifiers start with a letter (Unicode
+	// XID_Start) or `_` and continue with XID_Continue characters (letters,
+	// digits, `_` and combining marks). Text starting with a digit has to be
+	// a number, anything else (`12px`) is reported by the lexer.
+	//
+	// Raw identifiers (`r#enum`) are names which can't be confused with a
+	// keyword, see `identifier_name`
+	#[regex(r"[\p{XID_Start}_0-9]\p{XID_Continue}*")]
+	#[regex(r"r#[\p{XID_Start}_]\p{XID_Continue}*")]
 	Text,
 
-	// Multi-line text helpers
-	#[token("\"")]
-	Quotes,
+	// `"text"`, `r"raw text"` or `"""multi-line text"""`, see strings.rs
+	#[token("\"", |lexer| lex_string(lexer, StringKind::Regular))]
+	#[token("r\"", |lexer| lex_string(lexer, StringKind::Raw))]
+	#[token("\"\"\"", |lexer| lex_string(lexer, StringKind::Block))]
+	StringLiteral,
 
-	// Whitespace
-	#[regex(r"[ \t\n\f]+")]
+	// Whitespace, including `\r` of CRLF line endings and a byte order mark
+	#[regex(r"[ \t\r\n\f\u{FEFF}]+")]
 	Whitespace,
 
+	// Regular comments (doc comments are matched above)
+	#[regex("//([^/\r\n][^\r\n]*)?")]
+	Comment,
+
 	// Enything else
 	#[error]
 	Error,
 }
 
+impl TokenType {
+	// Tokens which carry no meaning for the parser
+	pub fn is_trivia(&self) -> bool {
+		matches!(self, TokenType::Whitespace | TokenType::Comment)
+	}
+
+	// Keywords are contextual: where a name is expected after a keyword or
+	// a `.` (`required type: String;`, `User.enum`) they're parsed as names
+	pub fn is_keyword(&self) -> bool {
+		matches!(
+			self,
+			TokenType::InterfaceDeclaration
+				| TokenType::InterfaceExtension
+				| TokenType::RequiredModifier
+				| TokenType::OptionalModifier
+				| TokenType::StringType
+				| TokenType::IntegerType
+				| TokenType::BooleanType
+				| TokenType::ListType
+				| TokenType::MapType
+				| TokenType::EnumerateDeclaration
+				| TokenType::AliasDeclaration
+				| TokenType::UnionDeclaration
+		)
+	}
+}
+
+// Name written by an identifier: `r#enum` names `enum`
+pub fn identifier_name(text: &str) -> &str {
+	text.strip_prefix("r#").unwrap_or(text)
+}
+
+// Token with it's own copy of the text
 #[derive(Debug, Clone)]
 pub struct TokenDeclaration {
 	pub token_type: TokenType,
 	pub value: Option<String>,
 	pub span: Range<usize>,
 }
+
+// Token borrowing it's text from the source, nothing is allocated for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token<'src> {
+	pub token_type: TokenType,
+	pub value: &'src str,
+	pub span: Range<usize>,
+}
+
+impl Token<'_> {
+	pub fn to_declaration(&self) -> TokenDeclaration {
+		TokenDeclaration {
+			token_type: self.token_type.clone(),
+			value: Option::Some(self.value.to_string()),
+			span: self.span.clone(),
+		}
+	}
+}
@@ -0,0 +1,452 @@
+use lexer::{strings::is_closed, tokens::TokenType};
+use lsp_types::{CompletionItem, CompletionItemKind};
+use parser::syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+//
+// Completion
+//
+// Works on the syntax tree only, so it keeps working while the file is
+// incomplete: the context is taken from tokens before the cursor and the
+// enclosing interfaces and enums, names come from declarations in the tree.
+//
+// Contexts:
+// [member start]            | keywords (`required`, `interface`...)
+// `: `, `<`, `,`, `alias X =` | built-in types and visible declarations
+// `extends`, `union X =`, `|` | visible interfaces
+// `Parent.`                 | declarations nested in `Parent`
+// `#`                       | attribute names
+// `#name = `                | allowed attribute values
+//
+
+const TOP_LEVEL_KEYWORDS: &[&str] = &["interface", "enum", "alias", "union"];
+const MEMBER_KEYWORDS: &[&str] = &[
+	"required",
+	"optional",
+	"interface",
+	"enum",
+	"alias",
+	"union",
+];
+const BUILT_IN_TYPES: &[&str] = &["String", "Int", "Boolean", "List", "Map"];
+
+// Attributes understood by the analyzer and generators, with allowed values
+const ATTRIBUTES: &[(&str, &str, &[&str])] = &[
+	("min_length", "Minimal length of a String", &[]),
+	("max_length", "Maximal length of a String", &[]),
+	("pattern", "Regular expression a String has to match", &[]),
+	("format", "Format of a String", &["email", "url", "uuid"]),
+	("min", "Minimal value of an Int", &[]),
+	("max", "Maximal value of an Int", &[]),
+	("min_items", "Minimal length of a List", &[]),
+	("max_items", "Maximal length of a List", &[]),
+	("proto_number", "Protobuf field or enum value number", &[]),
+	(
+		"graphql_input",
+		"Generate GraphQL input type for the interface",
+		&[],
+	),
+];
+
+// Significant token with the kind of the node it belongs to
+type Token<'a> = (&'a SyntaxToken, SyntaxKind);
+
+pub fn completions(syntax: &SyntaxNode, offset: usize) -> Vec<CompletionItem> {
+	let mut tokens = Vec::new();
+	collect_tokens(syntax, &mut tokens);
+
+	// Nothing to suggest inside comments and strings
+	if tokens.iter().any(|(token, _)| {
+		let is_inside = token.span.start < offset && offset < token.span.end;
+		let is_at_end = token.span.start < offset && offset == token.span.end;
+
+		match token.kind {
+			TokenType::Comment | TokenType::DocComment => is_inside || is_at_end,
+			// Unclosed string goes until the end of the line
+			TokenType::StringLiteral => is_inside || (is_at_end && !is_closed(&token.text)),
+			_ => false,
+		}
+	}) {
+		return Vec::new();
+	};
+
+	let mut before: Vec<Token> = tokens
+		.into_iter()
+		.filter(|(token, _)| token.span.end <= offset && !token.kind.is_trivia())
+		.collect();
+
+	// Word under the cursor is being typed, suggestions replace it
+	if let Some((token, _)) = before.last() {
+		if token.span.end == offset && is_word(token) {
+			before.pop();
+		};
+	};
+
+	let blocks = enclosing_blocks(syntax, offset);
+	let kinds: Vec<TokenType> = before.iter().map(|(token, _)| token.kind.clone()).collect();
+
+	let (last, parent) = match before.last() {
+		Some((token, parent)) => (Option::Some(token.kind.clone()), Option::Some(*parent)),
+		None => (Option::None, Option::None),
+	};
+	let nth_last = |n: usize| kinds.len().checked_sub(n + 1).map(|index| &kinds[index]);
+
+	match last {
+		Some(TokenType::AttributeDeclaration) => attribute_names(),
+		Some(TokenType::AttributeAssignment) if parent == Some(SyntaxKind::Attribute) => {
+			let name = &before[before.len() - 2].0.text;
+			attribute_values(name)
+		}
+		Some(TokenType::AttributeAssignment) => match nth_last(2) {
+			Some(TokenType::AliasDeclaration) => types(syntax, &blocks),
+			Some(TokenType::UnionDeclaration) => interfaces(syntax, &blocks),
+			_ => Vec::new(),
+		},
+		Some(TokenType::VariableConnection) => match blocks.last() {
+			Some(block) if block.kind == SyntaxKind::Interface => types(syntax, &blocks),
+			_ => Vec::new(),
+		},
+		Some(TokenType::GenericStart | TokenType::GenericSeparator) => types(syntax, &blocks),
+		Some(TokenType::InterfaceExtension | TokenType::UnionSeparator) => {
+			interfaces(syntax, &blocks)
+		}
+		Some(TokenType::PathSeparator) => {
+			// Segments before the dot: `User.Status.` => [User, Status]
+			let mut path = Vec::new();
+			let mut index = before.len() - 1;
+
+			while index > 0 && before[index].0.kind == TokenType::PathSeparator {
+				let segment = before[index - 1].0;
+				if segment.kind != TokenType::Text && !segment.kind.is_keyword() {
+					break;
+				};
+
+				path.insert(0, segment.name().to_string());
+				index = index.saturating_sub(2);
+			}
+
+			let only_interfaces = matches!(
+				kinds.get(index),
+				Some(TokenType::InterfaceExtension | TokenType::UnionSeparator)
+			) || (kinds.get(index) == Some(&TokenType::AttributeAssignment)
+				&& index > 1 && kinds.get(index - 2)
+				== Some(&TokenType::UnionDeclaration));
+
+			nested(syntax, &blocks, &path, only_interfaces)
+		}
+		// Start of a member or a declaration
+		None
+		| Some(TokenType::Semicolon | TokenType::RightCurlyBraces | TokenType::DocComment)
+		| Some(TokenType::Text | TokenType::StringLiteral)
+			if is_member_start(last.as_ref(), parent) =>
+		{
+			match blocks.last() {
+				Some(block) if block.kind == SyntaxKind::Enum => Vec::new(),
+				Some(_) => keywords(MEMBER_KEYWORDS),
+				None => keywords(TOP_LEVEL_KEYWORDS),
+			}
+		}
+		_ => Vec::new(),
+	}
+}
+
+fn collect_tokens<'a>(node: &'a SyntaxNode, tokens: &mut Vec<Token<'a>>) {
+	for child in node.children.iter() {
+		match child {
+			SyntaxElement::Node(child) => collect_tokens(child, tokens),
+			SyntaxElement::Token(token) => tokens.push((token, node.kind)),
+		};
+	}
+}
+
+fn is_word(token: &SyntaxToken) -> bool {
+	token
+		.text
+		.chars()
+		.all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
+
+fn is_member_start(last: Option<&TokenType>, parent: Option<SyntaxKind>) -> bool {
+	match (last, parent) {
+		(None, _) => true,
+		// `{` of a String constant
+		(Some(TokenType::RightCurlyBraces), Some(SyntaxKind::Constant)) => false,
+		(Some(TokenType::RightCurlyBraces | TokenType::Semicolon | TokenType::DocComment), _) => {
+			true
+		}
+		// End of an attribute
+		(Some(TokenType::Text), Some(SyntaxKind::Attribute)) => true,
+		(Some(TokenType::StringLiteral), Some(SyntaxKind::Attribute)) => true,
+		_ => false,
+	}
+}
+
+//
+// Interfaces and enums with the cursor inside of their braces, outermost first
+//
+fn enclosing_blocks(node: &SyntaxNode, offset: usize) -> Vec<&SyntaxNode> {
+	let mut blocks = Vec::new();
+	let mut current = node;
+
+	while let Some(block) = current.nodes().find(|child| {
+		if !matches!(child.kind, SyntaxKind::Interface | SyntaxKind::Enum) {
+			return false;
+		};
+
+		let open = child.first_token(TokenType::RightCurlyBraces);
+		let close = child.first_token(TokenType::LeftCurlyBraces);
+
+		match (open, close) {
+			(Some(open), Some(close)) => open.span.end <= offset && offset <= close.span.start,
+			(Some(open), None) => open.span.end <= offset,
+			_ => false,
+		}
+	}) {
+		blocks.push(block);
+		current = block;
+	}
+
+	blocks
+}
+
+//
+// Names
+//
+
+// Declarations directly inside the node
+fn declarations(node: &SyntaxNode) -> impl Iterator<Item = (&SyntaxNode, &SyntaxToken)> {
+	node.nodes().filter_map(|child| match child.kind {
+		SyntaxKind::Interface | SyntaxKind::Enum | SyntaxKind::Alias | SyntaxKind::Union => {
+			Option::Some((child, child.first_token(TokenType::Text)?))
+		}
+		_ => Option::None,
+	})
+}
+
+// Declarations of the enclosing interfaces and top-level ones, innermost first
+fn visible<'a>(
+	syntax: &'a SyntaxNode,
+	blocks: &[&'a SyntaxNode],
+) -> Vec<(&'a SyntaxNode, &'a SyntaxToken)> {
+	let mut names: Vec<(&SyntaxNode, &SyntaxToken)> = Vec::new();
+
+	for scope in blocks.iter().rev().copied().chain([syntax]) {
+		for (node, name) in declarations(scope) {
+			// Inner declarations shadow outer ones
+			if !names
+				.iter()
+				.any(|(_, visible)| visible.name() == name.name())
+			{
+				names.push((node, name));
+			};
+		}
+	}
+
+	names
+}
+
+fn types(syntax: &SyntaxNode, blocks: &[&SyntaxNode]) -> Vec<CompletionItem> {
+	let mut items: Vec<CompletionItem> = BUILT_IN_TYPES
+		.iter()
+		.map(|name| item(name, CompletionItemKind::KEYWORD, "built-in type"))
+		.collect();
+
+	items.extend(
+		visible(syntax, blocks)
+			.into_iter()
+			.map(|(node, name)| declaration_item(node, name)),
+	);
+
+	items
+}
+
+fn interfaces(syntax: &SyntaxNode, blocks: &[&SyntaxNode]) -> Vec<CompletionItem> {
+	visible(syntax, blocks)
+		.into_iter()
+		.filter(|(node, _)| node.kind == SyntaxKind::Interface)
+		.map(|(node, name)| declaration_item(node, name))
+		.collect()
+}
+
+// Declarations nested in the one the path leads to
+fn nested(
+	syntax: &SyntaxNode,
+	blocks: &[&SyntaxNode],
+	path: &[String],
+	only_interfaces: bool,
+) -> Vec<CompletionItem> {
+	let Some((first, rest)) = path.split_first() else {
+		return Vec::new();
+	};
+
+	let Some(mut target) = visible(syntax, blocks)
+		.into_iter()
+		.find(|(_, name)| name.name() == first)
+		.map(|(node, _)| node)
+	else {
+		return Vec::new();
+	};
+
+	for segment in rest {
+		match declarations(target).find(|(_, name)| name.name() == segment) {
+			Some((node, _)) => target = node,
+			None => return Vec::new(),
+		};
+	}
+
+	declarations(target)
+		.filter(|(node, _)| !only_interfaces || node.kind == SyntaxKind::Interface)
+		.map(|(node, name)| declaration_item(node, name))
+		.collect()
+}
+
+fn declaration_item(node: &SyntaxNode, name: &SyntaxToken) -> CompletionItem {
+	let (kind, detail) = match node.kind {
+		SyntaxKind::Interface => (CompletionItemKind::INTERFACE, "interface"),
+		SyntaxKind::Enum => (CompletionItemKind::ENUM, "enum"),
+		SyntaxKind::Alias => (CompletionItemKind::TYPE_PARAMETER, "alias"),
+		_ => (CompletionItemKind::STRUCT, "union"),
+	};
+
+	item(&name.text, kind, detail)
+}
+
+fn keywords(keywords: &[&str]) -> Vec<CompletionItem> {
+	keywords
+		.iter()
+		.map(|keyword| item(keyword, CompletionItemKind::KEYWORD, "keyword"))
+		.collect()
+}
+
+fn attribute_names() -> Vec<CompletionItem> {
+	ATTRIBUTES
+		.iter()
+		.map(|(name, detail, _)| item(name, CompletionItemKind::PROPERTY, detail))
+		.collect()
+}
+
+fn attribute_values(name: &str) -> Vec<CompletionItem> {
+	ATTRIBUTES
+		.iter()
+		.filter(|(attribute, _, _)| *attribute == name)
+		.flat_map(|(_, _, values)| values.iter())
+		.map(|value| item(value, CompletionItemKind::VALUE, name))
+		.collect()
+}
+
+fn item(label: &str, kind: CompletionItemKind, detail: &str) -> CompletionItem {
+	CompletionItem {
+		label: label.to_string(),
+		kind: Option::Some(kind),
+		detail: Option::Some(detail.to_string()),
+		..CompletionItem::default()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use parser::syntax::parse;
+
+	use super::*;
+
+	// Labels of completions at `$0`
+	fn labels(source: &str) -> Vec<String> {
+		let offset = source.find("$0").unwrap();
+		let source = source.replace("$0", "");
+
+		completions(&parse(&source).root, offset)
+			.into_iter()
+			.map(|item| item.label)
+			.collect()
+	}
+
+	#[test]
+	fn suggests_keywords() {
+		assert_eq!(labels("$0"), vec!["interface", "enum", "alias", "union"]);
+		assert_eq!(
+			labels("alias A = String;\nint$0"),
+			vec!["interface", "enum", "alias", "union"]
+		);
+		assert_eq!(
+			labels("interface User {\n\t/// Docs\n\t$0\n"),
+			vec![
+				"required",
+				"optional",
+				"interface",
+				"enum",
+				"alias",
+				"union"
+			]
+		);
+		assert_eq!(
+			labels("interface User {\n\t#graphql_input\n\treq$0\n};"),
+			labels("interface A {$0")
+		);
+		assert!(labels("enum Status {\n\t$0\n};").is_empty());
+		assert!(labels("interface User {\n\trequired $0").is_empty());
+		assert!(labels("// comment $0").is_empty());
+	}
+
+	#[test]
+	fn suggests_types() {
+		let source = "
+			interface User {
+				required status: St$0
+
+				enum Status {
+					ACTIVE: active;
+				};
+			};
+
+			interface Post {};
+			alias Id = String;
+		";
+
+		assert_eq!(
+			labels(source),
+			vec!["String", "Int", "Boolean", "List", "Map", "Status", "User", "Post", "Id"]
+		);
+		assert_eq!(labels("interface A { required a: List<$0").len(), 6);
+		assert_eq!(labels("interface A {};\nunion U = A | $0"), vec!["A"]);
+		assert_eq!(
+			labels("interface A {};\ninterface B extends $0 {};"),
+			vec!["A", "B"]
+		);
+		assert!(labels("enum A { B: $0 };").is_empty());
+		assert!(labels("interface A { required a: String { \"$0\" }; };").is_empty());
+		assert!(labels("#pattern = r\"^[a-z]$0\ninterface A {};").is_empty());
+	}
+
+	#[test]
+	fn suggests_nested_paths() {
+		let source = "
+			interface User {
+				enum Status { A: a; };
+				interface Profile {
+					alias Url = String;
+				};
+			};
+
+			alias A = User.$0
+		";
+
+		assert_eq!(labels(source), vec!["Status", "Profile"]);
+		assert_eq!(
+			labels(&source.replace("User.$0", "User.Profile.$0")),
+			vec!["Url"]
+		);
+		assert_eq!(
+			labels(&source.replace("alias A = User.$0", "union U = User.$0")),
+			vec!["Profile"]
+		);
+	}
+
+	#[test]
+	fn suggests_attributes() {
+		assert_eq!(labels("interface A {\n\t#$0").len(), ATTRIBUTES.len());
+		assert_eq!(
+			labels("interface A {\n\t#format = $0"),
+			vec!["email", "url", "uuid"]
+		);
+		assert!(labels("interface A {\n\t#min = $0").is_empty());
+	}
+}
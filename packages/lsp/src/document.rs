@@ -0,0 +1,27 @@
+use core::ops::Range;
+
+use lsp_types::Position;
+use semantic::workspace::WorkspaceFile;
+
+use crate::line_index::LineIndex;
+
+//
+// Open document
+//
+// Analysis of the file is kept in the workspace, document adds conversion
+// between LSP positions and byte offsets.
+#[derive(Clone, Copy)]
+pub struct Document<'a> {
+	pub file: &'a WorkspaceFile,
+	pub lines: &'a LineIndex,
+}
+
+impl Document<'_> {
+	pub fn offset(&self, position: Position) -> usize {
+		self.lines.offset(&self.file.source, position)
+	}
+
+	pub fn range(&self, span: &Range<usize>) -> lsp_types::Range {
+		self.lines.range(&self.file.source, span)
+	}
+}
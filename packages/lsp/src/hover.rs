@@ -0,0 +1,165 @@
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind};
+use semantic::{
+	index::Symbol,
+	ir::{Declaration, DeclarationKind, Field, Schema, Type},
+};
+
+use crate::document::Document;
+
+//
+// Hover
+//
+// Shows a declaration, a variable or an enum variant as it was resolved:
+// references are fully-qualified, aliases are followed and interfaces
+// list inherited variables as well.
+pub fn hover(document: Document, offset: usize) -> Option<Hover> {
+	let schema = document.file.schema.as_ref()?;
+	let occurrence = document.file.index.as_ref()?.at(offset)?;
+
+	let (code, docs, notes) = match occurrence.symbol.clone() {
+		Symbol::Declaration(id) => {
+			let declaration = schema.get(id);
+			(
+				declaration_code(schema, declaration),
+				declaration.docs.clone(),
+				Vec::new(),
+			)
+		}
+		Symbol::Field(id, name) => {
+			let field = field(schema.get(id), &name)?;
+			let mut notes = Vec::new();
+
+			let resolved = schema.resolve_type(&field.field_type);
+			if resolved != &field.field_type {
+				notes.push(format!("Resolved type: `{}`", type_name(schema, resolved)));
+			};
+
+			(field_code(schema, field), field.docs.clone(), notes)
+		}
+		Symbol::Variant(id, name) => {
+			let declaration = schema.get(id);
+			let DeclarationKind::Enum(enumerate) = &declaration.kind else {
+				return Option::None;
+			};
+			let variant = enumerate
+				.variants
+				.iter()
+				.find(|variant| variant.name == name)?;
+
+			let code = format!(
+				"{}.{}: {}",
+				declaration.qualified_name(),
+				variant.name,
+				variant.value.clone().unwrap_or_default()
+			);
+			(code, variant.docs.clone(), Vec::new())
+		}
+	};
+
+	let mut sections = vec![format!("```typm\n{}\n```", code)];
+	sections.extend(notes);
+	sections.extend(docs);
+
+	Option::Some(Hover {
+		contents: HoverContents::Markup(MarkupContent {
+			kind: MarkupKind::Markdown,
+			value: sections.join("\n\n"),
+		}),
+		range: Option::Some(document.range(&occurrence.span)),
+	})
+}
+
+fn field<'a>(declaration: &'a Declaration, name: &str) -> Option<&'a Field> {
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			interface.own_fields().find(|field| field.name == name)
+		}
+		_ => Option::None,
+	}
+}
+
+fn field_code(schema: &Schema, field: &Field) -> String {
+	format!(
+		"{} {}: {}",
+		if field.is_required {
+			"required"
+		} else {
+			"optional"
+		},
+		field.name,
+		type_name(schema, &field.field_type)
+	)
+}
+
+fn declaration_code(schema: &Schema, declaration: &Declaration) -> String {
+	let name = declaration.qualified_name();
+
+	match &declaration.kind {
+		DeclarationKind::Interface(interface) => {
+			let mut lines = vec![match interface.extends {
+				Some(parent) => format!(
+					"interface {} extends {} {{",
+					name,
+					schema.get(parent).qualified_name()
+				),
+				None => format!("interface {} {{", name),
+			}];
+
+			for field in interface.fields.iter() {
+				let mut line = format!("    {};", field_code(schema, field));
+
+				if let Some(parent) = field.inherited_from {
+					line.push_str(&format!(" // from {}", schema.get(parent).qualified_name()));
+				};
+
+				lines.push(line);
+			}
+
+			lines.push("}".to_string());
+			lines.join("\n")
+		}
+		DeclarationKind::Enum(enumerate) => {
+			let mut lines = vec![format!("enum {} {{", name)];
+
+			for variant in enumerate.variants.iter() {
+				lines.push(format!(
+					"    {}: {};",
+					variant.name,
+					variant.value.clone().unwrap_or_default()
+				));
+			}
+
+			lines.push("}".to_string());
+			lines.join("\n")
+		}
+		DeclarationKind::Alias(alias) => {
+			format!("alias {} = {}", name, type_name(schema, &alias.target))
+		}
+		DeclarationKind::Union(union) => {
+			let members: Vec<String> = union
+				.members
+				.iter()
+				.map(|member| schema.get(*member).qualified_name())
+				.collect();
+
+			format!("union {} = {}", name, members.join(" | "))
+		}
+	}
+}
+
+// Type in .typm syntax, references are fully-qualified
+pub fn type_name(schema: &Schema, field_type: &Type) -> String {
+	match field_type {
+		Type::String(Some(value)) => format!("String {{ \"{}\" }}", value),
+		Type::String(None) => "String".to_string(),
+		Type::Boolean => "Boolean".to_string(),
+		Type::Integer => "Int".to_string(),
+		Type::List(item_type) => format!("List<{}>", type_name(schema, item_type)),
+		Type::Map(key_type, value_type) => format!(
+			"Map<{}, {}>",
+			type_name(schema, key_type),
+			type_name(schema, value_type)
+		),
+		Type::Reference(id) => schema.get(*id).qualified_name(),
+	}
+}
@@ -0,0 +1,734 @@
+use std::{collections::HashMap, error::Error};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response, ResponseError};
+use lsp_types::{
+	notification::{
+		DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+		PublishDiagnostics,
+	},
+	request::{
+		Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, References, Rename,
+		Request as _,
+	},
+	CompletionOptions, CompletionParams, CompletionResponse, DiagnosticRelatedInformation,
+	DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+	DidOpenTextDocumentParams, DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams,
+	GotoDefinitionResponse, HoverParams, HoverProviderCapability, Location, OneOf,
+	PublishDiagnosticsParams, ReferenceParams, RenameParams, ServerCapabilities,
+	TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit,
+};
+use parser::errors::Severity;
+use semantic::workspace::Workspace;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use self::{
+	completion::completions, document::Document, hover::hover, line_index::LineIndex,
+	symbols::document_symbols,
+};
+
+pub mod completion;
+pub mod document;
+pub mod hover;
+pub mod line_index;
+pub mod symbols;
+
+pub type ServerError = Box<dyn Error + Sync + Send>;
+
+//
+// Language server
+//
+// Speaks LSP over the given connection until the client asks to shut down.
+// Documents are synced in full and analyzed on every change. Open documents
+// form a workspace: references and renames go through all of them.
+pub fn run(connection: &Connection) -> Result<(), ServerError> {
+	let capabilities = serde_json::to_value(capabilities())?;
+	connection.initialize(capabilities)?;
+
+	let mut server = Server {
+		connection,
+		workspace: Workspace::default(),
+		lines: HashMap::new(),
+	};
+
+	for message in &connection.receiver {
+		match message {
+			Message::Request(request) => {
+				if connection.handle_shutdown(&request)? {
+					return Ok(());
+				};
+
+				server.handle_request(request)?;
+			}
+			Message::Notification(notification) => server.handle_notification(notification)?,
+			Message::Response(_) => {}
+		};
+	}
+
+	Ok(())
+}
+
+pub fn capabilities() -> ServerCapabilities {
+	ServerCapabilities {
+		text_document_sync: Option::Some(TextDocumentSyncCapability::Kind(
+			TextDocumentSyncKind::FULL,
+		)),
+		hover_provider: Option::Some(HoverProviderCapability::Simple(true)),
+		document_symbol_provider: Option::Some(OneOf::Left(true)),
+		definition_provider: Option::Some(OneOf::Left(true)),
+		references_provider: Option::Some(OneOf::Left(true)),
+		rename_provider: Option::Some(OneOf::Left(true)),
+		completion_provider: Option::Some(CompletionOptions {
+			trigger_characters: Option::Some(
+				[".", ":", "#", "<"]
+					.iter()
+					.map(|character| character.to_string())
+					.collect(),
+			),
+			..CompletionOptions::default()
+		}),
+		..ServerCapabilities::default()
+	}
+}
+
+struct Server<'a> {
+	connection: &'a Connection,
+	// Keyed by document URI
+	workspace: Workspace,
+	lines: HashMap<Url, LineIndex>,
+}
+
+impl Server<'_> {
+	fn document(&self, uri: &Url) -> Option<Document<'_>> {
+		Option::Some(Document {
+			file: self.workspace.get(uri.as_str())?,
+			lines: self.lines.get(uri)?,
+		})
+	}
+
+	fn handle_request(&mut self, request: Request) -> Result<(), ServerError> {
+		let id = request.id.clone();
+
+		let response = match self.request_result(request) {
+			Ok(result) => Response::new_ok(id, result),
+			Err(error) => Response {
+				id,
+				result: Option::None,
+				error: Option::Some(ResponseError {
+					code: error.code as i32,
+					message: error.message,
+					data: Option::None,
+				}),
+			},
+		};
+
+		self.respond(response)
+	}
+
+	fn request_result(&mut self, request: Request) -> Result<Value, RequestError> {
+		let result = match request.method.as_str() {
+			HoverRequest::METHOD => {
+				let params: HoverParams = params(request.params)?;
+				let position = params.text_document_position_params;
+
+				let result = self
+					.document(&position.text_document.uri)
+					.and_then(|document| hover(document, document.offset(position.position)));
+
+				serde_json::to_value(result)?
+			}
+			DocumentSymbolRequest::METHOD => {
+				let params: DocumentSymbolParams = params(request.params)?;
+
+				let result = self
+					.document(&params.text_document.uri)
+					.map(|document| DocumentSymbolResponse::Nested(document_symbols(document)));
+
+				serde_json::to_value(result)?
+			}
+			Completion::METHOD => {
+				let params: CompletionParams = params(request.params)?;
+				let position = params.text_document_position;
+
+				let result = self.document(&position.text_document.uri).map(|document| {
+					let offset = document.offset(position.position);
+					CompletionResponse::Array(completions(&document.file.syntax, offset))
+				});
+
+				serde_json::to_value(result)?
+			}
+			GotoDefinition::METHOD => {
+				let params: GotoDefinitionParams = params(request.params)?;
+				let position = params.text_document_position_params;
+				let uri = position.text_document.uri;
+
+				let result = self.document(&uri).and_then(|document| {
+					let index = document.file.index.as_ref()?;
+					let occurrence = index.at(document.offset(position.position))?;
+					let definition = index.definition(&occurrence.symbol)?;
+
+					Option::Some(GotoDefinitionResponse::Scalar(Location::new(
+						uri.clone(),
+						document.range(&definition.span),
+					)))
+				});
+
+				serde_json::to_value(result)?
+			}
+			References::METHOD => {
+				let params: ReferenceParams = params(request.params)?;
+				let position = params.text_document_position;
+				let include_declaration = params.context.include_declaration;
+
+				let result = self
+					.document(&position.text_document.uri)
+					.and_then(|document| {
+						let offset = document.offset(position.position);
+						let (_, name) = document.file.symbol_at(offset)?;
+
+						Option::Some(self.references(&name, include_declaration))
+					});
+
+				serde_json::to_value(result)?
+			}
+			Rename::METHOD => {
+				let params: RenameParams = params(request.params)?;
+				let position = params.text_document_position;
+
+				let name = self
+					.document(&position.text_document.uri)
+					.and_then(|document| {
+						let offset = document.offset(position.position);
+						document.file.symbol_at(offset).map(|(_, name)| name)
+					});
+
+				let Some(name) = name else {
+					return Err(RequestError::new(
+						ErrorCode::RequestFailed,
+						"nothing to rename here",
+					));
+				};
+
+				match self.rename(&name, &params.new_name) {
+					Ok(edit) => serde_json::to_value(edit)?,
+					Err(message) => {
+						return Err(RequestError::new(ErrorCode::RequestFailed, message))
+					}
+				}
+			}
+			method => {
+				return Err(RequestError::new(
+					ErrorCode::MethodNotFound,
+					format!("unsupported request: {}", method),
+				));
+			}
+		};
+
+		Ok(result)
+	}
+
+	fn references(&self, name: &str, include_declaration: bool) -> Vec<Location> {
+		self.workspace
+			.references(name)
+			.into_iter()
+			.filter(|(_, occurrence)| include_declaration || !occurrence.is_definition)
+			.filter_map(|(file, occurrence)| {
+				let uri = Url::parse(file).ok()?;
+				let document = self.document(&uri)?;
+
+				Option::Some(Location::new(uri, document.range(&occurrence.span)))
+			})
+			.collect()
+	}
+
+	fn rename(&self, name: &str, new_name: &str) -> Result<WorkspaceEdit, String> {
+		let mut changes = HashMap::new();
+
+		for (file, edits) in self.workspace.rename(name, new_name)? {
+			let Some(uri) = Url::parse(&file).ok() else {
+				continue;
+			};
+			let Some(document) = self.document(&uri) else {
+				continue;
+			};
+
+			let edits = edits
+				.iter()
+				.map(|edit| TextEdit::new(document.range(&edit.span), edit.text.clone()))
+				.collect();
+
+			changes.insert(uri, edits);
+		}
+
+		Ok(WorkspaceEdit::new(changes))
+	}
+
+	fn handle_notification(&mut self, notification: Notification) -> Result<(), ServerError> {
+		let method = notification.method.as_str();
+
+		match method {
+			DidOpenTextDocument::METHOD => {
+				let Some(params) =
+					notification_params::<DidOpenTextDocumentParams>(method, notification.params)
+				else {
+					return Ok(());
+				};
+				let document = params.text_document;
+
+				self.update(document.uri, document.text, document.version)
+			}
+			DidChangeTextDocument::METHOD => {
+				let Some(params) =
+					notification_params::<DidChangeTextDocumentParams>(method, notification.params)
+				else {
+					return Ok(());
+				};
+
+				// Full sync: the last change contains the whole text
+				match params.content_changes.into_iter().last() {
+					Some(change) => self.update(
+						params.text_document.uri,
+						change.text,
+						params.text_document.version,
+					),
+					None => Ok(()),
+				}
+			}
+			DidCloseTextDocument::METHOD => {
+				let Some(params) =
+					notification_params::<DidCloseTextDocumentParams>(method, notification.params)
+				else {
+					return Ok(());
+				};
+				let uri = params.text_document.uri;
+
+				self.workspace.remove(uri.as_str());
+				self.lines.remove(&uri);
+
+				// Diagnostics of a closed file are cleared
+				self.publish_diagnostics(uri, Vec::new(), Option::None)
+			}
+			_ => Ok(()),
+		}
+	}
+
+	fn update(&mut self, uri: Url, source: String, version: i32) -> Result<(), ServerError> {
+		self.lines.insert(uri.clone(), LineIndex::new(&source));
+		self.workspace.insert(uri.as_str(), source);
+
+		let diagnostics = match self.document(&uri) {
+			Some(document) => diagnostics(&uri, document),
+			None => Vec::new(),
+		};
+
+		self.publish_diagnostics(uri, diagnostics, Option::Some(version))
+	}
+
+	fn publish_diagnostics(
+		&self,
+		uri: Url,
+		diagnostics: Vec<lsp_types::Diagnostic>,
+		version: Option<i32>,
+	) -> Result<(), ServerError> {
+		let params = PublishDiagnosticsParams {
+			uri,
+			diagnostics,
+			version,
+		};
+
+		self.send(Message::Notification(Notification::new(
+			PublishDiagnostics::METHOD.to_string(),
+			params,
+		)))
+	}
+
+	fn respond(&self, response: Response) -> Result<(), ServerError> {
+		self.send(Message::Response(response))
+	}
+
+	fn send(&self, message: Message) -> Result<(), ServerError> {
+		self.connection.sender.send(message)?;
+		Ok(())
+	}
+}
+
+//
+// Malformed messages
+//
+// A bad request is answered with `InvalidParams` and a bad notification is
+// logged, neither of them stops the server.
+struct RequestError {
+	code: ErrorCode,
+	message: String,
+}
+
+impl RequestError {
+	fn new(code: ErrorCode, message: impl Into<String>) -> RequestError {
+		RequestError {
+			code,
+			message: message.into(),
+		}
+	}
+}
+
+// Results which can't be serialized
+impl From<serde_json::Error> for RequestError {
+	fn from(error: serde_json::Error) -> RequestError {
+		RequestError::new(ErrorCode::InternalError, error.to_string())
+	}
+}
+
+fn params<P: DeserializeOwned>(params: Value) -> Result<P, RequestError> {
+	serde_json::from_value(params)
+		.map_err(|error| RequestError::new(ErrorCode::InvalidParams, error.to_string()))
+}
+
+fn notification_params<P: DeserializeOwned>(method: &str, params: Value) -> Option<P> {
+	match serde_json::from_value(params) {
+		Ok(params) => Option::Some(params),
+		Err(error) => {
+			eprintln!("invalid {} params: {}", method, error);
+			Option::None
+		}
+	}
+}
+
+//
+// Diagnostics of a document in LSP format
+//
+// Primary label gives the range, other labels become related information.
+fn diagnostics(uri: &Url, document: Document) -> Vec<lsp_types::Diagnostic> {
+	document
+		.file
+		.diagnostics
+		.iter()
+		.map(|diagnostic| {
+			let span = diagnostic
+				.labels
+				.first()
+				.map(|label| label.span.clone())
+				.unwrap_or(0..0);
+
+			let related: Vec<DiagnosticRelatedInformation> = diagnostic
+				.labels
+				.iter()
+				.skip(1)
+				.map(|label| DiagnosticRelatedInformation {
+					location: Location::new(uri.clone(), document.range(&label.span)),
+					message: label.message.clone(),
+				})
+				.collect();
+
+			lsp_types::Diagnostic {
+				range: document.range(&span),
+				severity: Option::Some(match diagnostic.severity {
+					Severity::Error => DiagnosticSeverity::ERROR,
+					Severity::Warning => DiagnosticSeverity::WARNING,
+				}),
+				source: Option::Some("typm".to_string()),
+				message: diagnostic.message.clone(),
+				related_information: if related.is_empty() {
+					Option::None
+				} else {
+					Option::Some(related)
+				},
+				..lsp_types::Diagnostic::default()
+			}
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::thread;
+
+	use lsp_types::{
+		notification::Exit,
+		request::{Initialize, Shutdown},
+		InitializeParams, PartialResultParams, Position, ReferenceContext, TextDocumentIdentifier,
+		TextDocumentItem, TextDocumentPositionParams, WorkDoneProgressParams,
+	};
+
+	use lsp_server::RequestId;
+	use serde_json::Value;
+
+	use super::*;
+
+	//
+	// Scripted client talking to a server running in another thread
+	//
+	struct Client {
+		connection: Connection,
+		next_id: i32,
+	}
+
+	impl Client {
+		fn start() -> (Client, thread::JoinHandle<()>) {
+			let (server, client) = Connection::memory();
+			let handle = thread::spawn(move || run(&server).unwrap());
+
+			let mut client = Client {
+				connection: client,
+				next_id: 0,
+			};
+
+			client.request(Initialize::METHOD, InitializeParams::default());
+			client.notify("initialized", serde_json::json!({}));
+
+			(client, handle)
+		}
+
+		fn request(&mut self, method: &str, params: impl serde::Serialize) -> Value {
+			self.response(method, params).result.unwrap_or(Value::Null)
+		}
+
+		fn response(&mut self, method: &str, params: impl serde::Serialize) -> Response {
+			self.next_id += 1;
+			let id = RequestId::from(self.next_id);
+
+			self.connection
+				.sender
+				.send(Message::Request(Request::new(
+					id.clone(),
+					method.to_string(),
+					params,
+				)))
+				.unwrap();
+
+			loop {
+				match self.connection.receiver.recv().unwrap() {
+					Message::Response(response) if response.id == id => return response,
+					_ => {}
+				};
+			}
+		}
+
+		fn notify(&self, method: &str, params: impl serde::Serialize) {
+			self.connection
+				.sender
+				.send(Message::Notification(Notification::new(
+					method.to_string(),
+					params,
+				)))
+				.unwrap();
+		}
+
+		fn open(&self, uri: &Url, text: &str) {
+			self.notify(
+				DidOpenTextDocument::METHOD,
+				DidOpenTextDocumentParams {
+					text_document: TextDocumentItem::new(
+						uri.clone(),
+						"typm".to_string(),
+						1,
+						text.to_string(),
+					),
+				},
+			);
+		}
+
+		// Waits for the next published diagnostics
+		fn diagnostics(&self) -> PublishDiagnosticsParams {
+			loop {
+				if let Message::Notification(notification) =
+					self.connection.receiver.recv().unwrap()
+				{
+					if notification.method == PublishDiagnostics::METHOD {
+						return serde_json::from_value(notification.params).unwrap();
+					};
+				};
+			}
+		}
+
+		fn shutdown(mut self, handle: thread::JoinHandle<()>) {
+			self.request(Shutdown::METHOD, ());
+			self.notify(Exit::METHOD, ());
+			handle.join().unwrap();
+		}
+	}
+
+	fn position(uri: &Url, line: u32, character: u32) -> TextDocumentPositionParams {
+		TextDocumentPositionParams::new(
+			TextDocumentIdentifier::new(uri.clone()),
+			Position::new(line, character),
+		)
+	}
+
+	const SOURCE: &str = "interface User {
+	/// Status of the account
+	required status: User.Status;
+	optional id: Id;
+
+	enum Status {
+		ACTIVE: active;
+	};
+};
+
+alias Id = String;
+";
+
+	#[test]
+	fn publishes_diagnostics() {
+		let (client, handle) = Client::start();
+		let uri = Url::parse("file:///schema.typm").unwrap();
+
+		client.open(&uri, SOURCE);
+		assert!(client.diagnostics().diagnostics.is_empty());
+
+		client.notify(
+			DidChangeTextDocument::METHOD,
+			serde_json::json!({
+				"textDocument": { "uri": uri, "version": 2 },
+				"contentChanges": [{ "text": "interface User {\n\trequired id String;\n};\n" }],
+			}),
+		);
+
+		let published = client.diagnostics();
+		assert_eq!(published.version, Option::Some(2));
+		assert_eq!(
+			published.diagnostics[0].message,
+			"expected `:`, found `String`"
+		);
+		assert_eq!(
+			published.diagnostics[0].range,
+			lsp_types::Range::new(Position::new(1, 13), Position::new(1, 19))
+		);
+
+		client.shutdown(handle);
+	}
+
+	#[test]
+	fn survives_malformed_params() {
+		let (mut client, handle) = Client::start();
+		let uri = Url::parse("file:///schema.typm").unwrap();
+
+		let response = client.response(HoverRequest::METHOD, serde_json::json!({ "bad": 1 }));
+		assert_eq!(
+			response.error.unwrap().code,
+			ErrorCode::InvalidParams as i32
+		);
+
+		// Ignored, the next message is still handled
+		client.notify(DidOpenTextDocument::METHOD, serde_json::json!([1, 2]));
+		client.open(&uri, SOURCE);
+		assert_eq!(client.diagnostics().uri, uri);
+
+		client.shutdown(handle);
+	}
+
+	#[test]
+	fn answers_hover_and_symbols() {
+		let (mut client, handle) = Client::start();
+		let uri = Url::parse("file:///schema.typm").unwrap();
+
+		client.open(&uri, SOURCE);
+		client.diagnostics();
+
+		// Variable name
+		let hover = client.request(
+			HoverRequest::METHOD,
+			HoverParams {
+				text_document_position_params: position(&uri, 2, 12),
+				work_done_progress_params: WorkDoneProgressParams::default(),
+			},
+		);
+		assert_eq!(
+			hover["contents"]["value"],
+			"```typm\nrequired status: User.Status\n```\n\nStatus of the account"
+		);
+
+		// Alias reference
+		let hover = client.request(
+			HoverRequest::METHOD,
+			HoverParams {
+				text_document_position_params: position(&uri, 3, 15),
+				work_done_progress_params: WorkDoneProgressParams::default(),
+			},
+		);
+		assert_eq!(
+			hover["contents"]["value"],
+			"```typm\nalias Id = String\n```"
+		);
+
+		let symbols = client.request(
+			DocumentSymbolRequest::METHOD,
+			DocumentSymbolParams {
+				text_document: TextDocumentIdentifier::new(uri.clone()),
+				work_done_progress_params: WorkDoneProgressParams::default(),
+				partial_result_params: PartialResultParams::default(),
+			},
+		);
+		let symbols: Vec<lsp_types::DocumentSymbol> = serde_json::from_value(symbols).unwrap();
+
+		assert_eq!(symbols.len(), 2);
+		let names: Vec<&str> = symbols[0]
+			.children
+			.as_ref()
+			.unwrap()
+			.iter()
+			.map(|symbol| symbol.name.as_str())
+			.collect();
+		assert_eq!(names, vec!["status", "id", "Status"]);
+		assert_eq!(symbols[1].detail.as_deref(), Some("String"));
+
+		client.shutdown(handle);
+	}
+
+	#[test]
+	fn navigates_and_renames() {
+		let (mut client, handle) = Client::start();
+		let schema = Url::parse("file:///schema.typm").unwrap();
+		let other = Url::parse("file:///other.typm").unwrap();
+
+		client.open(&schema, SOURCE);
+		client.diagnostics();
+		client.open(
+			&other,
+			"interface User {\n\tenum Status {\n\t\tA: a;\n\t};\n};\n",
+		);
+		client.diagnostics();
+
+		// `Status` segment of `User.Status`
+		let definition = client.request(
+			GotoDefinition::METHOD,
+			GotoDefinitionParams {
+				text_document_position_params: position(&schema, 2, 24),
+				work_done_progress_params: WorkDoneProgressParams::default(),
+				partial_result_params: PartialResultParams::default(),
+			},
+		);
+		assert_eq!(
+			definition["range"]["start"],
+			serde_json::json!({ "line": 5, "character": 6 })
+		);
+
+		let references = client.request(
+			References::METHOD,
+			ReferenceParams {
+				text_document_position: position(&schema, 2, 24),
+				work_done_progress_params: WorkDoneProgressParams::default(),
+				partial_result_params: PartialResultParams::default(),
+				context: ReferenceContext {
+					include_declaration: true,
+				},
+			},
+		);
+		let references: Vec<Location> = serde_json::from_value(references).unwrap();
+		assert_eq!(references.len(), 3);
+
+		let rename = client.request(
+			Rename::METHOD,
+			RenameParams {
+				text_document_position: position(&schema, 5, 7),
+				new_name: "AccountStatus".to_string(),
+				work_done_progress_params: WorkDoneProgressParams::default(),
+			},
+		);
+		let rename: WorkspaceEdit = serde_json::from_value(rename).unwrap();
+		let changes = rename.changes.unwrap();
+		assert_eq!(changes[&schema].len(), 2);
+		assert_eq!(changes[&other].len(), 1);
+
+		client.shutdown(handle);
+	}
+}
@@ -0,0 +1,94 @@
+use core::ops::Range;
+
+use lsp_types::Position;
+
+//
+// Conversion between byte offsets and LSP positions
+//
+// LSP positions count characters in UTF-16 code units, while every span
+// in the parser is a byte range.
+#[derive(Debug)]
+pub struct LineIndex {
+	// Byte offset of every line start
+	line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+	pub fn new(source: &str) -> LineIndex {
+		let mut line_starts = vec![0];
+
+		for (offset, character) in source.char_indices() {
+			if character == '\n' {
+				line_starts.push(offset + 1);
+			};
+		}
+
+		LineIndex { line_starts }
+	}
+
+	pub fn position(&self, source: &str, offset: usize) -> Position {
+		let offset = offset.min(source.len());
+		let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+		let line_start = self.line_starts[line];
+
+		let character = source
+			.get(line_start..offset)
+			.map(|text| text.encode_utf16().count())
+			.unwrap_or(0);
+
+		Position::new(line as u32, character as u32)
+	}
+
+	pub fn range(&self, source: &str, span: &Range<usize>) -> lsp_types::Range {
+		lsp_types::Range::new(
+			self.position(source, span.start),
+			self.position(source, span.end),
+		)
+	}
+
+	// Positions past the end of a line are clamped to it
+	pub fn offset(&self, source: &str, position: Position) -> usize {
+		let line_start = match self.line_starts.get(position.line as usize) {
+			Some(start) => *start,
+			None => return source.len(),
+		};
+
+		let mut units = 0;
+
+		for (offset, character) in source[line_start..].char_indices() {
+			if units >= position.character as usize || character == '\n' {
+				return line_start + offset;
+			};
+
+			units += character.len_utf16();
+		}
+
+		source.len()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn converts_utf16_positions() {
+		let source = "/// Ünïcode 😀 docs\nalias Id = String;\n";
+		let index = LineIndex::new(source);
+
+		let emoji = source.find('😀').unwrap();
+		let after_emoji = emoji + '😀'.len_utf8();
+
+		assert_eq!(index.position(source, emoji), Position::new(0, 12));
+		assert_eq!(index.position(source, after_emoji), Position::new(0, 14));
+		assert_eq!(index.offset(source, Position::new(0, 14)), after_emoji);
+
+		let alias = source.find("alias").unwrap();
+		assert_eq!(index.position(source, alias), Position::new(1, 0));
+		assert_eq!(index.offset(source, Position::new(1, 0)), alias);
+		assert_eq!(
+			index.offset(source, Position::new(1, 100)),
+			source.len() - 1
+		);
+	}
+}
@@ -0,0 +1,19 @@
+use std::process::ExitCode;
+
+use lsp_server::Connection;
+
+// Language server for .typm files, speaks LSP over stdio
+fn main() -> ExitCode {
+	let (connection, io_threads) = Connection::stdio();
+
+	let result = lsp::run(&connection);
+	drop(connection);
+
+	match result.and_then(|_| io_threads.join().map_err(Into::into)) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(error) => {
+			eprintln!("error: {}", error);
+			ExitCode::FAILURE
+		}
+	}
+}
@@ -0,0 +1,74 @@
+use lexer::tokens::TokenType;
+use lsp_types::{DocumentSymbol, SymbolKind};
+use parser::syntax::{SyntaxKind, SyntaxNode};
+
+use crate::document::Document;
+
+//
+// Document symbols
+//
+// Outline of the file built from the syntax tree, so it's available even
+// while the file has errors. Incomplete elements without a name are skipped.
+pub fn document_symbols(document: Document) -> Vec<DocumentSymbol> {
+	symbols(document, &document.file.syntax)
+}
+
+fn symbols(document: Document, node: &SyntaxNode) -> Vec<DocumentSymbol> {
+	node.nodes()
+		.filter_map(|child| symbol(document, child))
+		.collect()
+}
+
+fn symbol(document: Document, node: &SyntaxNode) -> Option<DocumentSymbol> {
+	let kind = match node.kind {
+		SyntaxKind::Interface => SymbolKind::INTERFACE,
+		SyntaxKind::Enum => SymbolKind::ENUM,
+		SyntaxKind::Alias => SymbolKind::TYPE_PARAMETER,
+		SyntaxKind::Union => SymbolKind::STRUCT,
+		SyntaxKind::Variable => SymbolKind::FIELD,
+		SyntaxKind::Variant => SymbolKind::ENUM_MEMBER,
+		_ => return Option::None,
+	};
+
+	let name = node.first_token(TokenType::Text)?;
+
+	// Type of a variable or an alias, as written
+	let detail = node
+		.nodes()
+		.find(|child| {
+			matches!(
+				child.kind,
+				SyntaxKind::StringType
+					| SyntaxKind::IntegerType
+					| SyntaxKind::BooleanType
+					| SyntaxKind::ListType
+					| SyntaxKind::MapType
+					| SyntaxKind::ReferenceType
+			)
+		})
+		.map(|child| {
+			child
+				.text()
+				.split_whitespace()
+				.collect::<Vec<&str>>()
+				.join(" ")
+		});
+
+	let children = symbols(document, node);
+
+	#[allow(deprecated)]
+	Option::Some(DocumentSymbol {
+		name: name.name().to_string(),
+		detail,
+		kind,
+		tags: Option::None,
+		deprecated: Option::None,
+		range: document.range(&node.span),
+		selection_range: document.range(&name.span),
+		children: if children.is_empty() {
+			Option::None
+		} else {
+			Option::Some(children)
+		},
+	})
+}
@@ -0,0 +1,89 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use lexer::{get_tokens, tokenize};
+use parser::{get_ast_tree, syntax::parse};
+
+//
+// Parser scaling
+//
+// Schemas of increasing size are parsed into the syntax tree, and into the
+// declaration tree read from it. Both are linear in the number of tokens, so
+// throughput (MiB/s) should stay about the same as the input grows.
+//
+// cargo bench -p parser
+
+// Schema with `count` interfaces, every one with variables of every kind,
+// a nested enum, metadata and comments
+fn generate_schema(count: usize) -> String {
+	let mut source = String::new();
+
+	for index in 0..count {
+		source.push_str(&format!(
+			r#"/// Interface number {index}
+#rename_all = camelCase
+interface Entity{index} {{
+    required id: String;
+    optional name: String {{ "Entity {index}" }}; // constant
+    #min = 0
+    required count: Int;
+    optional active: Boolean;
+    optional tags: List<String>;
+    optional scores: Map<String, List<Int>>;
+    required status: Entity{index}.Status;
+
+    enum Status {{
+        ACTIVE: active;
+        BANNED: "banned user";
+    }};
+}};
+
+alias Id{index} = Map<String, Entity{index}>;
+union Any{index} = Entity{index} | Entity{index}.Status;
+
+"#
+		));
+	}
+
+	source
+}
+
+fn parse_schemas(criterion: &mut Criterion) {
+	let mut group = criterion.benchmark_group("parse");
+
+	for count in [100, 400, 1600, 6400] {
+		let source = generate_schema(count);
+
+		group.throughput(Throughput::Bytes(source.len() as u64));
+
+		group.bench_with_input(
+			BenchmarkId::new("syntax", count),
+			&source,
+			|bencher, source| bencher.iter(|| parse(source)),
+		);
+
+		group.bench_with_input(
+			BenchmarkId::new("declarations", count),
+			&source,
+			|bencher, source| bencher.iter(|| get_ast_tree(&parse(source))),
+		);
+	}
+
+	group.finish();
+}
+
+// Owned tokens allocate a String per token, borrowed ones don't
+fn lex(criterion: &mut Criterion) {
+	let mut group = criterion.benchmark_group("lex");
+	let source = generate_schema(1600);
+
+	group.throughput(Throughput::Bytes(source.len() as u64));
+
+	group.bench_function("owned", |bencher| bencher.iter(|| get_tokens(&source)));
+	group.bench_function("borrowed", |bencher| {
+		bencher.iter(|| tokenize(&source).count())
+	});
+
+	group.finish();
+}
+
+criterion_group!(benches, parse_schemas, lex);
+criterion_main!(benches);
@@ -0,0 +1,48 @@
+use std::ops::Range;
+
+use lexer::tokens::TokenType;
+
+use crate::{
+	entities::metadata::Metadata,
+	syntax::ast::{self, AstNode},
+	types::VariableType,
+};
+
+#[derive(Debug)]
+pub struct Alias {
+	pub name: String,
+	pub name_span: Range<usize>,
+	pub alias_type: VariableType,
+	// Byte span of the type expression
+	pub type_span: Range<usize>,
+	pub metadata: Metadata,
+	// Byte span in the source
+	pub span: Range<usize>,
+}
+
+impl Alias {
+	//
+	// Type alias
+	//
+	// Example:
+	// alias UserId = String;
+	// alias Tags = List<String>;
+	//
+	// `None` when the alias is incomplete (no name or type)
+	pub fn from_syntax(alias: ast::Alias) -> Option<Alias> {
+		let name = alias.name()?;
+		let target = alias.target()?;
+
+		Option::Some(Alias {
+			name: name.name().to_string(),
+			name_span: name.span.clone(),
+			alias_type: target.to_variable_type()?,
+			type_span: target.syntax().span.clone(),
+			metadata: Metadata {
+				attributes: alias.attributes(),
+				docs: alias.docs(),
+			},
+			span: alias.syntax().span_from(TokenType::AliasDeclaration)?,
+		})
+	}
+}
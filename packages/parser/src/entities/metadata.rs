@@ -0,0 +1,16 @@
+use core::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+	pub name: String,
+	pub value: Option<String>,
+	// Byte span in the source, from `#` to the end of the value
+	pub span: Range<usize>,
+}
+
+// Attributes and doc comments written above a declaration
+#[derive(Debug, Default)]
+pub struct Metadata {
+	pub attributes: Vec<Attribute>,
+	pub docs: Vec<String>,
+}
@@ -1,2 +1,5 @@
+pub mod alias;
 pub mod enumerate;
 pub mod interface;
+pub mod metadata;
+pub mod union;
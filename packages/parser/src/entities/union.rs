@@ -0,0 +1,49 @@
+use std::ops::Range;
+
+use lexer::tokens::TokenType;
+
+use crate::{
+	entities::metadata::Metadata,
+	syntax::ast::{self, AstNode},
+	types::reference::TypePath,
+};
+
+#[derive(Debug)]
+pub struct Union {
+	pub name: String,
+	pub name_span: Range<usize>,
+	// Paths to member interfaces
+	pub members: Vec<TypePath>,
+	pub metadata: Metadata,
+	// Byte span in the source
+	pub span: Range<usize>,
+}
+
+impl Union {
+	//
+	// Union
+	//
+	// Example:
+	// union SearchResult = User | Post | Comment;
+	//
+	// `None` when the union has no name or no members
+	pub fn from_syntax(union: ast::Union) -> Option<Union> {
+		let name = union.name()?;
+		let members: Vec<TypePath> = union.members().map(|path| path.to_type_path()).collect();
+
+		if members.is_empty() {
+			return Option::None;
+		};
+
+		Option::Some(Union {
+			name: name.name().to_string(),
+			name_span: name.span.clone(),
+			members,
+			metadata: Metadata {
+				attributes: union.attributes(),
+				docs: union.docs(),
+			},
+			span: union.syntax().span_from(TokenType::UnionDeclaration)?,
+		})
+	}
+}
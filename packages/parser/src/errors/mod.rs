@@ -1,9 +1,139 @@
-pub struct ParserError {
-    // todo 
+use core::ops::Range;
+
+use lexer::errors::LexError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+	Error,
+	Warning,
+}
+
+// Byte span in the source with a short explanation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+	pub span: Range<usize>,
+	pub message: String,
 }
 
-impl ParserError {
-    pub fn empty() -> ParserError {
-        Self {}
-    }
-}
\ No newline at end of file
+//
+// Diagnostic
+//
+// Problem found somewhere after tokenization (parser, semantic analysis...).
+// First label is the primary one, other labels point at related places
+// (for example the first definition of a duplicated name).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	pub message: String,
+	pub labels: Vec<Label>,
+}
+
+impl Diagnostic {
+	pub fn error(message: impl Into<String>) -> Diagnostic {
+		Self {
+			severity: Severity::Error,
+			message: message.into(),
+			labels: Vec::new(),
+		}
+	}
+
+	pub fn warning(message: impl Into<String>) -> Diagnostic {
+		Self {
+			severity: Severity::Warning,
+			message: message.into(),
+			labels: Vec::new(),
+		}
+	}
+
+	pub fn with_label(mut self, span: Range<usize>, message: impl Into<String>) -> Diagnostic {
+		self.labels.push(Label {
+			span,
+			message: message.into(),
+		});
+
+		self
+	}
+
+	pub fn is_error(&self) -> bool {
+		self.severity == Severity::Error
+	}
+
+	//
+	// Rust-like representation of this diagnostic
+	//
+	// Example:
+	// error: unresolved type `User.Sttus`
+	//  --> schema.typm:3:5
+	//   |
+	// 3 |     required status: User.Sttus;
+	//   |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^ no declaration with this name
+	pub fn render(&self, source: &str, file_name: &str) -> String {
+		let severity = match self.severity {
+			Severity::Error => "error",
+			Severity::Warning => "warning",
+		};
+
+		let mut result = format!("{}: {}\n", severity, self.message);
+
+		for (index, label) in self.labels.iter().enumerate() {
+			let (line, column) = line_column(source, label.span.start);
+			let line_text = source.lines().nth(line - 1).unwrap_or("");
+			let gutter = " ".repeat(line.to_string().len());
+
+			// Tabs are expanded, so that underline stays aligned
+			let prefix: String = line_text.chars().take(column - 1).collect();
+			let start = prefix.replace('\t', "    ").chars().count();
+
+			// Underlining only the first line of multi-line spans
+			let length = source[label.span.clone()]
+				.chars()
+				.take_while(|char| *char != '\n')
+				.count();
+			let end = start + length.max(1);
+			let marker = if index == 0 { "^" } else { "-" };
+
+			result.push_str(&format!(
+				"{}--> {}:{}:{}\n",
+				gutter, file_name, line, column
+			));
+			result.push_str(&format!("{} |\n", gutter));
+			result.push_str(&format!("{} | {}\n", line, line_text.replace('\t', "    ")));
+			result.push_str(&format!(
+				"{} | {}{} {}\n",
+				gutter,
+				" ".repeat(start),
+				marker.repeat(end - start),
+				label.message
+			));
+		}
+
+		result
+	}
+}
+
+impl From<LexError> for Diagnostic {
+	fn from(error: LexError) -> Diagnostic {
+		Diagnostic::error(error.message).with_label(error.span, error.label)
+	}
+}
+
+// One-based line and column (in chars) of a byte offset
+pub fn line_column(source: &str, offset: usize) -> (usize, usize) {
+	let mut line = 1;
+	let mut column = 1;
+
+	for (index, char) in source.char_indices() {
+		if index >= offset {
+			break;
+		};
+
+		if char == '\n' {
+			line += 1;
+			column = 1;
+		} else {
+			column += 1;
+		};
+	}
+
+	(line, column)
+}
@@ -1,83 +1,248 @@
-use core::ops::Range;
-use entities::{
-	enumerate::{parse_enum, Enum},
-	interface::{parse_interface, Interface},
-};
-use helpers::create_linear_numbers_array;
-use lexer::{
-	get_tokens,
-	tokens::{TokenDeclaration, TokenType},
+use entities::{alias::Alias, enumerate::Enum, interface::Interface, union::Union};
+use errors::Diagnostic;
+use syntax::{
+	ast::{self, AstNode, SourceFile},
+	Parse,
 };
 
 pub mod entities;
-pub mod helpers;
-pub mod types;
 pub mod errors;
+pub mod syntax;
+pub mod types;
 
 #[derive(Debug)]
 pub enum Entity {
 	Interface(Interface),
 	Enum(Enum),
+	Alias(Alias),
+	Union(Union),
 }
 
 #[derive(Debug)]
 pub struct Node {
-	pub range: Range<usize>,
 	pub nodes: Vec<Node>,
 	pub entity: Entity,
 }
 
+impl Node {
+	// Byte span of the declaration in the source
+	pub fn span(&self) -> &core::ops::Range<usize> {
+		match &self.entity {
+			Entity::Interface(interface) => &interface.span,
+			Entity::Enum(enumerate) => &enumerate.span,
+			Entity::Alias(alias) => &alias.span,
+			Entity::Union(union) => &union.span,
+		}
+	}
+}
+
+#[derive(Default)]
 pub struct Tree {
 	pub nodes: Vec<Node>,
-	pub parsed_indicies: Vec<usize>,
+	// Problems which didn't stop parsing, reported along with semantic ones
+	pub diagnostics: Vec<Diagnostic>,
 }
 
-impl Tree {
-	pub fn add_node(&mut self, node: Node) {
-		let range = node.range.clone();
+//
+// Declaration tree
+//
+// Owned view of the syntax tree (see `syntax` module), which semantic
+// analysis lowers into IR. There's a single parser, so this tree has the
+// same diagnostics as the syntax tree.
+//
+// Incomplete declarations, variables and variants are left out, their
+// problems are in diagnostics already.
+pub fn get_ast_tree(parse: &Parse) -> Tree {
+	let nodes = match SourceFile::cast(&parse.root) {
+		Some(file) => file.declarations().filter_map(declaration_node).collect(),
+		None => Vec::new(),
+	};
+
+	Tree {
+		nodes,
+		diagnostics: parse.diagnostics.clone(),
+	}
+}
 
-		// Adding token indecies to parsed_indicies vector
-		for index in create_linear_numbers_array(range.start, range.end) {
-			if !self.parsed_indicies.contains(&index.clone()) {
-				self.parsed_indicies.push(index);
-			};
+fn declaration_node(declaration: ast::Declaration) -> Option<Node> {
+	let (entity, nodes) = match declaration {
+		ast::Declaration::Interface(interface) => (
+			Entity::Interface(Interface::from_syntax(interface)?),
+			interface
+				.declarations()
+				.filter_map(declaration_node)
+				.collect(),
+		),
+		ast::Declaration::Enum(enumerate) => {
+			(Entity::Enum(Enum::from_syntax(enumerate)?), Vec::new())
 		}
+		ast::Declaration::Alias(alias) => (Entity::Alias(Alias::from_syntax(alias)?), Vec::new()),
+		ast::Declaration::Union(union) => (Entity::Union(Union::from_syntax(union)?), Vec::new()),
+	};
+
+	Option::Some(Node { nodes, entity })
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{get_ast_tree, syntax::parse, types::VariableType, Entity};
+
+	#[test]
+	fn parses_declarations_with_spans() {
+		let source = "/// Docs\n#deprecated\ninterface User {\n\trequired tags: List<String>; // comment\n\tenum Status { ACTIVE: \"is active\"; };\n};\nalias Id = String;\n";
+		let tree = get_ast_tree(&parse(source));
+
+		assert_eq!(tree.nodes.len(), 2);
+
+		let Entity::Interface(interface) = &tree.nodes[0].entity else {
+			panic!("Interface expected");
+		};
+
+		// Interface span ends at the closing brace, metadata isn't included
+		let text = &source[interface.span.clone()];
+		assert!(text.starts_with("interface User {") && text.ends_with("};\n}"));
+		assert_eq!(interface.metadata.docs, vec!["Docs"]);
+		assert_eq!(interface.metadata.attributes[0].name, "deprecated");
+		assert_eq!(&source[interface.name_span.clone()], "User");
+		assert_eq!(
+			&source[interface.variables[0].span.clone()],
+			"required tags: List<String>;"
+		);
+		assert_eq!(&source[interface.variables[0].name_span.clone()], "tags");
+		assert_eq!(
+			&source[interface.variables[0].type_span.clone()],
+			"List<String>"
+		);
+		assert_eq!(
+			interface.variables[0].variable_type,
+			VariableType::List(Box::new(VariableType::String(Option::None)))
+		);
+
+		let Entity::Enum(status) = &tree.nodes[0].nodes[0].entity else {
+			panic!("Enum expected");
+		};
+
+		assert_eq!(&source[status.name_span.clone()], "Status");
+		assert_eq!(status.variants[0].value.as_deref(), Some("is active"));
+		assert_eq!(&source[status.variants[0].name_span.clone()], "ACTIVE");
+		assert_eq!(
+			&source[status.variants[0].span.clone()],
+			"ACTIVE: \"is active\";"
+		);
+
+		let Entity::Alias(alias) = &tree.nodes[1].entity else {
+			panic!("Alias expected");
+		};
 
-		// Pushing node to nodes array
-		self.nodes.push(node);
+		assert_eq!(&source[alias.span.clone()], "alias Id = String;");
+		assert_eq!(&source[alias.name_span.clone()], "Id");
+		assert_eq!(&source[alias.type_span.clone()], "String");
 	}
 
-	pub fn default() -> Tree {
-		Self {
-			nodes: Vec::new(),
-			parsed_indicies: Vec::new(),
-		}
+	#[test]
+	fn skips_unexpected_characters() {
+		let source = "\u{FEFF}alias Id = String;\r\n@@ alias Name = String { \"\\q\" };\r\n";
+		let tree = get_ast_tree(&parse(source));
+
+		assert_eq!(tree.nodes.len(), 2);
+
+		let Entity::Alias(alias) = &tree.nodes[1].entity else {
+			panic!("Alias expected");
+		};
+
+		assert_eq!(
+			&source[alias.span.clone()],
+			"alias Name = String { \"\\q\" };"
+		);
+
+		// Constant keeps the span of the literal
+		let VariableType::String(Some(constant)) = &alias.alias_type else {
+			panic!("Constant expected");
+		};
+		assert_eq!(&source[constant.span.clone()], "\"\\q\"");
+
+		let errors: Vec<(&str, &str)> = tree
+			.diagnostics
+			.iter()
+			.map(|diagnostic| {
+				let span = diagnostic.labels[0].span.clone();
+				(diagnostic.message.as_str(), &source[span])
+			})
+			.collect();
+
+		assert_eq!(
+			errors,
+			[
+				("unexpected characters `@@`", "@@"),
+				("unknown escape sequence `\\q`", "\\q"),
+			]
+		);
 	}
-}
 
-pub fn get_ast_tree(tokens: Vec<TokenDeclaration>) -> Tree {
-	let mut tree = Tree::default();
-
-	for (index, token) in tokens.iter().enumerate() {
-		// Checking if we already parsed token on this index
-		if !tree.parsed_indicies.contains(&index) {
-			match token.token_type.clone() {
-				TokenType::InterfaceDeclaration => {
-					let node = parse_interface(&tokens, index);
-					tree.add_node(node);
-				}
-				TokenType::EnumerateDeclaration => {
-					let node = parse_enum(&tokens, index);
-					tree.add_node(node);
-				}
-				TokenType::Whitespace => { /* Ignoring */ }
-				token_type => {
-					// Error
-					panic!("{:?} is not an top-level keyword.", token_type);
-				}
-			};
+	#[test]
+	fn recovers_from_syntax_errors() {
+		let source = "#min = 3\nrequired id: String;\ninterface User {\n\trequired id String;\n\tenum Kind { A a; B: b; };\n\toptional name: String { \"x\" };\n};\n} alias Id = ;\nalias Name = String;\n";
+		let tree = get_ast_tree(&parse(source));
+
+		let errors: Vec<(&str, &str)> = tree
+			.diagnostics
+			.iter()
+			.map(|diagnostic| {
+				let span = diagnostic.labels[0].span.clone();
+				(diagnostic.message.as_str(), &source[span])
+			})
+			.collect();
+
+		assert_eq!(
+			errors,
+			[
+				("variables are only allowed inside interfaces", "required"),
+				("expected `:`, found `String`", "String"),
+				("expected `:`, found `a`", "a"),
+				("expected a declaration, found `}`", "}"),
+				("expected a type, found `;`", ";"),
+			]
+		);
+
+		// Valid parts around the errors are kept
+		let Entity::Interface(interface) = &tree.nodes[0].entity else {
+			panic!("Interface expected");
+		};
+		assert_eq!(interface.variables.len(), 1);
+		assert_eq!(interface.variables[0].name, "name");
+
+		let Entity::Enum(kind) = &tree.nodes[0].nodes[0].entity else {
+			panic!("Enum expected");
+		};
+		assert_eq!(kind.variants.len(), 1);
+		assert_eq!(kind.variants[0].name, "B");
+
+		let Entity::Alias(alias) = &tree.nodes[1].entity else {
+			panic!("Alias expected");
 		};
+		assert_eq!(alias.name, "Name");
 	}
 
-	tree
+	#[test]
+	fn reports_empty_constants() {
+		let source = "interface User {\n\trequired kind: String { };\n\trequired id: String;\n};\n";
+		let tree = get_ast_tree(&parse(source));
+
+		let errors: Vec<(&str, &str)> = tree
+			.diagnostics
+			.iter()
+			.map(|diagnostic| {
+				let span = diagnostic.labels[0].span.clone();
+				(diagnostic.message.as_str(), &source[span])
+			})
+			.collect();
+
+		assert_eq!(errors, [("expected a constant value, found `}`", "}")]);
+
+		// Parsing goes on after the closing brace
+		let Entity::Interface(interface) = &tree.nodes[0].entity else {
+			panic!("Interface expected");
+		};
+		assert_eq!(interface.variables.len(), 2);
+	}
 }
@@ -0,0 +1,497 @@
+use lexer::{strings::decode_string, tokens::TokenType};
+
+use crate::{
+	entities::metadata::Attribute,
+	types::{reference::TypePath, string::Constant, VariableType},
+};
+
+use super::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+//
+// Typed AST
+//
+// Thin typed wrappers over syntax nodes. Every accessor returns `None`
+// (or skips an element) when the source is incomplete, so the view works
+// on a tree with syntax errors as well.
+pub trait AstNode<'a>: Sized {
+	fn cast(node: &'a SyntaxNode) -> Option<Self>;
+
+	fn syntax(&self) -> &'a SyntaxNode;
+}
+
+macro_rules! ast_node {
+	($name:ident, $kind:ident) => {
+		#[derive(Debug, Clone, Copy)]
+		pub struct $name<'a>(&'a SyntaxNode);
+
+		impl<'a> AstNode<'a> for $name<'a> {
+			fn cast(node: &'a SyntaxNode) -> Option<Self> {
+				if node.kind == SyntaxKind::$kind {
+					Option::Some(Self(node))
+				} else {
+					Option::None
+				}
+			}
+
+			fn syntax(&self) -> &'a SyntaxNode {
+				self.0
+			}
+		}
+	};
+}
+
+ast_node!(SourceFile, SourceFile);
+ast_node!(Interface, Interface);
+ast_node!(Enum, Enum);
+ast_node!(Alias, Alias);
+ast_node!(Union, Union);
+ast_node!(Variable, Variable);
+ast_node!(Variant, Variant);
+ast_node!(AttributeNode, Attribute);
+ast_node!(Path, Path);
+
+fn children<'a, N: AstNode<'a> + 'a>(node: &'a SyntaxNode) -> impl Iterator<Item = N> + 'a {
+	node.nodes().filter_map(N::cast)
+}
+
+fn child<'a, N: AstNode<'a>>(node: &'a SyntaxNode) -> Option<N> {
+	node.nodes().find_map(N::cast)
+}
+
+// First `Text` token directly inside the node
+fn name_token(node: &SyntaxNode) -> Option<&SyntaxToken> {
+	node.first_token(TokenType::Text)
+}
+
+//
+// Declarations
+//
+#[derive(Debug, Clone, Copy)]
+pub enum Declaration<'a> {
+	Interface(Interface<'a>),
+	Enum(Enum<'a>),
+	Alias(Alias<'a>),
+	Union(Union<'a>),
+}
+
+impl<'a> AstNode<'a> for Declaration<'a> {
+	fn cast(node: &'a SyntaxNode) -> Option<Self> {
+		match node.kind {
+			SyntaxKind::Interface => Option::Some(Declaration::Interface(Interface(node))),
+			SyntaxKind::Enum => Option::Some(Declaration::Enum(Enum(node))),
+			SyntaxKind::Alias => Option::Some(Declaration::Alias(Alias(node))),
+			SyntaxKind::Union => Option::Some(Declaration::Union(Union(node))),
+			_ => Option::None,
+		}
+	}
+
+	fn syntax(&self) -> &'a SyntaxNode {
+		match self {
+			Declaration::Interface(interface) => interface.0,
+			Declaration::Enum(enumerate) => enumerate.0,
+			Declaration::Alias(alias) => alias.0,
+			Declaration::Union(union) => union.0,
+		}
+	}
+}
+
+impl<'a> Declaration<'a> {
+	pub fn name(&self) -> Option<&'a SyntaxToken> {
+		name_token(self.syntax())
+	}
+
+	pub fn docs(&self) -> Vec<String> {
+		docs(self.syntax())
+	}
+
+	pub fn attributes(&self) -> Vec<Attribute> {
+		attributes(self.syntax())
+	}
+}
+
+impl<'a> SourceFile<'a> {
+	pub fn declarations(&self) -> impl Iterator<Item = Declaration<'a>> {
+		children(self.0)
+	}
+}
+
+//
+// Metadata
+//
+
+// Doc comment lines without `///` and a single leading space
+fn docs(node: &SyntaxNode) -> Vec<String> {
+	node.tokens()
+		.filter(|token| token.kind == TokenType::DocComment)
+		.map(|token| {
+			let line = token.text.trim_start_matches("///");
+			line.strip_prefix(' ')
+				.unwrap_or(line)
+				.trim_end()
+				.to_string()
+		})
+		.collect()
+}
+
+// Metadata of a specific declaration kind, same as the one of `Declaration`
+macro_rules! metadata_accessors {
+	($($name:ident),*) => {
+		$(
+			impl<'a> $name<'a> {
+				pub fn docs(&self) -> Vec<String> {
+					docs(self.0)
+				}
+
+				pub fn attributes(&self) -> Vec<Attribute> {
+					attributes(self.0)
+				}
+			}
+		)*
+	};
+}
+
+metadata_accessors!(Interface, Enum, Alias, Union);
+
+fn attributes(node: &SyntaxNode) -> Vec<Attribute> {
+	children::<AttributeNode>(node)
+		.filter_map(|attribute| {
+			Option::Some(Attribute {
+				name: attribute.name()?.text.clone(),
+				value: attribute.value(),
+				span: attribute.0.span.clone(),
+			})
+		})
+		.collect()
+}
+
+impl<'a> AttributeNode<'a> {
+	pub fn name(&self) -> Option<&'a SyntaxToken> {
+		name_token(self.0)
+	}
+
+	pub fn value(&self) -> Option<String> {
+		value(self.0, 1)
+	}
+}
+
+// Value after `skip` Text tokens: either a plain Text or a string literal
+fn value(node: &SyntaxNode, skip: usize) -> Option<String> {
+	if let Some(token) = node.first_token(TokenType::StringLiteral) {
+		// Invalid literals are reported by the parser, their raw text is used
+		let value = decode_string(&token.text, token.span.start);
+		return Option::Some(value.unwrap_or_else(|_| token.text.clone()));
+	};
+
+	node.tokens()
+		.filter(|token| token.kind == TokenType::Text)
+		.nth(skip)
+		.map(|token| token.text.clone())
+}
+
+//
+// Interface
+//
+impl<'a> Interface<'a> {
+	pub fn name(&self) -> Option<&'a SyntaxToken> {
+		name_token(self.0)
+	}
+
+	pub fn extends(&self) -> Option<Path<'a>> {
+		self.0.first_node(SyntaxKind::Extends).and_then(child)
+	}
+
+	pub fn variables(&self) -> impl Iterator<Item = Variable<'a>> {
+		children(self.0)
+	}
+
+	// Nested declarations
+	pub fn declarations(&self) -> impl Iterator<Item = Declaration<'a>> {
+		children(self.0)
+	}
+}
+
+impl<'a> Variable<'a> {
+	pub fn name(&self) -> Option<&'a SyntaxToken> {
+		name_token(self.0)
+	}
+
+	pub fn is_required(&self) -> bool {
+		self.0.first_token(TokenType::RequiredModifier).is_some()
+	}
+
+	pub fn variable_type(&self) -> Option<TypeExpr<'a>> {
+		child(self.0)
+	}
+
+	pub fn docs(&self) -> Vec<String> {
+		docs(self.0)
+	}
+
+	pub fn attributes(&self) -> Vec<Attribute> {
+		attributes(self.0)
+	}
+}
+
+//
+// Enum
+//
+impl<'a> Enum<'a> {
+	pub fn name(&self) -> Option<&'a SyntaxToken> {
+		name_token(self.0)
+	}
+
+	pub fn variants(&self) -> impl Iterator<Item = Variant<'a>> {
+		children(self.0)
+	}
+}
+
+impl<'a> Variant<'a> {
+	pub fn name(&self) -> Option<&'a SyntaxToken> {
+		name_token(self.0)
+	}
+
+	pub fn value(&self) -> Option<String> {
+		value(self.0, 1)
+	}
+
+	pub fn docs(&self) -> Vec<String> {
+		docs(self.0)
+	}
+
+	pub fn attributes(&self) -> Vec<Attribute> {
+		attributes(self.0)
+	}
+}
+
+//
+// Alias and union
+//
+impl<'a> Alias<'a> {
+	pub fn name(&self) -> Option<&'a SyntaxToken> {
+		name_token(self.0)
+	}
+
+	pub fn target(&self) -> Option<TypeExpr<'a>> {
+		child(self.0)
+	}
+}
+
+impl<'a> Union<'a> {
+	pub fn name(&self) -> Option<&'a SyntaxToken> {
+		name_token(self.0)
+	}
+
+	pub fn members(&self) -> impl Iterator<Item = Path<'a>> {
+		children(self.0)
+	}
+}
+
+//
+// Types
+//
+#[derive(Debug, Clone, Copy)]
+pub enum TypeExpr<'a> {
+	String(&'a SyntaxNode),
+	Integer(&'a SyntaxNode),
+	Boolean(&'a SyntaxNode),
+	List(&'a SyntaxNode),
+	Map(&'a SyntaxNode),
+	Reference(&'a SyntaxNode),
+}
+
+impl<'a> AstNode<'a> for TypeExpr<'a> {
+	fn cast(node: &'a SyntaxNode) -> Option<Self> {
+		match node.kind {
+			SyntaxKind::StringType => Option::Some(TypeExpr::String(node)),
+			SyntaxKind::IntegerType => Option::Some(TypeExpr::Integer(node)),
+			SyntaxKind::BooleanType => Option::Some(TypeExpr::Boolean(node)),
+			SyntaxKind::ListType => Option::Some(TypeExpr::List(node)),
+			SyntaxKind::MapType => Option::Some(TypeExpr::Map(node)),
+			SyntaxKind::ReferenceType => Option::Some(TypeExpr::Reference(node)),
+			_ => Option::None,
+		}
+	}
+
+	fn syntax(&self) -> &'a SyntaxNode {
+		match self {
+			TypeExpr::String(node)
+			| TypeExpr::Integer(node)
+			| TypeExpr::Boolean(node)
+			| TypeExpr::List(node)
+			| TypeExpr::Map(node)
+			| TypeExpr::Reference(node) => node,
+		}
+	}
+}
+
+impl<'a> TypeExpr<'a> {
+	// Generic arguments of List and Map
+	pub fn arguments(&self) -> Vec<TypeExpr<'a>> {
+		children(self.syntax()).collect()
+	}
+
+	// Value of `String { ... }`
+	pub fn constant(&self) -> Option<Constant> {
+		let TypeExpr::String(node) = self else {
+			return Option::None;
+		};
+
+		let constant = node.first_node(SyntaxKind::Constant)?;
+		let token = constant
+			.tokens()
+			.find(|token| matches!(token.kind, TokenType::Text | TokenType::StringLiteral))?;
+
+		Option::Some(Constant {
+			value: value(constant, 0)?,
+			span: token.span.clone(),
+		})
+	}
+
+	pub fn path(&self) -> Option<Path<'a>> {
+		match self {
+			TypeExpr::Reference(node) => child(node),
+			_ => Option::None,
+		}
+	}
+
+	// Type of the declaration tree (see `get_ast_tree`), `None` if incomplete
+	pub fn to_variable_type(&self) -> Option<VariableType> {
+		let arguments = self.arguments();
+
+		Option::Some(match self {
+			TypeExpr::String(_) => VariableType::String(self.constant()),
+			TypeExpr::Integer(_) => VariableType::Integer,
+			TypeExpr::Boolean(_) => VariableType::Boolean,
+			TypeExpr::List(_) => {
+				VariableType::List(Box::new(arguments.first()?.to_variable_type()?))
+			}
+			TypeExpr::Map(_) => VariableType::Map(
+				Box::new(arguments.first()?.to_variable_type()?),
+				Box::new(arguments.get(1)?.to_variable_type()?),
+			),
+			TypeExpr::Reference(_) => VariableType::Reference(self.path()?.to_type_path()),
+		})
+	}
+}
+
+impl<'a> Path<'a> {
+	pub fn segments(&self) -> Vec<String> {
+		self.0
+			.tokens()
+			.filter(|token| token.kind == TokenType::Text)
+			.map(|token| token.name().to_string())
+			.collect()
+	}
+
+	pub fn to_type_path(&self) -> TypePath {
+		TypePath {
+			segments: self.segments(),
+			span: self.0.span.clone(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::syntax::parse;
+
+	#[test]
+	fn reads_declarations() {
+		let parse = parse(
+			"
+			/// Registered user
+			#rename_all = camelCase
+			interface User extends Base.Entity {
+				#pattern = \"^[a-z]+ $\"
+				required name: String;
+				optional scores: Map<String, List<Int>>;
+
+				enum Status {
+					ACTIVE: \"is active\";
+				};
+			};
+
+			union Anyone = User | Guest;
+			",
+		);
+		let file = SourceFile::cast(&parse.root).unwrap();
+		let declarations: Vec<Declaration> = file.declarations().collect();
+
+		assert_eq!(declarations.len(), 2);
+		assert_eq!(declarations[0].docs(), vec!["Registered user".to_string()]);
+		assert_eq!(
+			declarations[0].attributes(),
+			vec![Attribute {
+				name: "rename_all".to_string(),
+				value: Option::Some("camelCase".to_string()),
+				span: 27..50,
+			}]
+		);
+
+		let Declaration::Interface(user) = declarations[0] else {
+			panic!("Interface expected");
+		};
+		assert_eq!(user.name().unwrap().text, "User");
+		assert_eq!(user.extends().unwrap().segments(), vec!["Base", "Entity"]);
+
+		let variables: Vec<Variable> = user.variables().collect();
+		assert!(variables[0].is_required());
+		assert_eq!(
+			variables[0].attributes()[0].value.as_deref(),
+			Some("^[a-z]+ $")
+		);
+		assert_eq!(
+			variables[1].variable_type().unwrap().to_variable_type(),
+			Option::Some(VariableType::Map(
+				Box::new(VariableType::String(Option::None)),
+				Box::new(VariableType::List(Box::new(VariableType::Integer)))
+			))
+		);
+
+		let Some(Declaration::Enum(status)) = user.declarations().next() else {
+			panic!("Enum expected");
+		};
+		let variant = status.variants().next().unwrap();
+		assert_eq!(variant.name().unwrap().text, "ACTIVE");
+		assert_eq!(variant.value().as_deref(), Some("is active"));
+
+		let Declaration::Union(anyone) = declarations[1] else {
+			panic!("Union expected");
+		};
+		assert_eq!(anyone.members().count(), 2);
+	}
+
+	#[test]
+	fn reads_keywords_and_raw_identifiers_as_names() {
+		let source = "interface r#String {\n\trequired enum: r#String.interface;\n\tenum interface { String: required; };\n};";
+		let parse = parse(source);
+
+		assert!(parse.diagnostics.is_empty());
+		assert_eq!(parse.root.text(), source);
+
+		let file = SourceFile::cast(&parse.root).unwrap();
+		let Some(Declaration::Interface(interface)) = file.declarations().next() else {
+			panic!("Interface expected");
+		};
+		assert_eq!(interface.name().unwrap().text, "r#String");
+		assert_eq!(interface.name().unwrap().name(), "String");
+
+		let variable = interface.variables().next().unwrap();
+		assert_eq!(variable.name().unwrap().name(), "enum");
+		assert_eq!(
+			variable.variable_type().unwrap().to_variable_type(),
+			Option::Some(VariableType::Reference(TypePath {
+				segments: vec!["String".to_string(), "interface".to_string()],
+				span: 37..55,
+			}))
+		);
+
+		let Some(Declaration::Enum(kind)) = interface.declarations().next() else {
+			panic!("Enum expected");
+		};
+		let variant = kind.variants().next().unwrap();
+		assert_eq!(variant.name().unwrap().name(), "String");
+		assert_eq!(variant.value().as_deref(), Some("required"));
+	}
+}
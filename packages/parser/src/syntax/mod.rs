@@ -0,0 +1,213 @@
+use core::ops::Range;
+
+use lexer::tokens::{identifier_name, TokenType};
+
+pub use self::parse::{parse, Parse};
+
+pub mod ast;
+pub mod parse;
+
+//
+// Concrete syntax tree
+//
+// Lossless representation of a .typm file: every token, including
+// whitespace and comments, belongs to exactly one node, so the text of
+// the root node is the original source byte-for-byte. Syntax errors
+// don't stop parsing, unexpected tokens are wrapped into `Error` nodes.
+//
+// Trivia before a node belongs to it's parent, so nodes start with their
+// first significant token (doc comments and attributes are a part of
+// the declaration they describe).
+//
+// Typed access to the tree is provided by `ast` module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntaxKind {
+	SourceFile,
+
+	// Declarations
+	Interface,
+	Enum,
+	Alias,
+	Union,
+
+	// Members
+	Variable,
+	Variant,
+	Attribute,
+
+	// `extends User.Base`
+	Extends,
+	// `User.Status`
+	Path,
+	// `{ "constant value" }` of a String type
+	Constant,
+
+	// Types
+	StringType,
+	IntegerType,
+	BooleanType,
+	ListType,
+	MapType,
+	ReferenceType,
+
+	// Tokens which couldn't be parsed
+	Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxToken {
+	pub kind: TokenType,
+	pub text: String,
+	// Byte span in the source
+	pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxElement {
+	Node(SyntaxNode),
+	Token(SyntaxToken),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxNode {
+	pub kind: SyntaxKind,
+	pub children: Vec<SyntaxElement>,
+	// Byte span in the source
+	pub span: Range<usize>,
+}
+
+impl SyntaxElement {
+	pub fn span(&self) -> Range<usize> {
+		match self {
+			SyntaxElement::Node(node) => node.span.clone(),
+			SyntaxElement::Token(token) => token.span.clone(),
+		}
+	}
+}
+
+impl SyntaxToken {
+	// Name written by an identifier token, without the prefix of a raw
+	// identifier (`r#enum` names `enum`)
+	pub fn name(&self) -> &str {
+		identifier_name(&self.text)
+	}
+}
+
+impl SyntaxNode {
+	// Source text covered by this node, including trivia inside of it
+	pub fn text(&self) -> String {
+		let mut text = String::new();
+		self.write_text(&mut text);
+		text
+	}
+
+	fn write_text(&self, text: &mut String) {
+		for child in self.children.iter() {
+			match child {
+				SyntaxElement::Node(node) => node.write_text(text),
+				SyntaxElement::Token(token) => text.push_str(&token.text),
+			};
+		}
+	}
+
+	// Child nodes
+	pub fn nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+		self.children.iter().filter_map(|child| match child {
+			SyntaxElement::Node(node) => Option::Some(node),
+			SyntaxElement::Token(_) => Option::None,
+		})
+	}
+
+	// Child tokens, without ones of child nodes
+	pub fn tokens(&self) -> impl Iterator<Item = &SyntaxToken> {
+		self.children.iter().filter_map(|child| match child {
+			SyntaxElement::Token(token) => Option::Some(token),
+			SyntaxElement::Node(_) => Option::None,
+		})
+	}
+
+	pub fn first_node(&self, kind: SyntaxKind) -> Option<&SyntaxNode> {
+		self.nodes().find(|node| node.kind == kind)
+	}
+
+	pub fn first_token(&self, kind: TokenType) -> Option<&SyntaxToken> {
+		self.tokens().find(|token| token.kind == kind)
+	}
+
+	// From the first child token of the kind to the end of this node, so that
+	// metadata in front of a declaration's keyword isn't included
+	pub fn span_from(&self, kind: TokenType) -> Option<Range<usize>> {
+		let start = self.first_token(kind)?.span.start;
+		Option::Some(start..self.span.end)
+	}
+
+	// Every token of this node and it's descendants, in source order
+	pub fn descendant_tokens(&self) -> Vec<&SyntaxToken> {
+		let mut tokens = Vec::new();
+		self.collect_tokens(&mut tokens);
+		tokens
+	}
+
+	fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a SyntaxToken>) {
+		for child in self.children.iter() {
+			match child {
+				SyntaxElement::Node(node) => node.collect_tokens(tokens),
+				SyntaxElement::Token(token) => tokens.push(token),
+			};
+		}
+	}
+
+	// Innermost nodes containing the offset, starting from this one
+	pub fn ancestors_at(&self, offset: usize) -> Vec<&SyntaxNode> {
+		let mut ancestors = vec![self];
+		let mut current = self;
+
+		while let Some(node) = current
+			.nodes()
+			.find(|node| node.span.start <= offset && offset <= node.span.end)
+		{
+			ancestors.push(node);
+			current = node;
+		}
+
+		ancestors
+	}
+
+	//
+	// Indented dump of the tree, used in tests
+	//
+	// Example:
+	// Alias@0..16
+	//   AliasDeclaration@0..5 "alias"
+	//   Whitespace@5..6 " "
+	//   ...
+	pub fn debug_tree(&self) -> String {
+		let mut output = String::new();
+		self.write_debug(&mut output, 0);
+		output
+	}
+
+	fn write_debug(&self, output: &mut String, level: usize) {
+		output.push_str(&format!(
+			"{}{:?}@{}..{}\n",
+			"  ".repeat(level),
+			self.kind,
+			self.span.start,
+			self.span.end
+		));
+
+		for child in self.children.iter() {
+			match child {
+				SyntaxElement::Node(node) => node.write_debug(output, level + 1),
+				SyntaxElement::Token(token) => output.push_str(&format!(
+					"{}{:?}@{}..{} {:?}\n",
+					"  ".repeat(level + 1),
+					token.kind,
+					token.span.start,
+					token.span.end,
+					token.text
+				)),
+			};
+		}
+	}
+}
@@ -0,0 +1,807 @@
+use lexer::{
+	lex,
+	tokens::{Token, TokenType},
+};
+
+use crate::errors::Diagnostic;
+
+use super::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+// Result of parsing: the tree is always built, even for invalid source
+#[derive(Debug)]
+pub struct Parse {
+	pub root: SyntaxNode,
+	pub diagnostics: Vec<Diagnostic>,
+}
+
+pub fn parse(source: &str) -> Parse {
+	let (tokens, errors) = lex(source);
+	let mut parser = Parser {
+		tokens,
+		position: 0,
+		source_length: source.len(),
+		stack: Vec::new(),
+		diagnostics: errors.into_iter().map(Diagnostic::from).collect(),
+	};
+
+	parser.start_node(SyntaxKind::SourceFile);
+	parse_source_file(&mut parser);
+
+	// Trailing trivia
+	while parser.position < parser.tokens.len() {
+		parser.bump_raw();
+	}
+
+	let root = parser.finish_root();
+
+	// Lexical and syntax errors in the order of appearance
+	let mut diagnostics = parser.diagnostics;
+	diagnostics.sort_by_key(|diagnostic| {
+		diagnostic
+			.labels
+			.first()
+			.map_or(usize::MAX, |label| label.span.start)
+	});
+
+	Parse { root, diagnostics }
+}
+
+// Unexpected characters are reported by the lexer and kept in the tree
+// along with trivia, the parser doesn't see them
+fn is_skipped(token: &Token) -> bool {
+	token.token_type.is_trivia() || token.token_type == TokenType::Error
+}
+
+//
+// Parser
+//
+// Forward-only cursor over tokens, which borrow their text from the source.
+// Text is copied only into tokens of the tree. Lookahead skips trivia and
+// unexpected characters, which are attached to the current node when the
+// next significant token is consumed.
+// Nodes are built on a stack: `start_node` opens a node, `finish_node`
+// closes it and adds it to it's parent. `checkpoint` + `start_node_at`
+// open a node retroactively, wrapping already consumed children.
+struct Parser<'src> {
+	tokens: Vec<Token<'src>>,
+	position: usize,
+	source_length: usize,
+	stack: Vec<(SyntaxKind, Vec<SyntaxElement>)>,
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl<'src> Parser<'src> {
+	// Next significant token
+	fn current(&self) -> Option<&Token<'src>> {
+		self.tokens[self.position..]
+			.iter()
+			.find(|token| !is_skipped(token))
+	}
+
+	fn current_kind(&self) -> Option<TokenType> {
+		self.current().map(|token| token.token_type.clone())
+	}
+
+	fn at(&self, kind: TokenType) -> bool {
+		self.current_kind() == Option::Some(kind)
+	}
+
+	fn at_end(&self) -> bool {
+		self.current().is_none()
+	}
+
+	fn bump_raw(&mut self) {
+		let token = &self.tokens[self.position];
+		let token = SyntaxToken {
+			kind: token.token_type.clone(),
+			text: token.value.to_string(),
+			span: token.span.clone(),
+		};
+
+		self.position += 1;
+		self.push(SyntaxElement::Token(token));
+	}
+
+	fn bump_trivia(&mut self) {
+		while self.tokens.get(self.position).is_some_and(is_skipped) {
+			self.bump_raw();
+		}
+	}
+
+	// Consumes next significant token with the trivia before it
+	fn bump(&mut self) {
+		self.bump_trivia();
+		if self.position < self.tokens.len() {
+			self.bump_raw();
+		};
+	}
+
+	fn push(&mut self, element: SyntaxElement) {
+		self.stack.last_mut().unwrap().1.push(element);
+	}
+
+	fn start_node(&mut self, kind: SyntaxKind) {
+		if !self.stack.is_empty() {
+			self.bump_trivia();
+		};
+
+		self.stack.push((kind, Vec::new()));
+	}
+
+	fn checkpoint(&mut self) -> usize {
+		self.bump_trivia();
+		self.stack.last().unwrap().1.len()
+	}
+
+	fn start_node_at(&mut self, checkpoint: usize, kind: SyntaxKind) {
+		let children = self.stack.last_mut().unwrap().1.split_off(checkpoint);
+		self.stack.push((kind, children));
+	}
+
+	fn finish_node(&mut self) {
+		let (kind, children) = self.stack.pop().unwrap();
+		let node = self.build_node(kind, children);
+		self.push(SyntaxElement::Node(node));
+	}
+
+	fn finish_root(&mut self) -> SyntaxNode {
+		let (kind, children) = self.stack.pop().unwrap();
+		self.build_node(kind, children)
+	}
+
+	fn build_node(&self, kind: SyntaxKind, children: Vec<SyntaxElement>) -> SyntaxNode {
+		let span = match (children.first(), children.last()) {
+			(Some(first), Some(last)) => first.span().start..last.span().end,
+			_ => {
+				let offset = self.offset();
+				offset..offset
+			}
+		};
+
+		SyntaxNode {
+			kind,
+			children,
+			span,
+		}
+	}
+
+	// Start of the next significant token, or the end of the source
+	fn offset(&self) -> usize {
+		match self.current() {
+			Some(token) => token.span.start,
+			None => self.source_length,
+		}
+	}
+
+	// Identifier or a keyword, which is a name where a name is expected
+	fn at_name(&self) -> bool {
+		self.current_kind()
+			.is_some_and(|kind| kind == TokenType::Text || kind.is_keyword())
+	}
+
+	// Consumes a name, keywords become `Text` tokens in the tree
+	fn expect_name(&mut self, expected: &str) -> bool {
+		if !self.at_name() {
+			self.error(expected);
+			return false;
+		};
+
+		self.bump_trivia();
+		self.tokens[self.position].token_type = TokenType::Text;
+		self.bump_raw();
+		true
+	}
+
+	// Consumes the token if it's there, reports it otherwise
+	fn expect(&mut self, kind: TokenType, expected: &str) -> bool {
+		if self.at(kind) {
+			self.bump();
+			true
+		} else {
+			self.error(expected);
+			false
+		}
+	}
+
+	// `expected ..., found ...` at the next significant token
+	fn error(&mut self, expected: &str) {
+		let (found, span) = match self.current() {
+			Some(token) => (format!("`{}`", token.value), token.span.clone()),
+			None => (
+				"end of file".to_string(),
+				self.source_length..self.source_length,
+			),
+		};
+
+		self.diagnostics.push(
+			Diagnostic::error(format!("expected {}, found {}", expected, found))
+				.with_label(span, format!("expected {}", expected)),
+		);
+	}
+
+	// Skips the rest of a broken item into an `Error` node: up to and
+	// including the next `;`, or up to the `}` closing the body it's in.
+	// Braces opened on the way (`String { ... }`) are skipped as a whole,
+	// so a broken item is reported once
+	fn recover(&mut self) {
+		self.start_node(SyntaxKind::Error);
+
+		let mut depth = 0;
+		while let Some(kind) = self.current_kind() {
+			match kind {
+				TokenType::RightCurlyBraces => depth += 1,
+				TokenType::LeftCurlyBraces if depth == 0 => break,
+				TokenType::LeftCurlyBraces => depth -= 1,
+				TokenType::Semicolon if depth == 0 => {
+					self.bump();
+					break;
+				}
+				_ => {}
+			};
+
+			self.bump();
+		}
+
+		self.finish_node();
+	}
+
+	// Reports and skips the next significant token
+	fn error_and_bump(&mut self, expected: &str) {
+		self.error(expected);
+
+		self.start_node(SyntaxKind::Error);
+		self.bump();
+		self.finish_node();
+	}
+}
+
+//
+// Source file
+//
+// Structure:
+// (Declaration | [anything else] => Error)*
+fn parse_source_file(parser: &mut Parser) {
+	while !parser.at_end() {
+		// Variables are parsed to report them as a whole
+		if starts_member(parser) {
+			parse_declaration(parser, false);
+		} else {
+			parser.error_and_bump("a declaration");
+		};
+	}
+}
+
+fn starts_declaration(parser: &Parser) -> bool {
+	matches!(
+		parser.current_kind(),
+		Some(
+			TokenType::InterfaceDeclaration
+				| TokenType::EnumerateDeclaration
+				| TokenType::AliasDeclaration
+				| TokenType::UnionDeclaration
+				| TokenType::AttributeDeclaration
+				| TokenType::DocComment
+		)
+	)
+}
+
+fn starts_member(parser: &Parser) -> bool {
+	starts_declaration(parser)
+		|| matches!(
+			parser.current_kind(),
+			Some(TokenType::RequiredModifier | TokenType::OptionalModifier)
+		)
+}
+
+//
+// Declaration or interface member with it's metadata
+//
+// Structure:
+// (AttributeDeclaration => parse_attribute | DocComment)*
+// 		(InterfaceDeclaration => parse_interface
+// 		| EnumerateDeclaration => parse_enum
+// 		| AliasDeclaration => parse_alias
+// 		| UnionDeclaration => parse_union
+// 		| (RequiredModifier | OptionalModifier) => parse_variable)
+//
+// Variables outside of interfaces are wrapped into an error node
+fn parse_declaration(parser: &mut Parser, in_interface: bool) {
+	let checkpoint = parser.checkpoint();
+	parse_metadata(parser);
+
+	let kind = match parser.current_kind() {
+		Some(TokenType::InterfaceDeclaration) => SyntaxKind::Interface,
+		Some(TokenType::EnumerateDeclaration) => SyntaxKind::Enum,
+		Some(TokenType::AliasDeclaration) => SyntaxKind::Alias,
+		Some(TokenType::UnionDeclaration) => SyntaxKind::Union,
+		Some(TokenType::RequiredModifier | TokenType::OptionalModifier) if in_interface => {
+			SyntaxKind::Variable
+		}
+		Some(TokenType::RequiredModifier | TokenType::OptionalModifier) => {
+			let span = parser.current().unwrap().span.clone();
+			parser.diagnostics.push(
+				Diagnostic::error("variables are only allowed inside interfaces")
+					.with_label(span, "variable outside of an interface"),
+			);
+
+			parser.start_node_at(checkpoint, SyntaxKind::Error);
+			parse_variable(parser);
+			parser.finish_node();
+			return;
+		}
+		_ => {
+			// Metadata without a declaration
+			parser.error("a declaration after attributes or doc comments");
+			parser.start_node_at(checkpoint, SyntaxKind::Error);
+			parser.finish_node();
+			return;
+		}
+	};
+
+	parser.start_node_at(checkpoint, kind);
+
+	match kind {
+		SyntaxKind::Interface => parse_interface(parser),
+		SyntaxKind::Enum => parse_enum(parser),
+		SyntaxKind::Alias => parse_alias(parser),
+		SyntaxKind::Union => parse_union(parser),
+		_ => parse_variable(parser),
+	};
+
+	parser.finish_node();
+}
+
+fn parse_metadata(parser: &mut Parser) {
+	loop {
+		match parser.current_kind() {
+			Some(TokenType::AttributeDeclaration) => parse_attribute(parser),
+			Some(TokenType::DocComment) => parser.bump(),
+			_ => break,
+		};
+	}
+}
+
+//
+// Attribute
+//
+// Structure:
+// AttributeDeclaration Text (AttributeAssignment (Text | StringLiteral))?
+fn parse_attribute(parser: &mut Parser) {
+	parser.start_node(SyntaxKind::Attribute);
+	parser.bump();
+
+	parser.expect(TokenType::Text, "an attribute name");
+
+	if parser.at(TokenType::AttributeAssignment) {
+		parser.bump();
+		parse_value(parser, "an attribute value");
+	};
+
+	parser.finish_node();
+}
+
+// Text | StringLiteral
+fn parse_value(parser: &mut Parser, expected: &str) -> bool {
+	match parser.current_kind() {
+		Some(TokenType::Text | TokenType::StringLiteral) => {
+			parser.bump();
+			true
+		}
+		_ => {
+			parser.error(expected);
+			false
+		}
+	}
+}
+
+//
+// Interface
+//
+// Structure:
+// InterfaceDeclaration Text (InterfaceExtension => parse_path)?
+// RightCurlyBraces (parse_declaration)* LeftCurlyBraces Semicolon
+fn parse_interface(parser: &mut Parser) {
+	parser.bump();
+	if !parser.expect_name("an interface name") {
+		return parser.recover();
+	};
+
+	if parser.at(TokenType::InterfaceExtension) {
+		parser.start_node(SyntaxKind::Extends);
+		parser.bump();
+		let has_path = parse_path(parser);
+		parser.finish_node();
+
+		if !has_path {
+			return parser.recover();
+		};
+	};
+
+	if !parser.expect(TokenType::RightCurlyBraces, "`{`") {
+		return parser.recover();
+	};
+
+	while !parser.at_end() && !parser.at(TokenType::LeftCurlyBraces) {
+		if starts_member(parser) {
+			parse_declaration(parser, true);
+		} else {
+			parser.error_and_bump("a variable or a declaration");
+		};
+	}
+
+	parser.expect(TokenType::LeftCurlyBraces, "`}`");
+	parser.expect(TokenType::Semicolon, "`;`");
+}
+
+//
+// Interface variable
+//
+// Structure:
+// (RequiredModifier | OptionalModifier) Text VariableConnection (parse_type) Semicolon
+fn parse_variable(parser: &mut Parser) {
+	parser.bump();
+
+	if !parser.expect_name("a variable name")
+		|| !parser.expect(TokenType::VariableConnection, "`:`")
+		|| !parse_type(parser)
+	{
+		return parser.recover();
+	};
+
+	parser.expect(TokenType::Semicolon, "`;`");
+}
+
+//
+// Enum
+//
+// Structure:
+// EnumerateDeclaration Text RightCurlyBraces
+// 		((AttributeDeclaration | DocComment)* Text VariableConnection (Text | StringLiteral) Semicolon)*
+// LeftCurlyBraces Semicolon
+fn parse_enum(parser: &mut Parser) {
+	parser.bump();
+
+	if !parser.expect_name("an enum name") || !parser.expect(TokenType::RightCurlyBraces, "`{`") {
+		return parser.recover();
+	};
+
+	while !parser.at_end() && !parser.at(TokenType::LeftCurlyBraces) {
+		match parser.current_kind() {
+			Some(TokenType::AttributeDeclaration | TokenType::DocComment) => parse_variant(parser),
+			_ if parser.at_name() => parse_variant(parser),
+			_ => parser.error_and_bump("an enum variant"),
+		};
+	}
+
+	parser.expect(TokenType::LeftCurlyBraces, "`}`");
+	parser.expect(TokenType::Semicolon, "`;`");
+}
+
+fn parse_variant(parser: &mut Parser) {
+	let checkpoint = parser.checkpoint();
+	parse_metadata(parser);
+
+	if !parser.at_name() {
+		parser.error("an enum variant after attributes or doc comments");
+		parser.start_node_at(checkpoint, SyntaxKind::Error);
+		parser.finish_node();
+		return;
+	};
+
+	parser.start_node_at(checkpoint, SyntaxKind::Variant);
+	parser.expect_name("an enum variant");
+
+	// Keywords are values here as well (`ENUM: enum;`)
+	let has_value = parser.expect(TokenType::VariableConnection, "`:`")
+		&& if parser.at_name() {
+			parser.expect_name("a variant value")
+		} else {
+			parse_value(parser, "a variant value")
+		};
+
+	if has_value {
+		parser.expect(TokenType::Semicolon, "`;`");
+	} else {
+		parser.recover();
+	};
+	parser.finish_node();
+}
+
+//
+// Alias
+//
+// Structure:
+// AliasDeclaration Text AttributeAssignment (parse_type) Semicolon
+fn parse_alias(parser: &mut Parser) {
+	parser.bump();
+
+	if !parser.expect_name("an alias name")
+		|| !parser.expect(TokenType::AttributeAssignment, "`=`")
+		|| !parse_type(parser)
+	{
+		return parser.recover();
+	};
+
+	parser.expect(TokenType::Semicolon, "`;`");
+}
+
+//
+// Union
+//
+// Structure:
+// UnionDeclaration Text AttributeAssignment (parse_path) (UnionSeparator (parse_path))* Semicolon
+fn parse_union(parser: &mut Parser) {
+	parser.bump();
+
+	if !parser.expect_name("a union name")
+		|| !parser.expect(TokenType::AttributeAssignment, "`=`")
+		|| !parse_path(parser)
+	{
+		return parser.recover();
+	};
+
+	while parser.at(TokenType::UnionSeparator) {
+		parser.bump();
+		if !parse_path(parser) {
+			return parser.recover();
+		};
+	}
+
+	parser.expect(TokenType::Semicolon, "`;`");
+}
+
+//
+// Path
+//
+// Structure:
+// Text (PathSeparator Text)*
+//
+// Only the first segment has to be an identifier, the rest may be keywords
+fn parse_path(parser: &mut Parser) -> bool {
+	if !parser.at(TokenType::Text) {
+		parser.error("a type name");
+		return false;
+	};
+
+	parser.start_node(SyntaxKind::Path);
+	parser.bump();
+
+	let mut is_complete = true;
+	while is_complete && parser.at(TokenType::PathSeparator) {
+		parser.bump();
+		is_complete = parser.expect_name("a type name");
+	}
+
+	parser.finish_node();
+	is_complete
+}
+
+//
+// Type
+//
+// Structure:
+// StringType (RightCurlyBraces (Text | StringLiteral) LeftCurlyBraces)?
+// | IntegerType
+// | BooleanType
+// | ListType GenericStart (parse_type) GenericEnd
+// | MapType GenericStart (parse_type) GenericSeparator (parse_type) GenericEnd
+// | (parse_path)
+fn parse_type(parser: &mut Parser) -> bool {
+	match parser.current_kind() {
+		Some(TokenType::StringType) => {
+			parser.start_node(SyntaxKind::StringType);
+			parser.bump();
+
+			// A missing value is reported, the closing brace is still expected
+			let mut is_complete = true;
+			if parser.at(TokenType::RightCurlyBraces) {
+				parser.start_node(SyntaxKind::Constant);
+				parser.bump();
+				parse_value(parser, "a constant value");
+				is_complete = parser.expect(TokenType::LeftCurlyBraces, "`}`");
+				parser.finish_node();
+			};
+
+			parser.finish_node();
+			is_complete
+		}
+		Some(TokenType::IntegerType) => {
+			parser.start_node(SyntaxKind::IntegerType);
+			parser.bump();
+			parser.finish_node();
+			true
+		}
+		Some(TokenType::BooleanType) => {
+			parser.start_node(SyntaxKind::BooleanType);
+			parser.bump();
+			parser.finish_node();
+			true
+		}
+		Some(TokenType::ListType) => {
+			parser.start_node(SyntaxKind::ListType);
+			parser.bump();
+
+			let is_complete = parser.expect(TokenType::GenericStart, "`<`")
+				&& parse_type(parser)
+				&& parser.expect(TokenType::GenericEnd, "`>`");
+
+			parser.finish_node();
+			is_complete
+		}
+		Some(TokenType::MapType) => {
+			parser.start_node(SyntaxKind::MapType);
+			parser.bump();
+
+			let is_complete = parser.expect(TokenType::GenericStart, "`<`")
+				&& parse_type(parser)
+				&& parser.expect(TokenType::GenericSeparator, "`,`")
+				&& parse_type(parser)
+				&& parser.expect(TokenType::GenericEnd, "`>`");
+
+			parser.finish_node();
+			is_complete
+		}
+		Some(TokenType::Text) => {
+			parser.start_node(SyntaxKind::ReferenceType);
+			let is_complete = parse_path(parser);
+			parser.finish_node();
+			is_complete
+		}
+		_ => {
+			parser.error("a type");
+			false
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn round_trips_source() {
+		let sources = [
+			"
+			// Users
+			/// Registered user
+			#rename_all = camelCase
+			interface User extends Base {
+				required id: String; // trailing comment
+				optional tags: List<String>;
+				required kind: String { \"user  \\\"kind\\\"\" };
+				#pattern = r\"^\\d+$\"
+				required code: String;
+				required text: String { \"\"\"
+					Multi-line
+					text
+					\"\"\" };
+				required scores: Map<String, Int>;
+
+				enum Status {
+					REGISTERED: registered;
+				};
+			};
+
+			alias Id = String;
+			union Anyone = User | User.Admin;
+			",
+			// Invalid source is kept as well
+			"interface { required : ; } @ enum X { A: ; union",
+			"interface User {\n\trequired name: String { \"unterminated };\n",
+			"alias X = String { \"\"\"unterminated\n",
+		];
+
+		for source in sources {
+			assert_eq!(parse(source).root.text(), source);
+		}
+	}
+
+	#[test]
+	fn builds_tree() {
+		let parse = parse("/// Id\nalias Id = List<String>; // ids");
+
+		assert!(parse.diagnostics.is_empty());
+		assert_eq!(
+			parse.root.debug_tree(),
+			r#"SourceFile@0..38
+  Alias@0..31
+    DocComment@0..6 "/// Id"
+    Whitespace@6..7 "\n"
+    AliasDeclaration@7..12 "alias"
+    Whitespace@12..13 " "
+    Text@13..15 "Id"
+    Whitespace@15..16 " "
+    AttributeAssignment@16..17 "="
+    Whitespace@17..18 " "
+    ListType@18..30
+      ListType@18..22 "List"
+      GenericStart@22..23 "<"
+      StringType@23..29
+        StringType@23..29 "String"
+      GenericEnd@29..30 ">"
+    Semicolon@30..31 ";"
+  Whitespace@31..32 " "
+  Comment@32..38 "// ids"
+"#
+		);
+	}
+
+	#[test]
+	fn recovers_from_errors() {
+		let parse = parse(
+			"
+			interface User {
+				required id String;
+				@
+				optional name: String;
+			};
+			",
+		);
+
+		let messages: Vec<&str> = parse
+			.diagnostics
+			.iter()
+			.map(|diagnostic| diagnostic.message.as_str())
+			.collect();
+
+		// Rest of the broken variable is skipped, so it's reported once
+		assert_eq!(
+			messages,
+			vec!["expected `:`, found `String`", "unexpected character `@`",]
+		);
+
+		// Valid variable after the errors is still parsed
+		let interface = parse.root.first_node(SyntaxKind::Interface).unwrap();
+		assert_eq!(
+			interface
+				.nodes()
+				.filter(|node| node.kind == SyntaxKind::Variable)
+				.count(),
+			2
+		);
+	}
+
+	#[test]
+	fn rejects_top_level_variables() {
+		let source = "#min = 3\nrequired id: String;\n";
+		let parse = parse(source);
+
+		assert_eq!(parse.diagnostics.len(), 1);
+		assert_eq!(
+			parse.diagnostics[0].message,
+			"variables are only allowed inside interfaces"
+		);
+		assert_eq!(
+			&source[parse.diagnostics[0].labels[0].span.clone()],
+			"required"
+		);
+
+		// Attributes and the variable are kept in the error node
+		let error = parse.root.first_node(SyntaxKind::Error).unwrap();
+		assert_eq!(error.text(), "#min = 3\nrequired id: String;");
+		assert!(parse.root.first_node(SyntaxKind::Variable).is_none());
+	}
+
+	#[test]
+	fn reports_invalid_strings() {
+		let source = "#pattern = \"\\d+\"\nalias Id = String { \"open };\n";
+		let parse = parse(source);
+
+		let errors: Vec<(&str, &str)> = parse
+			.diagnostics
+			.iter()
+			.map(|diagnostic| {
+				let span = diagnostic.labels[0].span.clone();
+				(diagnostic.message.as_str(), &source[span])
+			})
+			.collect();
+
+		assert_eq!(
+			errors[..2],
+			[
+				("unknown escape sequence `\\d`", "\\d"),
+				("unterminated string", "\"open };"),
+			]
+		);
+	}
+}
@@ -0,0 +1,14 @@
+use core::ops::Range;
+
+// Dotted path to a declaration (`User.Status`), used by reference types,
+// `extends` clauses and union members.
+//
+// Segments after the first one may be keywords (`User.enum`), the first one
+// has to be an identifier, `r#String` refers to a declaration named `String`.
+// References are resolved later, in semantic analysis
+#[derive(Debug, PartialEq)]
+pub struct TypePath {
+	pub segments: Vec<String>,
+	// Byte span in the source
+	pub span: Range<usize>,
+}
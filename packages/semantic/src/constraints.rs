@@ -0,0 +1,383 @@
+use core::ops::Range;
+
+use parser::errors::Diagnostic;
+
+use crate::ir::{Attribute, Constraint, Format, Type};
+
+//
+// Constraints
+//
+// Validation rules are written as variable attributes:
+// #min_length = 3       | String
+// #max_length = 32      | String
+// #pattern = "^[a-z]+$" | String
+// #format = email       | String (email, url or uuid)
+// #min = 0              | Int
+// #max = 100            | Int
+// #min_items = 1        | List
+// #max_items = 10       | List
+//
+// Negative numbers have to be quoted for now (`#min = "-5"`). Patterns are
+// checked with the syntax of the `regex` crate, which most targets share
+// (no lookaround or backreferences). Problems are reported at the attribute.
+//
+// Length of a string is the number of it's characters (Unicode code points),
+// every generated validator counts them the same way. A constant
+// (`String { "admin" }`) is checked against it's constraints right here,
+// they aren't passed to generators, as the value can't change.
+pub fn lower_constraints(
+	attributes: &[Attribute],
+	field_type: &Type,
+	diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<Constraint> {
+	let mut constraints = Vec::new();
+	// Attribute of every constraint
+	let mut attributes_of = Vec::new();
+
+	for attribute in attributes.iter() {
+		let span = &attribute.span;
+
+		let expected_type = match attribute.name.as_str() {
+			"min_length" | "max_length" | "pattern" | "format" => "String",
+			"min" | "max" => "Int",
+			"min_items" | "max_items" => "List",
+			// Not a constraint
+			_ => continue,
+		};
+
+		let is_applicable = matches!(
+			(expected_type, field_type),
+			("String", Type::String(_)) | ("Int", Type::Integer) | ("List", Type::List(_))
+		);
+
+		if !is_applicable {
+			diagnostics.push(
+				Diagnostic::error(format!(
+					"`{}` can only be used on {} variables",
+					attribute.name, expected_type
+				))
+				.with_label(
+					span.clone(),
+					format!("`{}` isn't applicable here", attribute.name),
+				),
+			);
+			continue;
+		};
+
+		let value = match &attribute.value {
+			Some(value) => value.as_str(),
+			None => {
+				diagnostics.push(
+					Diagnostic::error(format!("`{}` requires a value", attribute.name)).with_label(
+						span.clone(),
+						format!("`#{} = ...` expected", attribute.name),
+					),
+				);
+				continue;
+			}
+		};
+
+		let constraint = match attribute.name.as_str() {
+			"min_length" => parse_count(value).map(Constraint::MinLength),
+			"max_length" => parse_count(value).map(Constraint::MaxLength),
+			"min_items" => parse_count(value).map(Constraint::MinItems),
+			"max_items" => parse_count(value).map(Constraint::MaxItems),
+			"min" => value.parse::<i64>().ok().map(Constraint::Minimum),
+			"max" => value.parse::<i64>().ok().map(Constraint::Maximum),
+			"pattern" => match check_pattern(value) {
+				Ok(()) => Option::Some(Constraint::Pattern(value.to_string())),
+				Err(error) => {
+					diagnostics.push(
+						Diagnostic::error(format!(
+							"invalid `pattern` value `{}`: {}",
+							value, error
+						))
+						.with_label(span.clone(), "invalid regular expression"),
+					);
+					continue;
+				}
+			},
+			"format" => match value {
+				"email" => Option::Some(Constraint::Format(Format::Email)),
+				"url" => Option::Some(Constraint::Format(Format::Url)),
+				"uuid" => Option::Some(Constraint::Format(Format::Uuid)),
+				_ => Option::None,
+			},
+			_ => Option::None,
+		};
+
+		match constraint {
+			Some(constraint) => {
+				constraints.push(constraint);
+				attributes_of.push(attribute);
+			}
+			None => {
+				let expected = match attribute.name.as_str() {
+					"min" | "max" => "an integer",
+					"format" => "`email`, `url` or `uuid`",
+					_ => "a non-negative integer",
+				};
+
+				diagnostics.push(
+					Diagnostic::error(format!(
+						"invalid `{}` value `{}`, expected {}",
+						attribute.name, value, expected
+					))
+					.with_label(span.clone(), "invalid constraint"),
+				);
+			}
+		};
+	}
+
+	let spans: Vec<Range<usize>> = attributes_of
+		.iter()
+		.map(|attribute| attribute.span.clone())
+		.collect();
+	check_bounds(&constraints, &spans, diagnostics);
+
+	if let Type::String(Some(value)) = field_type {
+		for (constraint, attribute) in constraints.iter().zip(attributes_of) {
+			if !is_satisfied(constraint, value) {
+				diagnostics.push(
+					Diagnostic::error(format!(
+						"constant `{}` doesn't satisfy `#{} = {}`",
+						value,
+						attribute.name,
+						attribute.value.as_deref().unwrap_or_default()
+					))
+					.with_label(attribute.span.clone(), "never satisfied by the constant"),
+				);
+			};
+		}
+
+		return Vec::new();
+	};
+
+	constraints
+}
+
+// Whether a constant string satisfies a string constraint
+fn is_satisfied(constraint: &Constraint, value: &str) -> bool {
+	let pattern = match constraint {
+		Constraint::MinLength(length) => return value.chars().count() >= *length,
+		Constraint::MaxLength(length) => return value.chars().count() <= *length,
+		Constraint::Pattern(pattern) => pattern.as_str(),
+		Constraint::Format(format) => format.pattern(),
+		_ => return true,
+	};
+
+	// Patterns are checked already, only size limits can fail here
+	regex::Regex::new(pattern)
+		.map(|regex| regex.is_match(value))
+		.unwrap_or(true)
+}
+
+fn parse_count(value: &str) -> Option<usize> {
+	value.parse::<usize>().ok()
+}
+
+fn check_pattern(pattern: &str) -> Result<(), String> {
+	match regex_syntax::Parser::new().parse(pattern) {
+		Ok(_) => Ok(()),
+		Err(regex_syntax::Error::Parse(error)) => Err(error.kind().to_string()),
+		Err(regex_syntax::Error::Translate(error)) => Err(error.kind().to_string()),
+		Err(error) => Err(error.to_string()),
+	}
+}
+
+// Lower bounds can't be greater than upper bounds. The lower bound is
+// labelled, the upper one is pointed at as well
+fn check_bounds(
+	constraints: &[Constraint],
+	spans: &[Range<usize>],
+	diagnostics: &mut Vec<Diagnostic>,
+) {
+	let mut min_length = Option::None;
+	let mut max_length = Option::None;
+	let mut minimum = Option::None;
+	let mut maximum = Option::None;
+	let mut min_items = Option::None;
+	let mut max_items = Option::None;
+
+	for (constraint, span) in constraints.iter().zip(spans) {
+		match constraint {
+			Constraint::MinLength(value) => min_length = Option::Some((*value as i64, span)),
+			Constraint::MaxLength(value) => max_length = Option::Some((*value as i64, span)),
+			Constraint::Minimum(value) => minimum = Option::Some((*value, span)),
+			Constraint::Maximum(value) => maximum = Option::Some((*value, span)),
+			Constraint::MinItems(value) => min_items = Option::Some((*value as i64, span)),
+			Constraint::MaxItems(value) => max_items = Option::Some((*value as i64, span)),
+			_ => {}
+		};
+	}
+
+	for (lower, upper, names) in [
+		(min_length, max_length, ("min_length", "max_length")),
+		(minimum, maximum, ("min", "max")),
+		(min_items, max_items, ("min_items", "max_items")),
+	] {
+		if let (Some((lower, lower_span)), Some((upper, upper_span))) = (lower, upper) {
+			if lower > upper {
+				diagnostics.push(
+					Diagnostic::error(format!(
+						"`{}` ({}) is greater than `{}` ({})",
+						names.0, lower, names.1, upper
+					))
+					.with_label(lower_span.clone(), "no value can satisfy these constraints")
+					.with_label(upper_span.clone(), format!("`{}` is set here", names.1)),
+				);
+			};
+		};
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		analyze,
+		ir::{Constraint, DeclarationKind, Format},
+	};
+
+	#[test]
+	fn lowers_constraints() {
+		let schema = analyze(
+			"
+			interface User {
+				#min_length = 3
+				#max_length = 32
+				#pattern = \"^[a-z_]+$\"
+				required username: String;
+
+				#format = email
+				optional email: String;
+
+				#min = \"-10\"
+				#max = 10
+				required offset: Int;
+
+				#min_items = 1
+				required tags: List<String>;
+			};
+			",
+		)
+		.unwrap();
+
+		let constraints: Vec<Vec<Constraint>> = match &schema.find("User").unwrap().kind {
+			DeclarationKind::Interface(interface) => interface
+				.fields
+				.iter()
+				.map(|field| field.constraints.clone())
+				.collect(),
+			_ => panic!("User should be an interface"),
+		};
+
+		assert_eq!(
+			constraints,
+			vec![
+				vec![
+					Constraint::MinLength(3),
+					Constraint::MaxLength(32),
+					Constraint::Pattern("^[a-z_]+$".to_string()),
+				],
+				vec![Constraint::Format(Format::Email)],
+				vec![Constraint::Minimum(-10), Constraint::Maximum(10)],
+				vec![Constraint::MinItems(1)],
+			]
+		);
+	}
+
+	#[test]
+	fn reports_misplaced_and_invalid_constraints() {
+		let diagnostics = analyze(
+			"
+			interface User {
+				#min_items = 1
+				required username: String;
+
+				#format = phone
+				required email: String;
+
+				#min = 10
+				#max = 1
+				required age: Int;
+			};
+			",
+		)
+		.unwrap_err();
+
+		let messages: Vec<&str> = diagnostics
+			.iter()
+			.map(|diagnostic| diagnostic.message.as_str())
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec![
+				"`min_items` can only be used on List variables",
+				"invalid `format` value `phone`, expected `email`, `url` or `uuid`",
+				"`min` (10) is greater than `max` (1)",
+			]
+		);
+	}
+
+	#[test]
+	fn checks_patterns_at_attributes() {
+		let source = "interface User {\n\t#pattern = \"^[a-z+$\"\n\t#max_length = \"x\"\n\trequired name: String;\n};\n";
+		let diagnostics = analyze(source).unwrap_err();
+
+		let errors: Vec<(&str, &str)> = diagnostics
+			.iter()
+			.map(|diagnostic| {
+				let span = diagnostic.labels[0].span.clone();
+				(diagnostic.message.as_str(), &source[span])
+			})
+			.collect();
+
+		assert_eq!(
+			errors,
+			vec![
+				(
+					"invalid `pattern` value `^[a-z+$`: unclosed character class",
+					"#pattern = \"^[a-z+$\""
+				),
+				(
+					"invalid `max_length` value `x`, expected a non-negative integer",
+					"#max_length = \"x\""
+				),
+			]
+		);
+	}
+
+	#[test]
+	fn checks_constants_against_their_constraints() {
+		let diagnostics = analyze(
+			"
+			interface User {
+				#min_length = 5
+				#max_length = 5
+				#pattern = \"^[a-z]+$\"
+				required kind: String { \"ñandú\" };
+
+				#min_length = 6
+				#format = email
+				required contact: String { \"admin\" };
+			};
+			",
+		)
+		.unwrap_err();
+
+		let messages: Vec<&str> = diagnostics
+			.iter()
+			.map(|diagnostic| diagnostic.message.as_str())
+			.collect();
+
+		assert_eq!(
+			messages,
+			vec![
+				"constant `ñandú` doesn't satisfy `#pattern = ^[a-z]+$`",
+				"constant `admin` doesn't satisfy `#min_length = 6`",
+				"constant `admin` doesn't satisfy `#format = email`",
+			]
+		);
+	}
+}
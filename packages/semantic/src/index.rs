@@ -0,0 +1,210 @@
+use core::ops::Range;
+
+use lexer::tokens::TokenType;
+use parser::syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+use crate::ir::{DeclarationId, DeclarationKind, Schema};
+
+//
+// References index
+//
+// Every name in a file mapped to the schema element it names: definitions
+// of declarations, variables and enum variants, and every segment of type
+// paths (`User` and `Status` in `User.Status`). Paths are resolved the
+// same way lowering resolves them, so the index agrees with the schema.
+//
+// Type paths are the only references: files don't import each other and
+// no attribute takes a declaration or variable name, so attribute values
+// are plain text and aren't indexed.
+//
+
+// Named element of a schema
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Symbol {
+	Declaration(DeclarationId),
+	// Interface and variable name
+	Field(DeclarationId, String),
+	// Enum and variant name
+	Variant(DeclarationId, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+	pub symbol: Symbol,
+	// Byte span of the name
+	pub span: Range<usize>,
+	pub is_definition: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Index {
+	// In source order
+	occurrences: Vec<Occurrence>,
+}
+
+impl Index {
+	pub fn new(syntax: &SyntaxNode, schema: &Schema) -> Index {
+		let mut index = Index::default();
+		index.walk(syntax, schema, Option::None);
+		index
+	}
+
+	// Occurrence of a name at the offset, name end is included
+	pub fn at(&self, offset: usize) -> Option<&Occurrence> {
+		self.occurrences
+			.iter()
+			.find(|occurrence| occurrence.span.start <= offset && offset <= occurrence.span.end)
+	}
+
+	pub fn occurrences(&self) -> &[Occurrence] {
+		&self.occurrences
+	}
+
+	// Definition and references of the symbol
+	pub fn references(&self, symbol: &Symbol) -> impl Iterator<Item = &Occurrence> {
+		let symbol = symbol.clone();
+
+		self.occurrences
+			.iter()
+			.filter(move |occurrence| occurrence.symbol == symbol)
+	}
+
+	pub fn definition(&self, symbol: &Symbol) -> Option<&Occurrence> {
+		self.references(symbol)
+			.find(|occurrence| occurrence.is_definition)
+	}
+
+	// `scope` is the declaration enclosing the node
+	fn walk(&mut self, node: &SyntaxNode, schema: &Schema, scope: Option<DeclarationId>) {
+		for child in node.nodes() {
+			match child.kind {
+				SyntaxKind::Interface
+				| SyntaxKind::Enum
+				| SyntaxKind::Alias
+				| SyntaxKind::Union => {
+					let Some(name) = name_token(child) else {
+						continue;
+					};
+
+					let qualified_name = match scope {
+						Some(scope) => {
+							format!("{}.{}", schema.get(scope).qualified_name(), name.name())
+						}
+						None => name.name().to_string(),
+					};
+
+					// Second declaration with the same name isn't a part of the schema
+					let Some(declaration) = schema.find(&qualified_name) else {
+						continue;
+					};
+
+					if declaration.name_span == name.span {
+						self.define(Symbol::Declaration(declaration.id), name);
+						self.walk(child, schema, Option::Some(declaration.id));
+					};
+				}
+				SyntaxKind::Variable | SyntaxKind::Variant => {
+					if let (Some(scope), Some(name)) = (scope, name_token(child)) {
+						let symbol = if child.kind == SyntaxKind::Variable {
+							Symbol::Field(scope, name.name().to_string())
+						} else {
+							Symbol::Variant(scope, name.name().to_string())
+						};
+
+						self.define(symbol, name);
+					};
+
+					self.walk(child, schema, scope);
+				}
+				// Parent is looked up outside of the extending interface
+				SyntaxKind::Extends => {
+					let parent = scope.and_then(|scope| schema.get(scope).parent);
+					self.walk(child, schema, parent);
+				}
+				SyntaxKind::Path => self.path(child, schema, scope),
+				_ => self.walk(child, schema, scope),
+			};
+		}
+	}
+
+	// Every segment of a path refers to an ancestor of the resolved declaration
+	fn path(&mut self, node: &SyntaxNode, schema: &Schema, scope: Option<DeclarationId>) {
+		let segments: Vec<&SyntaxToken> = node
+			.tokens()
+			.filter(|token| token.kind == TokenType::Text)
+			.collect();
+		let path: Vec<String> = segments
+			.iter()
+			.map(|segment| segment.name().to_string())
+			.collect();
+
+		let mut declaration = schema.lookup(scope, &path);
+		let mut occurrences = Vec::new();
+
+		for segment in segments.iter().rev() {
+			let Some(resolved) = declaration else {
+				break;
+			};
+
+			occurrences.push(Occurrence {
+				symbol: Symbol::Declaration(resolved.id),
+				span: segment.span.clone(),
+				is_definition: false,
+			});
+
+			declaration = resolved.parent.map(|parent| schema.get(parent));
+		}
+
+		// Segments were visited from the last one
+		self.occurrences.extend(occurrences.into_iter().rev());
+	}
+
+	fn define(&mut self, symbol: Symbol, name: &SyntaxToken) {
+		self.occurrences.push(Occurrence {
+			symbol,
+			span: name.span.clone(),
+			is_definition: true,
+		});
+	}
+}
+
+fn name_token(node: &SyntaxNode) -> Option<&SyntaxToken> {
+	node.first_token(TokenType::Text)
+}
+
+//
+// Qualified names of symbols
+//
+// `User.Status` for a declaration, `User.name` for a variable and
+// `User.Status.ACTIVE` for an enum variant.
+pub fn symbol_name(schema: &Schema, symbol: &Symbol) -> String {
+	match symbol {
+		Symbol::Declaration(id) => schema.get(*id).qualified_name(),
+		Symbol::Field(id, name) | Symbol::Variant(id, name) => {
+			format!("{}.{}", schema.get(*id).qualified_name(), name)
+		}
+	}
+}
+
+// Declarations win over variables with the same qualified name
+pub fn find_symbol(schema: &Schema, qualified_name: &str) -> Option<Symbol> {
+	if let Some(declaration) = schema.find(qualified_name) {
+		return Option::Some(Symbol::Declaration(declaration.id));
+	};
+
+	let (parent, name) = qualified_name.rsplit_once('.')?;
+	let parent = schema.find(parent)?;
+
+	match &parent.kind {
+		DeclarationKind::Interface(interface) => interface
+			.own_fields()
+			.any(|field| field.name == name)
+			.then(|| Symbol::Field(parent.id, name.to_string())),
+		DeclarationKind::Enum(enumerate) => enumerate
+			.variants
+			.iter()
+			.any(|variant| variant.name == name)
+			.then(|| Symbol::Variant(parent.id, name.to_string())),
+		_ => Option::None,
+	}
+}
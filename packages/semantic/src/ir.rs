@@ -0,0 +1,268 @@
+use core::ops::Range;
+use std::collections::HashMap;
+
+//
+// Schema IR
+//
+// Resolved and immutable representation of a .typm file. It's produced by
+// `lower` and it's the only thing generators work with: every type reference
+// points to a declaration, inherited variables are copied into interfaces
+// and metadata is attached to the element it was written above.
+//
+
+// Index of a declaration in `Schema::declarations`. Declarations are numbered
+// in source order (parents before their children), so the same file always
+// produces the same ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DeclarationId(pub usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+	pub name: String,
+	pub value: Option<String>,
+	// Byte span in the source
+	pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Type {
+	// Constant string if value is present
+	String(Option<String>),
+	Boolean,
+	Integer,
+	List(Box<Type>),
+	// Key and value types, key is always String or Integer
+	Map(Box<Type>, Box<Type>),
+	Reference(DeclarationId),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+	Email,
+	Url,
+	Uuid,
+}
+
+impl Format {
+	// Regex checking the format, shared by every generated validator
+	pub fn pattern(&self) -> &'static str {
+		match self {
+			Format::Email => r"^[^\s@]+@[^\s@]+\.[^\s@]+$",
+			Format::Url => r"^[a-zA-Z][a-zA-Z0-9+.-]*://[^\s/?#]+[^\s]*$",
+			Format::Uuid => {
+				r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$"
+			}
+		}
+	}
+}
+
+// Validation rule of a variable, written as an attribute (`#min_length = 3`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constraint {
+	MinLength(usize),
+	MaxLength(usize),
+	Pattern(String),
+	Format(Format),
+	Minimum(i64),
+	Maximum(i64),
+	MinItems(usize),
+	MaxItems(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+	pub name: String,
+	pub name_span: Range<usize>,
+	pub field_type: Type,
+	pub is_required: bool,
+	pub constraints: Vec<Constraint>,
+	pub docs: Option<String>,
+	pub attributes: Vec<Attribute>,
+	// Interface this variable was declared in, if it was inherited
+	pub inherited_from: Option<DeclarationId>,
+	pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+	pub name: String,
+	pub name_span: Range<usize>,
+	pub value: Option<String>,
+	pub docs: Option<String>,
+	pub attributes: Vec<Attribute>,
+	pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+	pub extends: Option<DeclarationId>,
+	// Inherited variables first, then own ones
+	pub fields: Vec<Field>,
+}
+
+impl Interface {
+	pub fn own_fields(&self) -> impl Iterator<Item = &Field> {
+		self.fields
+			.iter()
+			.filter(|field| field.inherited_from.is_none())
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enum {
+	pub variants: Vec<Variant>,
+}
+
+// Another name for a type (`alias UserId = String;`). Aliases can't refer
+// to themselves, so following them always ends at a non-alias type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alias {
+	pub target: Type,
+}
+
+// One of several interfaces (`union SearchResult = User | Post;`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Union {
+	pub members: Vec<DeclarationId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeclarationKind {
+	Interface(Interface),
+	Enum(Enum),
+	Alias(Alias),
+	Union(Union),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Declaration {
+	pub id: DeclarationId,
+	pub name: String,
+	pub name_span: Range<usize>,
+	// Fully-qualified path, e.g. ["User", "Status"]
+	pub path: Vec<String>,
+	pub parent: Option<DeclarationId>,
+	pub children: Vec<DeclarationId>,
+	pub docs: Option<String>,
+	pub attributes: Vec<Attribute>,
+	pub kind: DeclarationKind,
+	pub span: Range<usize>,
+}
+
+impl Declaration {
+	pub fn qualified_name(&self) -> String {
+		self.path.join(".")
+	}
+
+	pub fn attribute(&self, name: &str) -> Option<&Attribute> {
+		self.attributes
+			.iter()
+			.find(|attribute| attribute.name == name)
+	}
+}
+
+impl Field {
+	pub fn attribute(&self, name: &str) -> Option<&Attribute> {
+		self.attributes
+			.iter()
+			.find(|attribute| attribute.name == name)
+	}
+}
+
+impl Variant {
+	pub fn attribute(&self, name: &str) -> Option<&Attribute> {
+		self.attributes
+			.iter()
+			.find(|attribute| attribute.name == name)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema {
+	declarations: Vec<Declaration>,
+	roots: Vec<DeclarationId>,
+	by_name: HashMap<String, DeclarationId>,
+}
+
+impl Schema {
+	pub(crate) fn new(declarations: Vec<Declaration>) -> Schema {
+		let roots = declarations
+			.iter()
+			.filter(|declaration| declaration.parent.is_none())
+			.map(|declaration| declaration.id)
+			.collect();
+
+		// First declaration wins if the same name was declared twice
+		let mut by_name = HashMap::new();
+		for declaration in declarations.iter() {
+			by_name
+				.entry(declaration.qualified_name())
+				.or_insert(declaration.id);
+		}
+
+		Self {
+			declarations,
+			roots,
+			by_name,
+		}
+	}
+
+	// Every declaration, ordered by id
+	pub fn declarations(&self) -> &[Declaration] {
+		&self.declarations
+	}
+
+	// Top-level declarations in source order
+	pub fn roots(&self) -> impl Iterator<Item = &Declaration> {
+		self.roots.iter().map(|id| self.get(*id))
+	}
+
+	pub fn children<'a>(
+		&'a self,
+		declaration: &'a Declaration,
+	) -> impl Iterator<Item = &'a Declaration> {
+		declaration.children.iter().map(|id| self.get(*id))
+	}
+
+	pub fn get(&self, id: DeclarationId) -> &Declaration {
+		&self.declarations[id.0]
+	}
+
+	// Type behind any number of aliases, or the type itself
+	pub fn resolve_type<'a>(&'a self, field_type: &'a Type) -> &'a Type {
+		let mut resolved = field_type;
+
+		while let Type::Reference(id) = resolved {
+			match &self.get(*id).kind {
+				DeclarationKind::Alias(alias) => resolved = &alias.target,
+				_ => break,
+			};
+		}
+
+		resolved
+	}
+
+	// Looks declaration up by it's fully-qualified name (`User.Status`)
+	pub fn find(&self, qualified_name: &str) -> Option<&Declaration> {
+		self.by_name.get(qualified_name).map(|id| self.get(*id))
+	}
+
+	// Looks a reference up the same way lowering does: inside the scope
+	// declaration first, then in it's parents, then at the top level
+	pub fn lookup(&self, scope: Option<DeclarationId>, path: &[String]) -> Option<&Declaration> {
+		let name = path.join(".");
+		let mut scope = scope;
+
+		while let Some(id) = scope {
+			let scoped_name = format!("{}.{}", self.get(id).qualified_name(), name);
+
+			if let Some(declaration) = self.find(&scoped_name) {
+				return Option::Some(declaration);
+			};
+
+			scope = self.get(id).parent;
+		}
+
+		self.find(&name)
+	}
+}
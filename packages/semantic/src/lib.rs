@@ -0,0 +1,17 @@
+use ir::Schema;
+use parser::{errors::Diagnostic, get_ast_tree, syntax::parse};
+
+pub mod constraints;
+pub mod index;
+pub mod ir;
+pub mod lowering;
+pub mod validation;
+pub mod workspace;
+
+pub use lowering::lower;
+pub use validation::{validate, ReservedWords};
+
+// Parses and lowers source into Schema IR
+pub fn analyze(source: &str) -> Result<Schema, Vec<Diagnostic>> {
+	lower(&get_ast_tree(&parse(source)))
+}
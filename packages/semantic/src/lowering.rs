@@ -0,0 +1,1039 @@
+use std::collections::HashMap;
+
+use parser::{
+	entities::{interface::variables::InterfaceVariable, metadata::Metadata},
+	errors::Diagnostic,
+	types::{reference::TypePath, VariableType},
+	Entity, Node, Tree,
+};
+
+use crate::{
+	constraints::lower_constraints,
+	ir::{
+		Alias, Attribute, Declaration, DeclarationId, DeclarationKind, Enum, Field, Interface,
+		Schema, Type, Union, Variant,
+	},
+};
+
+// Declaration found while walking the tree, before anything is resolved
+struct Collected<'a> {
+	path: Vec<String>,
+	parent: Option<DeclarationId>,
+	children: Vec<DeclarationId>,
+	node: &'a Node,
+}
+
+struct Lowering<'a> {
+	declarations: Vec<Collected<'a>>,
+	by_name: HashMap<String, DeclarationId>,
+	// Lowered alias targets, by declaration id
+	aliases: Vec<Option<Type>>,
+	diagnostics: Vec<Diagnostic>,
+}
+
+//
+// Lowering
+//
+// Turns parsed Tree into Schema IR:
+// 1. Every declaration gets an id and a fully-qualified path
+// 2. Aliases are resolved and checked for cycles
+// 3. Type references, `extends` clauses and union members are resolved,
+//    starting from the innermost interface and going up to the top level
+// 4. Parent interface variables are copied into their children
+// 5. Union members are checked to be listed subtypes first
+pub fn lower(tree: &Tree) -> Result<Schema, Vec<Diagnostic>> {
+	let mut lowering = Lowering {
+		declarations: Vec::new(),
+		by_name: HashMap::new(),
+		aliases: Vec::new(),
+		// Parser problems (invalid string escapes) go first
+		diagnostics: tree.diagnostics.clone(),
+	};
+
+	for node in tree.nodes.iter() {
+		lowering.collect(node, Option::None);
+	}
+
+	// Aliases go first, so that constraints of variables can see through them
+	lowering.aliases = vec![Option::None; lowering.declarations.len()];
+	for index in 0..lowering.declarations.len() {
+		if let Entity::Alias(alias) = &lowering.declarations[index].node.entity {
+			lowering.aliases[index] = lowering.lower_type(DeclarationId(index), &alias.alias_type);
+		};
+	}
+	lowering.check_alias_cycles();
+	for index in 0..lowering.declarations.len() {
+		if let (Entity::Alias(alias), Some(target)) = (
+			&lowering.declarations[index].node.entity,
+			lowering.aliases[index].clone(),
+		) {
+			lowering.check_map_keys(&target, &alias.type_span);
+		};
+	}
+
+	// Resolving parent interfaces, variable types and union members
+	let mut parents = Vec::<Option<DeclarationId>>::new();
+	let mut own_fields = Vec::<Vec<Field>>::new();
+	let mut union_members = Vec::<Vec<DeclarationId>>::new();
+
+	for index in 0..lowering.declarations.len() {
+		let id = DeclarationId(index);
+
+		match &lowering.declarations[index].node.entity {
+			Entity::Interface(interface) => {
+				let parent = match &interface.extends {
+					Some(path) => lowering.resolve_parent(id, path),
+					None => Option::None,
+				};
+
+				let fields = interface
+					.variables
+					.iter()
+					.filter_map(|variable| lowering.lower_variable(id, variable))
+					.collect();
+
+				parents.push(parent);
+				own_fields.push(fields);
+				union_members.push(Vec::new());
+			}
+			Entity::Union(union) => {
+				let members = lowering.resolve_members(id, union);
+
+				parents.push(Option::None);
+				own_fields.push(Vec::new());
+				union_members.push(members);
+			}
+			Entity::Enum(_) | Entity::Alias(_) => {
+				parents.push(Option::None);
+				own_fields.push(Vec::new());
+				union_members.push(Vec::new());
+			}
+		};
+	}
+
+	// Flattening inheritance
+	let mut flattened: Vec<Option<Vec<Field>>> = vec![Option::None; lowering.declarations.len()];
+	for index in 0..lowering.declarations.len() {
+		let mut chain = Vec::new();
+		lowering.flatten(
+			DeclarationId(index),
+			&parents,
+			&own_fields,
+			&mut flattened,
+			&mut chain,
+		);
+	}
+
+	if !lowering.diagnostics.is_empty() {
+		return Err(lowering.diagnostics);
+	};
+
+	// Inheritance has no cycles at this point
+	for (index, members) in union_members.iter().enumerate() {
+		lowering.check_member_order(members, index, &parents);
+	}
+
+	if !lowering.diagnostics.is_empty() {
+		return Err(lowering.diagnostics);
+	};
+
+	// Building our declarations
+	let mut aliases = std::mem::take(&mut lowering.aliases);
+	let declarations = lowering
+		.declarations
+		.into_iter()
+		.zip(flattened)
+		.enumerate()
+		.map(|(index, (collected, fields))| {
+			let (name, name_span, metadata, span, kind) = match &collected.node.entity {
+				Entity::Interface(interface) => (
+					interface.name.clone(),
+					interface.name_span.clone(),
+					&interface.metadata,
+					interface.span.clone(),
+					DeclarationKind::Interface(Interface {
+						extends: parents[index],
+						fields: fields.unwrap_or_default(),
+					}),
+				),
+				Entity::Enum(enumerate) => (
+					enumerate.name.clone(),
+					enumerate.name_span.clone(),
+					&enumerate.metadata,
+					enumerate.span.clone(),
+					DeclarationKind::Enum(Enum {
+						variants: enumerate
+							.variants
+							.iter()
+							.map(|variant| Variant {
+								name: variant.name.clone(),
+								name_span: variant.name_span.clone(),
+								value: variant.value.clone(),
+								docs: lower_docs(&variant.metadata),
+								attributes: lower_attributes(&variant.metadata),
+								span: variant.span.clone(),
+							})
+							.collect(),
+					}),
+				),
+				Entity::Alias(alias) => (
+					alias.name.clone(),
+					alias.name_span.clone(),
+					&alias.metadata,
+					alias.span.clone(),
+					DeclarationKind::Alias(Alias {
+						// Unresolved aliases were reported above
+						target: aliases[index].take().unwrap(),
+					}),
+				),
+				Entity::Union(union) => (
+					union.name.clone(),
+					union.name_span.clone(),
+					&union.metadata,
+					union.span.clone(),
+					DeclarationKind::Union(Union {
+						members: std::mem::take(&mut union_members[index]),
+					}),
+				),
+			};
+
+			Declaration {
+				id: DeclarationId(index),
+				name,
+				name_span,
+				path: collected.path,
+				parent: collected.parent,
+				children: collected.children,
+				docs: lower_docs(metadata),
+				attributes: lower_attributes(metadata),
+				kind,
+				span,
+			}
+		})
+		.collect();
+
+	Ok(Schema::new(declarations))
+}
+
+impl<'a> Lowering<'a> {
+	fn collect(&mut self, node: &'a Node, parent: Option<DeclarationId>) -> DeclarationId {
+		let id = DeclarationId(self.declarations.len());
+
+		let name = match &node.entity {
+			Entity::Interface(interface) => interface.name.clone(),
+			Entity::Enum(enumerate) => enumerate.name.clone(),
+			Entity::Alias(alias) => alias.name.clone(),
+			Entity::Union(union) => union.name.clone(),
+		};
+
+		let mut path = match parent {
+			Some(parent) => self.declarations[parent.0].path.clone(),
+			None => Vec::new(),
+		};
+		path.push(name);
+
+		// Duplicates are reported by validation, first declaration wins here
+		self.by_name.entry(path.join(".")).or_insert(id);
+
+		self.declarations.push(Collected {
+			path,
+			parent,
+			children: Vec::new(),
+			node,
+		});
+
+		for child in node.nodes.iter() {
+			let child_id = self.collect(child, Option::Some(id));
+			self.declarations[id.0].children.push(child_id);
+		}
+
+		id
+	}
+
+	// Resolves dotted path, starting at the scope of `from` declaration
+	fn resolve(&self, from: DeclarationId, path: &[String]) -> Option<DeclarationId> {
+		let name = path.join(".");
+		let mut scope = Option::Some(from);
+
+		while let Some(id) = scope {
+			let scoped_name = format!("{}.{}", self.declarations[id.0].path.join("."), name);
+
+			if let Some(id) = self.by_name.get(&scoped_name) {
+				return Option::Some(*id);
+			};
+
+			scope = self.declarations[id.0].parent;
+		}
+
+		self.by_name.get(&name).copied()
+	}
+
+	fn resolve_parent(&mut self, id: DeclarationId, path: &TypePath) -> Option<DeclarationId> {
+		let name = path.segments.join(".");
+		let span = path.span.clone();
+
+		// Interface can't extend itself or something declared in it
+		let scope = self.declarations[id.0].parent;
+		let resolved = match scope {
+			Some(scope) => self.resolve(scope, &path.segments),
+			None => self.by_name.get(&name).copied(),
+		};
+
+		match resolved {
+			Some(parent) => match self.declarations[parent.0].node.entity {
+				Entity::Interface(_) => Option::Some(parent),
+				_ => {
+					let kind = entity_kind(&self.declarations[parent.0].node.entity);
+
+					self.diagnostics.push(
+						Diagnostic::error(format!("`{}` is {} and can't be extended", name, kind))
+							.with_label(span, "only interfaces can be extended"),
+					);
+
+					Option::None
+				}
+			},
+			None => {
+				self.diagnostics.push(
+					Diagnostic::error(format!("unresolved interface `{}`", name))
+						.with_label(span, format!("no interface named `{}`", name)),
+				);
+
+				Option::None
+			}
+		}
+	}
+
+	fn lower_variable(
+		&mut self,
+		scope: DeclarationId,
+		variable: &InterfaceVariable,
+	) -> Option<Field> {
+		let field_type = self.lower_type(scope, &variable.variable_type)?;
+		self.check_map_keys(&field_type, &variable.type_span);
+		let attributes = lower_attributes(&variable.metadata);
+		let constraints = lower_constraints(
+			&attributes,
+			&self.resolve_alias(&field_type),
+			&mut self.diagnostics,
+		);
+
+		Option::Some(Field {
+			name: variable.name.clone(),
+			name_span: variable.name_span.clone(),
+			field_type,
+			is_required: variable.is_required,
+			constraints,
+			docs: lower_docs(&variable.metadata),
+			attributes,
+			inherited_from: Option::None,
+			span: variable.span.clone(),
+		})
+	}
+
+	fn lower_type(&mut self, scope: DeclarationId, variable_type: &VariableType) -> Option<Type> {
+		let lowered = match variable_type {
+			VariableType::String(constant) => {
+				Type::String(constant.as_ref().map(|constant| constant.value.clone()))
+			}
+			VariableType::Boolean => Type::Boolean,
+			VariableType::Integer => Type::Integer,
+			VariableType::List(item_type) => {
+				Type::List(Box::new(self.lower_type(scope, item_type)?))
+			}
+			VariableType::Map(key_type, value_type) => {
+				// Both types are lowered to report everything at once
+				let key_type = self.lower_type(scope, key_type);
+				let value_type = self.lower_type(scope, value_type);
+
+				Type::Map(Box::new(key_type?), Box::new(value_type?))
+			}
+			VariableType::Reference(path) => match self.resolve(scope, &path.segments) {
+				Some(id) => Type::Reference(id),
+				None => {
+					let name = path.segments.join(".");
+
+					self.diagnostics.push(
+						Diagnostic::error(format!("unresolved type `{}`", name)).with_label(
+							path.span.clone(),
+							format!("no declaration named `{}`", name),
+						),
+					);
+
+					return Option::None;
+				}
+			},
+		};
+
+		Option::Some(lowered)
+	}
+
+	// Union members have to be interfaces, each listed once
+	fn resolve_members(
+		&mut self,
+		id: DeclarationId,
+		union: &parser::entities::union::Union,
+	) -> Vec<DeclarationId> {
+		let mut members = Vec::new();
+
+		for path in union.members.iter() {
+			let name = path.segments.join(".");
+
+			let member = match self.resolve(id, &path.segments) {
+				Some(member) => member,
+				None => {
+					self.diagnostics.push(
+						Diagnostic::error(format!("unresolved interface `{}`", name)).with_label(
+							path.span.clone(),
+							format!("no interface named `{}`", name),
+						),
+					);
+					continue;
+				}
+			};
+
+			if !matches!(
+				self.declarations[member.0].node.entity,
+				Entity::Interface(_)
+			) {
+				let kind = entity_kind(&self.declarations[member.0].node.entity);
+
+				self.diagnostics.push(
+					Diagnostic::error(format!(
+						"`{}` is {} and can't be a union member",
+						name, kind
+					))
+					.with_label(path.span.clone(), "only interfaces can be union members"),
+				);
+				continue;
+			};
+
+			if members.contains(&member) {
+				self.diagnostics.push(
+					Diagnostic::error(format!(
+						"`{}` is listed more than once in union `{}`",
+						name, union.name
+					))
+					.with_label(path.span.clone(), "duplicate union member"),
+				);
+				continue;
+			};
+
+			members.push(member);
+		}
+
+		members
+	}
+
+	// Every target picks the first union member matching the data, so
+	// a member listed after one of it's parents would never be picked
+	fn check_member_order(
+		&mut self,
+		members: &[DeclarationId],
+		index: usize,
+		parents: &[Option<DeclarationId>],
+	) {
+		let Entity::Union(union) = &self.declarations[index].node.entity else {
+			return;
+		};
+
+		for (position, member) in members.iter().enumerate() {
+			let mut ancestor = parents[member.0];
+			while let Some(parent) = ancestor {
+				if members[..position].contains(&parent) {
+					self.diagnostics.push(
+						Diagnostic::error(format!(
+							"`{}` extends `{}`, so it has to be listed before it in union `{}`",
+							union.members[position].segments.join("."),
+							self.declarations[parent.0].path.join("."),
+							union.name
+						))
+						.with_label(
+							union.members[position].span.clone(),
+							"listed after it's parent, it would never be picked",
+						),
+					);
+					break;
+				};
+				ancestor = parents[parent.0];
+			}
+		}
+	}
+
+	// Type behind aliases. Cyclic and unresolved aliases have no target,
+	// so this always ends
+	fn resolve_alias(&self, field_type: &Type) -> Type {
+		match field_type {
+			Type::Reference(id) => match &self.aliases[id.0] {
+				Some(target) => self.resolve_alias(target),
+				None => field_type.clone(),
+			},
+			_ => field_type.clone(),
+		}
+	}
+
+	// Maps can only be keyed by strings and integers (or aliases of them),
+	// because most targets serialize them as JSON objects
+	fn check_map_keys(&mut self, field_type: &Type, span: &core::ops::Range<usize>) {
+		match field_type {
+			Type::List(item_type) => self.check_map_keys(item_type, span),
+			Type::Map(key_type, value_type) => {
+				if !matches!(
+					self.resolve_alias(key_type),
+					Type::String(None) | Type::Integer
+				) {
+					self.diagnostics.push(
+						Diagnostic::error("map keys must be `String` or `Int`")
+							.with_label(span.clone(), "unsupported map key type"),
+					);
+				};
+
+				self.check_map_keys(value_type, span);
+			}
+			_ => {}
+		};
+	}
+
+	// Aliases can't refer to themselves, even through lists and maps
+	fn check_alias_cycles(&mut self) {
+		let mut cyclic = Vec::<DeclarationId>::new();
+
+		for index in 0..self.aliases.len() {
+			let id = DeclarationId(index);
+
+			if self.aliases[index].is_none() || cyclic.contains(&id) {
+				continue;
+			};
+
+			let mut chain = vec![id];
+			if !self.find_alias_cycle(&mut chain) {
+				continue;
+			};
+
+			let names: Vec<String> = chain
+				.iter()
+				.map(|chain_id| self.declarations[chain_id.0].path.join("."))
+				.collect();
+
+			let mut diagnostic = Diagnostic::error(format!(
+				"alias cycle: {} -> {}",
+				names.join(" -> "),
+				names[0]
+			));
+			if let Entity::Alias(alias) = &self.declarations[index].node.entity {
+				diagnostic = diagnostic.with_label(alias.span.clone(), "alias refers to itself");
+			};
+			self.diagnostics.push(diagnostic);
+
+			cyclic.extend(chain);
+		}
+
+		// Breaking cycles
+		for id in cyclic {
+			self.aliases[id.0] = Option::None;
+		}
+	}
+
+	// Walks aliases referenced by the last one in chain, true if it leads back
+	// to the first one
+	fn find_alias_cycle(&self, chain: &mut Vec<DeclarationId>) -> bool {
+		let current = *chain.last().unwrap();
+
+		let mut referenced = Vec::new();
+		if let Some(target) = &self.aliases[current.0] {
+			collect_references(target, &mut referenced);
+		};
+
+		for id in referenced {
+			if id == chain[0] {
+				return true;
+			};
+
+			if self.aliases[id.0].is_none() || chain.contains(&id) {
+				continue;
+			};
+
+			chain.push(id);
+			if self.find_alias_cycle(chain) {
+				return true;
+			};
+			chain.pop();
+		}
+
+		false
+	}
+
+	// Parent's (already flattened) variables followed by own variables
+	fn flatten(
+		&mut self,
+		id: DeclarationId,
+		parents: &[Option<DeclarationId>],
+		own_fields: &[Vec<Field>],
+		flattened: &mut Vec<Option<Vec<Field>>>,
+		chain: &mut Vec<DeclarationId>,
+	) {
+		if flattened[id.0].is_some() {
+			return;
+		};
+
+		if chain.contains(&id) {
+			let names: Vec<String> = chain
+				.iter()
+				.skip_while(|chain_id| **chain_id != id)
+				.map(|chain_id| self.declarations[chain_id.0].path.join("."))
+				.collect();
+
+			let mut diagnostic = Diagnostic::error(format!(
+				"inheritance cycle: {} -> {}",
+				names.join(" -> "),
+				names[0]
+			));
+			if let Entity::Interface(interface) = &self.declarations[id.0].node.entity {
+				diagnostic =
+					diagnostic.with_label(interface.span.clone(), "interface extends itself");
+			};
+			self.diagnostics.push(diagnostic);
+
+			// Breaking the cycle
+			flattened[id.0] = Option::Some(own_fields[id.0].clone());
+			return;
+		};
+
+		let mut fields = Vec::new();
+
+		if let Some(parent) = parents[id.0] {
+			chain.push(id);
+			self.flatten(parent, parents, own_fields, flattened, chain);
+			chain.pop();
+
+			if flattened[id.0].is_some() {
+				// Cycle was detected and broken on this interface
+				return;
+			};
+
+			for field in flattened[parent.0].clone().unwrap_or_default() {
+				fields.push(Field {
+					inherited_from: field.inherited_from.or(Option::Some(parent)),
+					..field
+				});
+			}
+		};
+
+		fields.extend(own_fields[id.0].iter().cloned());
+		flattened[id.0] = Option::Some(fields);
+	}
+}
+
+// Article and name of a declaration kind, for diagnostics
+fn entity_kind(entity: &Entity) -> &'static str {
+	match entity {
+		Entity::Interface(_) => "an interface",
+		Entity::Enum(_) => "an enum",
+		Entity::Alias(_) => "an alias",
+		Entity::Union(_) => "a union",
+	}
+}
+
+fn collect_references(field_type: &Type, references: &mut Vec<DeclarationId>) {
+	match field_type {
+		Type::Reference(id) => references.push(*id),
+		Type::List(item_type) => collect_references(item_type, references),
+		Type::Map(key_type, value_type) => {
+			collect_references(key_type, references);
+			collect_references(value_type, references);
+		}
+		_ => {}
+	};
+}
+
+fn lower_docs(metadata: &Metadata) -> Option<String> {
+	if metadata.docs.is_empty() {
+		return Option::None;
+	};
+
+	Option::Some(metadata.docs.join("\n"))
+}
+
+fn lower_attributes(metadata: &Metadata) -> Vec<Attribute> {
+	metadata
+		.attributes
+		.iter()
+		.map(|attribute| Attribute {
+			name: attribute.name.clone(),
+			value: attribute.value.clone(),
+			span: attribute.span.clone(),
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		analyze,
+		ir::{Alias, DeclarationKind, Type, Union},
+	};
+
+	#[test]
+	fn resolves_nested_references() {
+		let schema = analyze(
+			"
+			interface User {
+				required status: Status;
+				optional previous_status: User.Status;
+
+				enum Status {
+					Registered: \"registered\";
+				};
+			};
+			",
+		)
+		.unwrap();
+
+		let user = schema.find("User").unwrap();
+		let status = schema.find("User.Status").unwrap();
+
+		assert_eq!(status.parent, Some(user.id));
+		assert_eq!(user.children, vec![status.id]);
+
+		match &user.kind {
+			DeclarationKind::Interface(interface) => {
+				assert_eq!(interface.fields[0].field_type, Type::Reference(status.id));
+				assert_eq!(interface.fields[1].field_type, Type::Reference(status.id));
+			}
+			_ => panic!("User should be an interface"),
+		};
+	}
+
+	#[test]
+	fn flattens_inheritance() {
+		let schema = analyze(
+			"
+			/// Any user
+			interface User {
+				required id: String;
+			};
+
+			#table = admins
+			interface Admin extends User {
+				required level: Int;
+			};
+			",
+		)
+		.unwrap();
+
+		let user = schema.find("User").unwrap();
+		let admin = schema.find("Admin").unwrap();
+
+		assert_eq!(user.docs, Some("Any user".to_string()));
+		assert_eq!(
+			admin.attribute("table").unwrap().value,
+			Some("admins".to_string())
+		);
+
+		match &admin.kind {
+			DeclarationKind::Interface(interface) => {
+				assert_eq!(interface.extends, Some(user.id));
+
+				let names: Vec<&str> = interface
+					.fields
+					.iter()
+					.map(|field| field.name.as_str())
+					.collect();
+				assert_eq!(names, vec!["id", "level"]);
+				assert_eq!(interface.fields[0].inherited_from, Some(user.id));
+				assert_eq!(interface.own_fields().count(), 1);
+			}
+			_ => panic!("Admin should be an interface"),
+		};
+	}
+
+	#[test]
+	fn reports_unresolved_types_and_cycles() {
+		let diagnostics = analyze(
+			"
+			interface A extends B {
+				required status: Missing;
+			};
+
+			interface B extends A {};
+			",
+		)
+		.unwrap_err();
+
+		let messages: Vec<&str> = diagnostics
+			.iter()
+			.map(|diagnostic| diagnostic.message.as_str())
+			.collect();
+		assert_eq!(
+			messages,
+			vec![
+				"unresolved type `Missing`",
+				"inheritance cycle: A -> B -> A"
+			]
+		);
+	}
+
+	#[test]
+	fn resolves_aliases() {
+		let schema = analyze(
+			"
+			alias UserId = String;
+			alias Ids = List<UserId>;
+
+			interface User {
+				#min_length = 3
+				required id: UserId;
+				optional friends: Ids;
+			};
+			",
+		)
+		.unwrap();
+
+		let user_id = schema.find("UserId").unwrap();
+		let ids = schema.find("Ids").unwrap();
+
+		assert_eq!(
+			ids.kind,
+			DeclarationKind::Alias(Alias {
+				target: Type::List(Box::new(Type::Reference(user_id.id)))
+			})
+		);
+		assert_eq!(
+			schema.resolve_type(&Type::Reference(ids.id)),
+			&Type::List(Box::new(Type::Reference(user_id.id)))
+		);
+
+		match &schema.find("User").unwrap().kind {
+			DeclarationKind::Interface(interface) => {
+				assert_eq!(interface.fields[0].field_type, Type::Reference(user_id.id));
+				assert_eq!(interface.fields[0].constraints.len(), 1);
+			}
+			_ => panic!("User should be an interface"),
+		};
+
+		let diagnostics = analyze(
+			"
+			alias A = List<B>;
+			alias B = A;
+			",
+		)
+		.unwrap_err();
+
+		assert_eq!(diagnostics[0].message, "alias cycle: A -> B -> A");
+	}
+
+	#[test]
+	fn checks_map_keys() {
+		let schema = analyze(
+			"
+			alias Key = Int;
+
+			interface Scores {
+				required by_name: Map<String, Int>;
+				required by_id: Map<Key, List<String>>;
+			};
+			",
+		)
+		.unwrap();
+
+		let key = schema.find("Key").unwrap();
+		match &schema.find("Scores").unwrap().kind {
+			DeclarationKind::Interface(interface) => assert_eq!(
+				interface.fields[1].field_type,
+				Type::Map(
+					Box::new(Type::Reference(key.id)),
+					Box::new(Type::List(Box::new(Type::String(None))))
+				)
+			),
+			_ => panic!("Scores should be an interface"),
+		};
+
+		let diagnostics = analyze(
+			"
+			interface Scores {
+				required by_flag: Map<Boolean, Int>;
+			};
+			",
+		)
+		.unwrap_err();
+
+		assert_eq!(diagnostics[0].message, "map keys must be `String` or `Int`");
+	}
+
+	#[test]
+	fn resolves_union_members() {
+		let schema = analyze(
+			"
+			interface User {};
+			interface Post {};
+			union SearchResult = User | Post;
+			",
+		)
+		.unwrap();
+
+		assert_eq!(
+			schema.find("SearchResult").unwrap().kind,
+			DeclarationKind::Union(Union {
+				members: vec![
+					schema.find("User").unwrap().id,
+					schema.find("Post").unwrap().id
+				]
+			})
+		);
+
+		let diagnostics = analyze(
+			"
+			enum Status {};
+			interface User {};
+			union Broken = User | Status | User | Missing;
+			",
+		)
+		.unwrap_err();
+
+		let messages: Vec<&str> = diagnostics
+			.iter()
+			.map(|diagnostic| diagnostic.message.as_str())
+			.collect();
+		assert_eq!(
+			messages,
+			vec![
+				"`Status` is an enum and can't be a union member",
+				"`User` is listed more than once in union `Broken`",
+				"unresolved interface `Missing`",
+			]
+		);
+	}
+
+	#[test]
+	fn decodes_string_literals() {
+		let schema = analyze(
+			r#"
+			#description = """
+				Registered user,
+				  with "quotes"
+				"""
+			interface User {
+				#example = r"^\d+$"
+				required code: String { "tab\tand \u{e9}" };
+			};
+			"#,
+		)
+		.unwrap();
+
+		let user = schema.find("User").unwrap();
+		assert_eq!(
+			user.attribute("description").unwrap().value.as_deref(),
+			Some("Registered user,\n  with \"quotes\"")
+		);
+
+		match &user.kind {
+			DeclarationKind::Interface(interface) => {
+				let code = &interface.fields[0];
+				assert_eq!(
+					code.attribute("example").unwrap().value.as_deref(),
+					Some(r"^\d+$")
+				);
+				assert_eq!(
+					code.field_type,
+					Type::String(Some("tab\tand \u{e9}".to_string()))
+				);
+			}
+			_ => panic!("User should be an interface"),
+		};
+
+		let source = "alias Id = String { \"bad \\q\" };";
+		let diagnostics = analyze(source).unwrap_err();
+
+		assert_eq!(diagnostics[0].message, "unknown escape sequence `\\q`");
+		assert_eq!(&source[diagnostics[0].labels[0].span.clone()], "\\q");
+	}
+
+	#[test]
+	fn accepts_keywords_and_raw_identifiers_as_names() {
+		let schema = analyze(
+			"
+			interface r#String {
+				required type: String;
+				required enum: r#String.interface;
+				optional String: Int;
+
+				enum interface {
+					required: required;
+					enum: Boolean;
+				};
+			};
+
+			alias union = List<r#String>;
+			",
+		)
+		.unwrap();
+
+		let declaration = schema.find("String").unwrap();
+		let kind = schema.find("String.interface").unwrap();
+		assert!(schema.find("union").is_some());
+
+		match &declaration.kind {
+			DeclarationKind::Interface(interface) => {
+				let names: Vec<&str> = interface
+					.fields
+					.iter()
+					.map(|field| field.name.as_str())
+					.collect();
+
+				assert_eq!(names, ["type", "enum", "String"]);
+				assert_eq!(interface.fields[0].field_type, Type::String(Option::None));
+				assert_eq!(interface.fields[1].field_type, Type::Reference(kind.id));
+			}
+			_ => panic!("String should be an interface"),
+		};
+
+		match &kind.kind {
+			DeclarationKind::Enum(enumerate) => {
+				assert_eq!(enumerate.variants[0].name, "required");
+				assert_eq!(enumerate.variants[1].name, "enum");
+				assert_eq!(enumerate.variants[1].value.as_deref(), Some("Boolean"));
+			}
+			_ => panic!("String.interface should be an enum"),
+		};
+	}
+
+	#[test]
+	fn reports_references_at_their_paths() {
+		let source = "
+			interface Admin extends Missing {
+				required status: List<User.Status>;
+				required scores: Map<Boolean, Int>;
+			};
+			union Anyone = Admin | Post;
+			";
+		let diagnostics = analyze(source).unwrap_err();
+
+		let labels: Vec<&str> = diagnostics
+			.iter()
+			.map(|diagnostic| &source[diagnostic.labels[0].span.clone()])
+			.collect();
+		assert_eq!(
+			labels,
+			vec!["Missing", "User.Status", "Map<Boolean, Int>", "Post"]
+		);
+	}
+
+	#[test]
+	fn reports_union_members_listed_after_their_parents() {
+		let source = "
+			interface User {};
+			interface Admin extends User {};
+			interface Owner extends Admin {};
+			union Valid = Owner | Admin | User;
+			union Invalid = User | Owner;
+			";
+		let diagnostics = analyze(source).unwrap_err();
+
+		assert_eq!(diagnostics.len(), 1);
+		assert_eq!(
+			diagnostics[0].message,
+			"`Owner` extends `User`, so it has to be listed before it in union `Invalid`"
+		);
+		assert_eq!(&source[diagnostics[0].labels[0].span.clone()], "Owner");
+	}
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use core::ops::Range;
+use parser::errors::Diagnostic;
+
+use crate::ir::{DeclarationKind, Interface, Schema};
+
+// Names which can't be used in a particular output target
+#[derive(Debug, Clone, Copy)]
+pub struct ReservedWords {
+	pub target: &'static str,
+	// Can't be used for interfaces, enums, aliases and unions
+	pub declarations: &'static [&'static str],
+	// Can't be used for variables and enum variants
+	pub members: &'static [&'static str],
+}
+
+//
+// Validation
+//
+// Declaration-level checks, which don't affect lowering itself:
+// 1. Duplicate declarations, variables and enum variants
+// 2. Identifiers starting with a digit (Text token allows them)
+// 3. Names reserved in any of enabled targets
+pub fn validate(schema: &Schema, reserved_words: &[ReservedWords]) -> Vec<Diagnostic> {
+	let mut diagnostics = Vec::new();
+
+	// Declarations with the same fully-qualified name
+	let mut declared = HashMap::<String, Range<usize>>::new();
+
+	for declaration in schema.declarations() {
+		let kind = match declaration.kind {
+			DeclarationKind::Interface(_) => "interface",
+			DeclarationKind::Enum(_) => "enum",
+			DeclarationKind::Alias(_) => "alias",
+			DeclarationKind::Union(_) => "union",
+		};
+
+		match declared.get(&declaration.qualified_name()) {
+			Some(first) => diagnostics.push(duplicate(
+				format!(
+					"`{}` is declared more than once",
+					declaration.qualified_name()
+				),
+				declaration.name_span.clone(),
+				first.clone(),
+			)),
+			None => {
+				declared.insert(declaration.qualified_name(), declaration.name_span.clone());
+			}
+		};
+
+		check_identifier(
+			&mut diagnostics,
+			kind,
+			&declaration.name,
+			&declaration.name_span,
+		);
+		for words in reserved_words.iter() {
+			check_reserved(
+				&mut diagnostics,
+				kind,
+				&declaration.name,
+				&declaration.name_span,
+				words.target,
+				words.declarations,
+			);
+		}
+
+		match &declaration.kind {
+			DeclarationKind::Interface(interface) => {
+				let mut variables = HashMap::<&str, Range<usize>>::new();
+
+				for field in interface.fields.iter() {
+					match variables.get(field.name.as_str()) {
+						Some(first) => {
+							// Inherited variables are reported only once, on the interface they're declared in
+							if field.inherited_from.is_some() {
+								continue;
+							};
+
+							let message = match field_origin(schema, interface, &field.name) {
+								Some(parent) => format!(
+									"variable `{}` of `{}` is already inherited from `{}`",
+									field.name,
+									declaration.qualified_name(),
+									parent
+								),
+								None => format!(
+									"variable `{}` is declared more than once in `{}`",
+									field.name,
+									declaration.qualified_name()
+								),
+							};
+
+							diagnostics.push(duplicate(
+								message,
+								field.name_span.clone(),
+								first.clone(),
+							));
+						}
+						None => {
+							variables.insert(&field.name, field.name_span.clone());
+						}
+					};
+
+					if field.inherited_from.is_none() {
+						check_identifier(
+							&mut diagnostics,
+							"variable",
+							&field.name,
+							&field.name_span,
+						);
+						for words in reserved_words.iter() {
+							check_reserved(
+								&mut diagnostics,
+								"variable",
+								&field.name,
+								&field.name_span,
+								words.target,
+								words.members,
+							);
+						}
+					};
+				}
+			}
+			DeclarationKind::Enum(enumerate) => {
+				let mut variants = HashMap::<&str, Range<usize>>::new();
+
+				for variant in enumerate.variants.iter() {
+					match variants.get(variant.name.as_str()) {
+						Some(first) => diagnostics.push(duplicate(
+							format!(
+								"variant `{}` is declared more than once in `{}`",
+								variant.name,
+								declaration.qualified_name()
+							),
+							variant.name_span.clone(),
+							first.clone(),
+						)),
+						None => {
+							variants.insert(&variant.name, variant.name_span.clone());
+						}
+					};
+
+					check_identifier(
+						&mut diagnostics,
+						"enum variant",
+						&variant.name,
+						&variant.name_span,
+					);
+					for words in reserved_words.iter() {
+						check_reserved(
+							&mut diagnostics,
+							"enum variant",
+							&variant.name,
+							&variant.name_span,
+							words.target,
+							words.members,
+						);
+					}
+				}
+			}
+			DeclarationKind::Alias(_) | DeclarationKind::Union(_) => {}
+		};
+	}
+
+	diagnostics
+}
+
+fn duplicate(message: String, span: Range<usize>, first: Range<usize>) -> Diagnostic {
+	Diagnostic::error(message)
+		.with_label(span, "redefined here")
+		.with_label(first, "first defined here")
+}
+
+// Name of the interface which declared inherited variable
+fn field_origin(schema: &Schema, interface: &Interface, name: &str) -> Option<String> {
+	interface
+		.fields
+		.iter()
+		.find(|field| field.name == name)
+		.and_then(|field| field.inherited_from)
+		.map(|id| schema.get(id).qualified_name())
+}
+
+fn check_identifier(
+	diagnostics: &mut Vec<Diagnostic>,
+	kind: &str,
+	name: &str,
+	span: &Range<usize>,
+) {
+	if name.starts_with(|char: char| char.is_ascii_digit()) {
+		diagnostics.push(
+			Diagnostic::error(format!("invalid {} name `{}`", kind, name))
+				.with_label(span.clone(), "identifiers can't start with a digit"),
+		);
+	};
+}
+
+fn check_reserved(
+	diagnostics: &mut Vec<Diagnostic>,
+	kind: &str,
+	name: &str,
+	span: &Range<usize>,
+	target: &str,
+	words: &[&str],
+) {
+	if words.contains(&name) {
+		diagnostics.push(
+			Diagnostic::error(format!(
+				"{} name `{}` is reserved in {}",
+				kind, name, target
+			))
+			.with_label(
+				span.clone(),
+				format!("`{}` is a reserved word in {}", name, target),
+			),
+		);
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::analyze;
+
+	use super::*;
+
+	fn messages(source: &str, reserved_words: &[ReservedWords]) -> Vec<String> {
+		let schema = analyze(source).unwrap();
+
+		validate(&schema, reserved_words)
+			.into_iter()
+			.map(|diagnostic| diagnostic.message)
+			.collect()
+	}
+
+	#[test]
+	fn reports_duplicates() {
+		let messages = messages(
+			"
+			interface User {
+				required id: String;
+				required id: Int;
+			};
+
+			interface Admin extends User {
+				required id: String;
+			};
+
+			enum Status {
+				Active: active;
+				Active: enabled;
+			};
+
+			enum Status {
+				Banned: banned;
+			};
+			",
+			&[],
+		);
+
+		assert_eq!(
+			messages,
+			vec![
+				"variable `id` is declared more than once in `User`",
+				"variable `id` of `Admin` is already inherited from `User`",
+				"variant `Active` is declared more than once in `Status`",
+				"`Status` is declared more than once",
+			]
+		);
+	}
+
+	#[test]
+	fn reports_invalid_and_reserved_names() {
+		let reserved = ReservedWords {
+			target: "TypeScript",
+			declarations: &["string"],
+			members: &["delete"],
+		};
+
+		let messages = messages(
+			"
+			interface 123 {
+				required 2: String;
+				required delete: String;
+			};
+
+			interface string {};
+			",
+			&[reserved],
+		);
+
+		assert_eq!(
+			messages,
+			vec![
+				"invalid interface name `123`",
+				"invalid variable name `2`",
+				"variable name `delete` is reserved in TypeScript",
+				"interface name `string` is reserved in TypeScript",
+			]
+		);
+	}
+
+	#[test]
+	fn points_at_names() {
+		let source = "
+			interface User {
+				required id: String;
+				optional id: Int;
+			};
+			";
+		let schema = analyze(source).unwrap();
+		let diagnostics = validate(&schema, &[]);
+
+		let labels: Vec<(&str, usize)> = diagnostics[0]
+			.labels
+			.iter()
+			.map(|label| (&source[label.span.clone()], label.span.start))
+			.collect();
+		assert_eq!(
+			labels,
+			vec![
+				("id", source.rfind("id").unwrap()),
+				("id", source.find("id").unwrap())
+			]
+		);
+	}
+}
@@ -0,0 +1,428 @@
+use core::ops::Range;
+use std::collections::BTreeMap;
+
+use lexer::{tokenize, tokens::TokenType};
+use parser::{
+	errors::Diagnostic,
+	get_ast_tree,
+	syntax::{parse, SyntaxNode},
+};
+
+use crate::{
+	index::{find_symbol, symbol_name, Index, Occurrence, Symbol},
+	ir::Schema,
+	lower, validate,
+};
+
+//
+// Analyzed .typm file
+//
+// Syntax tree is always available, schema and references index only
+// when the file has no syntax or semantic errors.
+#[derive(Debug)]
+pub struct WorkspaceFile {
+	pub source: String,
+	pub syntax: SyntaxNode,
+	pub schema: Option<Schema>,
+	pub index: Option<Index>,
+	pub diagnostics: Vec<Diagnostic>,
+}
+
+impl WorkspaceFile {
+	pub fn new(source: String) -> WorkspaceFile {
+		let parse = parse(&source);
+
+		let mut diagnostics = parse.diagnostics.clone();
+		let mut schema = Option::None;
+
+		// Syntax tree is parsed once and lowered as well
+		if diagnostics.is_empty() {
+			match lower(&get_ast_tree(&parse)) {
+				Ok(analyzed) => {
+					diagnostics.extend(validate(&analyzed, &[]));
+					schema = Option::Some(analyzed);
+				}
+				Err(errors) => diagnostics.extend(errors),
+			};
+		};
+
+		let index = schema
+			.as_ref()
+			.map(|schema| Index::new(&parse.root, schema));
+
+		WorkspaceFile {
+			source,
+			syntax: parse.root,
+			schema,
+			index,
+			diagnostics,
+		}
+	}
+
+	pub fn has_errors(&self) -> bool {
+		self.diagnostics
+			.iter()
+			.any(|diagnostic| diagnostic.is_error())
+	}
+
+	// Symbol named at the offset, with it's qualified name
+	pub fn symbol_at(&self, offset: usize) -> Option<(&Occurrence, String)> {
+		let occurrence = self.index.as_ref()?.at(offset)?;
+		let name = symbol_name(self.schema.as_ref()?, &occurrence.symbol);
+
+		Option::Some((occurrence, name))
+	}
+
+	// Definition and references of a symbol by it's qualified name (`User.Status`)
+	pub fn references(&self, qualified_name: &str) -> Vec<&Occurrence> {
+		let (Some(schema), Some(index)) = (&self.schema, &self.index) else {
+			return Vec::new();
+		};
+
+		match find_symbol(schema, qualified_name) {
+			Some(symbol) => index.references(&symbol).collect(),
+			None => Vec::new(),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+	pub span: Range<usize>,
+	pub text: String,
+}
+
+//
+// Workspace
+//
+// Set of loaded files, keyed by path or URI. Files don't import each
+// other, so a symbol is identified by it's qualified name: `User.Status`
+// in every file declaring it is treated as the same type (all of them
+// end up in the same generated code).
+#[derive(Debug, Default)]
+pub struct Workspace {
+	files: BTreeMap<String, WorkspaceFile>,
+}
+
+impl Workspace {
+	pub fn insert(&mut self, name: impl Into<String>, source: String) -> &WorkspaceFile {
+		let name = name.into();
+		self.files.insert(name.clone(), WorkspaceFile::new(source));
+		&self.files[&name]
+	}
+
+	pub fn remove(&mut self, name: &str) {
+		self.files.remove(name);
+	}
+
+	pub fn get(&self, name: &str) -> Option<&WorkspaceFile> {
+		self.files.get(name)
+	}
+
+	pub fn files(&self) -> impl Iterator<Item = (&String, &WorkspaceFile)> {
+		self.files.iter()
+	}
+
+	// Definitions and references in every file, ordered by file name
+	pub fn references(&self, qualified_name: &str) -> Vec<(&str, &Occurrence)> {
+		self.files
+			.iter()
+			.flat_map(|(name, file)| {
+				file.references(qualified_name)
+					.into_iter()
+					.map(move |occurrence| (name.as_str(), occurrence))
+			})
+			.collect()
+	}
+
+	//
+	// Edits renaming a symbol in every file
+	//
+	// The new name replaces the definition and every path segment naming
+	// the symbol, so `User.Status` becomes `User.AccountStatus` and renaming
+	// `User` changes the first segment of it. Attribute values and comments
+	// are left alone, they never refer to symbols. Rename is refused if any file
+	// with the symbol has errors, or if renamed files would resolve
+	// differently (for example, when the new name is already taken).
+	pub fn rename(
+		&self,
+		qualified_name: &str,
+		new_name: &str,
+	) -> Result<BTreeMap<String, Vec<TextEdit>>, String> {
+		if !is_identifier(new_name) {
+			return Err(format!("`{}` is not a valid name", new_name));
+		};
+
+		let mut edits = BTreeMap::new();
+
+		for (name, file) in self.files.iter() {
+			if file.schema.is_none() {
+				if file.has_errors() && mentions(file, qualified_name) {
+					return Err(format!("{} has errors, fix them before renaming", name));
+				};
+
+				continue;
+			};
+
+			let occurrences = file.references(qualified_name);
+			if occurrences.is_empty() {
+				continue;
+			};
+
+			let file_edits: Vec<TextEdit> = occurrences
+				.iter()
+				.map(|occurrence| TextEdit {
+					span: occurrence.span.clone(),
+					text: new_name.to_string(),
+				})
+				.collect();
+
+			check_rename(
+				file,
+				&occurrences,
+				&file_edits,
+				name,
+				qualified_name,
+				new_name,
+			)?;
+			edits.insert(name.clone(), file_edits);
+		}
+
+		if edits.is_empty() {
+			return Err(format!(
+				"no declaration, variable or variant named `{}`",
+				qualified_name
+			));
+		};
+
+		Ok(edits)
+	}
+}
+
+pub fn apply_edits(source: &str, edits: &[TextEdit]) -> String {
+	let mut edits: Vec<&TextEdit> = edits.iter().collect();
+	edits.sort_by_key(|edit| edit.span.start);
+
+	let mut result = String::new();
+	let mut position = 0;
+
+	for edit in edits {
+		result.push_str(&source[position..edit.span.start]);
+		result.push_str(&edit.text);
+		position = edit.span.end;
+	}
+
+	result.push_str(&source[position..]);
+	result
+}
+
+// Same token as a name in the source: a plain identifier, not a keyword
+fn is_identifier(name: &str) -> bool {
+	let mut tokens = tokenize(name);
+
+	match (tokens.next(), tokens.next()) {
+		(Some(token), None) => token.token_type == TokenType::Text,
+		_ => false,
+	}
+}
+
+// File with errors can't be resolved, so it's only checked textually
+fn mentions(file: &WorkspaceFile, qualified_name: &str) -> bool {
+	let name = qualified_name.rsplit('.').next().unwrap_or(qualified_name);
+
+	file.syntax
+		.descendant_tokens()
+		.iter()
+		.any(|token| token.kind == TokenType::Text && token.name() == name)
+}
+
+// Renamed file has to be valid and the symbol has to keep all of it's references
+fn check_rename(
+	file: &WorkspaceFile,
+	occurrences: &[&Occurrence],
+	edits: &[TextEdit],
+	file_name: &str,
+	qualified_name: &str,
+	new_name: &str,
+) -> Result<(), String> {
+	let renamed = WorkspaceFile::new(apply_edits(&file.source, edits));
+	let conflict = || {
+		format!(
+			"renaming `{}` to `{}` would change the meaning of {}",
+			qualified_name, new_name, file_name
+		)
+	};
+
+	if renamed.has_errors() {
+		return Err(conflict());
+	};
+
+	// Spans move by the same amount for every renamed name before them
+	let shift = new_name.len() as isize;
+	let expected: Vec<Range<usize>> = occurrences
+		.iter()
+		.enumerate()
+		.map(|(position, occurrence)| {
+			let moved: isize = occurrences[..position]
+				.iter()
+				.map(|previous| shift - previous.span.len() as isize)
+				.sum();
+			let start = (occurrence.span.start as isize + moved) as usize;
+
+			start..start + new_name.len()
+		})
+		.collect();
+
+	let definition = occurrences
+		.iter()
+		.position(|occurrence| occurrence.is_definition)
+		.ok_or_else(conflict)?;
+
+	let symbol: &Symbol = &renamed
+		.index
+		.as_ref()
+		.and_then(|index| index.at(expected[definition].start))
+		.ok_or_else(conflict)?
+		.symbol;
+
+	let actual: Vec<Range<usize>> = renamed
+		.index
+		.as_ref()
+		.unwrap()
+		.references(symbol)
+		.map(|occurrence| occurrence.span.clone())
+		.collect();
+
+	if actual != expected {
+		return Err(conflict());
+	};
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SOURCE: &str = "
+interface User {
+	required status: User.Status;
+	optional friends: List<User>;
+
+	enum Status {
+		ACTIVE: active;
+	};
+};
+
+interface Admin extends User {
+	required previous: Status;
+};
+
+enum Status {
+	OTHER: other;
+};
+
+union Anyone = Admin | User;
+";
+
+	fn renamed(
+		workspace: &Workspace,
+		file: &str,
+		edits: &BTreeMap<String, Vec<TextEdit>>,
+	) -> String {
+		apply_edits(&workspace.get(file).unwrap().source, &edits[file])
+	}
+
+	#[test]
+	fn finds_references() {
+		let mut workspace = Workspace::default();
+		workspace.insert("schema.typm", SOURCE.to_string());
+
+		let spans = |name: &str| -> Vec<&str> {
+			workspace
+				.references(name)
+				.iter()
+				.map(|(_, occurrence)| &SOURCE[occurrence.span.clone()])
+				.collect()
+		};
+
+		// Top-level `Status` in Admin is a different enum
+		assert_eq!(spans("User.Status"), vec!["Status", "Status"]);
+		assert_eq!(spans("Status").len(), 2);
+		assert_eq!(spans("User").len(), 5);
+		assert_eq!(spans("User.friends"), vec!["friends"]);
+
+		let file = workspace.get("schema.typm").unwrap();
+		let offset = SOURCE.find("User.Status").unwrap();
+		assert_eq!(file.symbol_at(offset).unwrap().1, "User");
+		assert_eq!(file.symbol_at(offset + 5).unwrap().1, "User.Status");
+	}
+
+	#[test]
+	fn renames_across_files() {
+		let mut workspace = Workspace::default();
+		workspace.insert("a.typm", SOURCE.to_string());
+		workspace.insert(
+			"b.typm",
+			"interface User {\n\tenum Status {\n\t\tA: a;\n\t};\n};\nalias S = User.Status;\n"
+				.to_string(),
+		);
+		workspace.insert("c.typm", "alias Unrelated = String;\n".to_string());
+
+		let edits = workspace.rename("User.Status", "AccountStatus").unwrap();
+
+		assert_eq!(edits.len(), 2);
+		assert!(renamed(&workspace, "a.typm", &edits)
+			.contains("required status: User.AccountStatus;\n\toptional"));
+		assert!(renamed(&workspace, "a.typm", &edits).contains("required previous: Status;"));
+		assert_eq!(
+			renamed(&workspace, "b.typm", &edits),
+			"interface User {\n\tenum AccountStatus {\n\t\tA: a;\n\t};\n};\nalias S = User.AccountStatus;\n"
+		);
+	}
+
+	#[test]
+	fn labels_every_error() {
+		let sources = [
+			"#min = 3\nrequired id: String;\n",
+			"interface User {\n\trequired id: Missing;\n};\n",
+			"interface User {\n\trequired id String;\n",
+		];
+
+		for source in sources {
+			let file = WorkspaceFile::new(source.to_string());
+
+			assert!(file.has_errors());
+			assert!(file
+				.diagnostics
+				.iter()
+				.all(|diagnostic| !diagnostic.labels.is_empty()));
+		}
+	}
+
+	#[test]
+	fn keeps_attribute_values() {
+		let mut workspace = Workspace::default();
+		let source = "#description = \"User\"\ninterface User {\n\t#description = User\n\trequired id: String;\n};\nalias Users = List<User>;\n";
+		workspace.insert("schema.typm", source.to_string());
+
+		let edits = workspace.rename("User", "Account").unwrap();
+
+		assert_eq!(
+			renamed(&workspace, "schema.typm", &edits),
+			"#description = \"User\"\ninterface Account {\n\t#description = User\n\trequired id: String;\n};\nalias Users = List<Account>;\n"
+		);
+	}
+
+	#[test]
+	fn refuses_conflicting_renames() {
+		let mut workspace = Workspace::default();
+		workspace.insert("schema.typm", SOURCE.to_string());
+
+		// Nested enum would shadow the top-level one inside of User
+		assert!(workspace.rename("Admin", "User").is_err());
+		assert!(workspace.rename("User.Status", "interface").is_err());
+		assert!(workspace.rename("User.Status", "Account.Status").is_err());
+		assert!(workspace.rename("Missing", "Other").is_err());
+	}
+}